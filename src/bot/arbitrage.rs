@@ -0,0 +1,285 @@
+//! Model-free arbitrage across mutually exclusive winner markets.
+//!
+//! Polymarket often lists separate home-win, away-win and draw markets for
+//! one fixture.  Exactly one of them resolves YES, so a basket holding one
+//! YES share of each pays exactly $1 and a basket holding one NO share of
+//! each pays exactly `n - 1`.  When the asks sum below one dollar (or the
+//! bids above it) after fees, buying the basket locks in the difference
+//! regardless of the result.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::db::models::Market;
+
+use super::hedging::sport_has_draws;
+use super::strategy::BotEngine;
+
+/// What the scanner does with an opportunity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArbitrageMode {
+    Off,
+    /// Log opportunities only.
+    Report,
+    /// Buy the basket.
+    Execute,
+}
+
+impl FromStr for ArbitrageMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "off" => Ok(ArbitrageMode::Off),
+            "report" => Ok(ArbitrageMode::Report),
+            "execute" => Ok(ArbitrageMode::Execute),
+            other => anyhow::bail!(
+                "unknown arbitrage mode '{}' (expected off, report or execute)",
+                other
+            ),
+        }
+    }
+}
+
+/// Which result a winner market pays out on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Leg {
+    Home,
+    Away,
+    Draw,
+}
+
+/// Best YES bid/ask for one leg of a basket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegQuote {
+    pub market_id: String,
+    pub leg: Leg,
+    pub best_bid: f64,
+    pub best_ask: f64,
+}
+
+/// Which token the basket buys on every leg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BasketSide {
+    /// Asks sum below 1: buy YES on every leg, one leg pays $1.
+    BuyYes,
+    /// Bids sum above 1: buy NO on every leg, all but one pay $1.
+    BuyNo,
+}
+
+impl BasketSide {
+    /// Outcome token bought on each leg.
+    pub fn outcome(&self) -> &'static str {
+        match self {
+            BasketSide::BuyYes => "YES",
+            BasketSide::BuyNo => "NO",
+        }
+    }
+}
+
+/// A mispriced basket of mutually exclusive markets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitrageOpportunity {
+    pub event_name: String,
+    pub side: BasketSide,
+    /// `(market_id, price paid per share)` for each leg.
+    pub legs: Vec<(String, f64)>,
+    /// Cost of one share of every leg, fees included.
+    pub cost_per_set: f64,
+    /// Guaranteed profit per set after fees.
+    pub edge_per_set: f64,
+}
+
+/// Home and away team from a Polymarket event title ("Arsenal vs. Chelsea").
+pub fn event_teams(event_name: &str) -> Option<(&str, &str)> {
+    let (home, away) = event_name
+        .split_once(" vs. ")
+        .or_else(|| event_name.split_once(" vs "))?;
+    let (home, away) = (home.trim(), away.trim());
+    (!home.is_empty() && !away.is_empty()).then_some((home, away))
+}
+
+/// Classify a market as the home-win, away-win or draw leg of its event.
+pub fn classify_market(market: &Market, home: &str, away: &str) -> Option<Leg> {
+    let question = market.question.to_lowercase();
+    if question.contains("draw") && !question.contains("draw no bet") {
+        return Some(Leg::Draw);
+    }
+    match BotEngine::infer_yes_is_home(market, home, away)? {
+        true => Some(Leg::Home),
+        false => Some(Leg::Away),
+    }
+}
+
+/// Group markets into complete sets of mutually exclusive legs per event.
+///
+/// A set needs exactly one home and one away market, plus a draw market when
+/// the sport can end level.  Events with a duplicate or missing leg are
+/// skipped rather than guessed at.
+pub fn group_exclusive_markets(markets: &[Market]) -> Vec<(String, Vec<(Market, Leg)>)> {
+    let mut by_event: HashMap<&str, Vec<&Market>> = HashMap::new();
+    for market in markets.iter().filter(|m| m.status == "active") {
+        if let Some(event_name) = market.event_name.as_deref() {
+            by_event.entry(event_name).or_default().push(market);
+        }
+    }
+
+    let mut sets = Vec::new();
+    for (event_name, markets) in by_event {
+        let Some((home, away)) = event_teams(event_name) else {
+            continue;
+        };
+        let mut legs: HashMap<Leg, &Market> = HashMap::new();
+        let mut duplicate = false;
+        for market in markets {
+            if let Some(leg) = classify_market(market, home, away) {
+                duplicate |= legs.insert(leg, market).is_some();
+            }
+        }
+        let has_draws = legs.contains_key(&Leg::Draw)
+            || legs
+                .values()
+                .any(|m| m.sport.as_deref().is_some_and(sport_has_draws));
+        let needed = if has_draws { 3 } else { 2 };
+        let sport_known = has_draws || legs.values().any(|m| m.sport.is_some());
+        if duplicate
+            || !sport_known
+            || legs.len() != needed
+            || !legs.contains_key(&Leg::Home)
+            || !legs.contains_key(&Leg::Away)
+        {
+            continue;
+        }
+        let mut set: Vec<(Market, Leg)> = legs
+            .into_iter()
+            .map(|(leg, market)| (market.clone(), leg))
+            .collect();
+        set.sort_by_key(|(_, leg)| *leg as u8);
+        sets.push((event_name.to_string(), set));
+    }
+    sets
+}
+
+/// Check a complete set of quotes for a basket that pays more than it costs
+/// after `one_way_fee` (fee + slippage fraction per buy) and `min_edge`.
+pub fn find_opportunity(
+    event_name: &str,
+    quotes: &[LegQuote],
+    one_way_fee: f64,
+    min_edge: f64,
+) -> Option<ArbitrageOpportunity> {
+    if quotes.len() < 2
+        || quotes
+            .iter()
+            .any(|q| !(q.best_bid > 0.0 && q.best_ask < 1.0 && q.best_bid <= q.best_ask))
+    {
+        return None;
+    }
+
+    let yes_legs: Vec<(String, f64)> = quotes
+        .iter()
+        .map(|q| (q.market_id.clone(), q.best_ask))
+        .collect();
+    let yes_cost = yes_legs.iter().map(|(_, p)| p).sum::<f64>() * (1.0 + one_way_fee);
+    let yes_edge = 1.0 - yes_cost;
+
+    // Buying NO at 1 - bid is the same as selling YES at the bid.
+    let no_legs: Vec<(String, f64)> = quotes
+        .iter()
+        .map(|q| (q.market_id.clone(), 1.0 - q.best_bid))
+        .collect();
+    let no_cost = no_legs.iter().map(|(_, p)| p).sum::<f64>() * (1.0 + one_way_fee);
+    let no_edge = (quotes.len() - 1) as f64 - no_cost;
+
+    let (side, legs, cost_per_set, edge_per_set) = if yes_edge >= no_edge {
+        (BasketSide::BuyYes, yes_legs, yes_cost, yes_edge)
+    } else {
+        (BasketSide::BuyNo, no_legs, no_cost, no_edge)
+    };
+    if edge_per_set <= min_edge {
+        return None;
+    }
+    Some(ArbitrageOpportunity {
+        event_name: event_name.to_string(),
+        side,
+        legs,
+        cost_per_set,
+        edge_per_set,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn market(id: &str, question: &str, sport: &str) -> Market {
+        Market {
+            id: id.into(),
+            question: question.into(),
+            sport: Some(sport.into()),
+            league: None,
+            event_name: Some("Arsenal vs. Chelsea".into()),
+            yes_price: None,
+            no_price: None,
+            volume: None,
+            status: "active".into(),
+            fetched_at: Utc::now(),
+            slug: None,
+            end_date: None,
+            liquidity: None,
+        }
+    }
+
+    fn quote(id: &str, leg: Leg, bid: f64, ask: f64) -> LegQuote {
+        LegQuote {
+            market_id: id.into(),
+            leg,
+            best_bid: bid,
+            best_ask: ask,
+        }
+    }
+
+    #[test]
+    fn groups_complete_three_way_sets_only() {
+        let markets = vec![
+            market("h", "Will Arsenal win?", "soccer"),
+            market("a", "Will Chelsea win?", "soccer"),
+            market("d", "Will Arsenal vs. Chelsea end in a draw?", "soccer"),
+            market("t", "Over 2.5 goals in Arsenal vs. Chelsea?", "soccer"),
+        ];
+        let sets = group_exclusive_markets(&markets);
+        assert_eq!(sets.len(), 1);
+        let legs: Vec<Leg> = sets[0].1.iter().map(|(_, leg)| *leg).collect();
+        assert_eq!(legs, vec![Leg::Home, Leg::Away, Leg::Draw]);
+
+        // Without the draw market a soccer set is incomplete.
+        assert!(group_exclusive_markets(&markets[..2]).is_empty());
+    }
+
+    #[test]
+    fn detects_cheap_yes_basket_after_fees() {
+        let quotes = vec![
+            quote("h", Leg::Home, 0.40, 0.42),
+            quote("a", Leg::Away, 0.28, 0.30),
+            quote("d", Leg::Draw, 0.22, 0.24),
+        ];
+        let opp = find_opportunity("Arsenal vs. Chelsea", &quotes, 0.01, 0.0).unwrap();
+        assert_eq!(opp.side, BasketSide::BuyYes);
+        assert!((opp.edge_per_set - (1.0 - 0.96 * 1.01)).abs() < 1e-9);
+
+        // Fees eat a thin edge.
+        assert!(find_opportunity("Arsenal vs. Chelsea", &quotes, 0.05, 0.0).is_none());
+    }
+
+    #[test]
+    fn detects_rich_bids_as_no_basket() {
+        let quotes = vec![
+            quote("h", Leg::Home, 0.60, 0.61),
+            quote("a", Leg::Away, 0.45, 0.46),
+        ];
+        let opp = find_opportunity("Lakers vs Celtics", &quotes, 0.0, 0.0).unwrap();
+        assert_eq!(opp.side, BasketSide::BuyNo);
+        assert!((opp.edge_per_set - 0.05).abs() < 1e-9);
+    }
+}
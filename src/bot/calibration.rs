@@ -1,15 +1,134 @@
-/// Probability calibration utilities (Platt scaling).
+/// Probability calibration utilities.
 ///
-/// The model is `p_calibrated = sigmoid(a * logit(p_raw) + b)`.
+/// Three calibrators share the [`Calibrator`] interface:
+/// - Platt scaling, `p_calibrated = sigmoid(a * logit(p_raw) + b)`;
+/// - isotonic regression (pool-adjacent-violators), which can fix
+///   non-monotone-in-logit distortions Platt cannot;
+/// - histogram binning with shrinkage towards the bin centre.
+///
+/// The model used for a level is chosen by cross-validated log loss.
+/// Levels nest sport → league → game phase; see [`level_keys`].
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
 const EPS: f64 = 1e-6;
 
-#[derive(Debug, Clone, Copy)]
+/// Pseudo-count pulling each histogram bin towards its centre.
+const HISTOGRAM_PRIOR_WEIGHT: f64 = 2.0;
+
+/// Coarse stage of a game, for phase-level calibration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamePhase {
+    Early,
+    Mid,
+    Late,
+}
+
+impl GamePhase {
+    /// Phase for a fraction of regulation time elapsed (thirds).
+    pub fn from_progress(progress: f64) -> Self {
+        if progress < 1.0 / 3.0 {
+            GamePhase::Early
+        } else if progress < 2.0 / 3.0 {
+            GamePhase::Mid
+        } else {
+            GamePhase::Late
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GamePhase::Early => "early",
+            GamePhase::Mid => "mid",
+            GamePhase::Late => "late",
+        }
+    }
+}
+
+/// Calibration keys from the sport level down, e.g. `soccer`,
+/// `soccer/premier league`, `soccer/premier league/late`.  A phase level
+/// only exists below a known league.
+pub fn level_keys(sport: &str, league: Option<&str>, phase: Option<GamePhase>) -> Vec<String> {
+    let sport = sport.trim().to_lowercase();
+    let mut keys = vec![sport.clone()];
+    if let Some(league) = league
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+    {
+        let league_key = format!("{}/{}", sport, league);
+        if let Some(phase) = phase {
+            keys.push(format!("{}/{}", league_key, phase.as_str()));
+        }
+        keys.insert(1, league_key);
+    }
+    keys
+}
+
+/// A fitted model for one level and the samples it was fitted on.
+#[derive(Debug, Clone)]
+pub struct LevelCalibration {
+    pub model: CalibrationModel,
+    pub samples: usize,
+}
+
+/// Content version of a set of level calibrations, `cal-<12 hex>` over its
+/// models in key order, together with the JSON it was computed from so the
+/// set can be archived.  `None` for an empty set.
+pub fn calibration_set_version(
+    levels: &HashMap<String, LevelCalibration>,
+) -> serde_json::Result<Option<(String, String)>> {
+    if levels.is_empty() {
+        return Ok(None);
+    }
+    let ordered: BTreeMap<&str, &CalibrationModel> =
+        levels.iter().map(|(k, l)| (k.as_str(), &l.model)).collect();
+    let json = serde_json::to_string(&ordered)?;
+    let digest = format!("{:x}", Sha256::digest(json.as_bytes()));
+    Ok(Some((format!("cal-{}", &digest[..12]), json)))
+}
+
+/// Calibrate through a chain of levels, most general first.
+///
+/// The sport level applies in full.  Each finer level is blended with its
+/// parent's output by `n / (n + shrinkage_samples)`, so a league or phase
+/// fitted on few games only nudges the sport-level answer.  Missing levels
+/// pass the parent through.
+pub fn apply_hierarchical(
+    levels: &[Option<&LevelCalibration>],
+    raw_prob: f64,
+    shrinkage_samples: f64,
+) -> f64 {
+    let mut p = raw_prob.clamp(0.0, 1.0);
+    for (depth, level) in levels.iter().enumerate() {
+        let Some(level) = level else {
+            continue;
+        };
+        let child = level.model.apply(raw_prob);
+        let n = level.samples as f64;
+        let weight = if depth == 0 {
+            1.0
+        } else {
+            n / (n + shrinkage_samples.max(0.0)).max(1.0)
+        };
+        p = weight * child + (1.0 - weight) * p;
+    }
+    p.clamp(0.0, 1.0)
+}
+
+/// Maps a raw model probability to a calibrated one.
+pub trait Calibrator {
+    fn apply(&self, raw_prob: f64) -> f64;
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PlattCalibration {
     pub a: f64,
     pub b: f64,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FitMetrics {
     pub logloss_before: f64,
     pub logloss_after: f64,
@@ -20,6 +139,8 @@ pub struct FitMetrics {
 #[derive(Debug, Clone, Copy)]
 pub struct FitResult {
     pub calibration: PlattCalibration,
+    /// In-sample metrics; model selection uses cross-validated ones.
+    #[allow(dead_code)]
     pub metrics: FitMetrics,
 }
 
@@ -52,6 +173,291 @@ pub fn apply_platt(raw_prob: f64, model: PlattCalibration) -> f64 {
     sigmoid(model.a * x + model.b).clamp(0.0, 1.0)
 }
 
+impl Calibrator for PlattCalibration {
+    fn apply(&self, raw_prob: f64) -> f64 {
+        apply_platt(raw_prob, *self)
+    }
+}
+
+/// Monotone step function fitted by pool-adjacent-violators, interpolated
+/// linearly between block centres.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsotonicCalibration {
+    /// Mean raw probability of each pooled block (increasing)
+    pub x: Vec<f64>,
+    /// Observed win rate of each block (non-decreasing)
+    pub y: Vec<f64>,
+}
+
+impl Calibrator for IsotonicCalibration {
+    fn apply(&self, raw_prob: f64) -> f64 {
+        let (Some(&first_x), Some(&last_x)) = (self.x.first(), self.x.last()) else {
+            return raw_prob.clamp(0.0, 1.0);
+        };
+        if raw_prob <= first_x {
+            return self.y[0];
+        }
+        if raw_prob >= last_x {
+            return self.y[self.y.len() - 1];
+        }
+        let i = self.x.partition_point(|&x| x <= raw_prob);
+        let (x0, x1) = (self.x[i - 1], self.x[i]);
+        let (y0, y1) = (self.y[i - 1], self.y[i]);
+        let t = if x1 > x0 {
+            (raw_prob - x0) / (x1 - x0)
+        } else {
+            0.0
+        };
+        (y0 + t * (y1 - y0)).clamp(0.0, 1.0)
+    }
+}
+
+/// Equal-width reliability bins on [0, 1].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramCalibration {
+    /// Calibrated probability per bin
+    pub values: Vec<f64>,
+}
+
+impl Calibrator for HistogramCalibration {
+    fn apply(&self, raw_prob: f64) -> f64 {
+        if self.values.is_empty() {
+            return raw_prob.clamp(0.0, 1.0);
+        }
+        self.values[bin_index(raw_prob, self.values.len())]
+    }
+}
+
+/// A fitted calibrator of any kind, as stored in `model_calibrations`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CalibrationModel {
+    Platt(PlattCalibration),
+    Isotonic(IsotonicCalibration),
+    Histogram(HistogramCalibration),
+}
+
+impl CalibrationModel {
+    /// Kind name stored in the `model_kind` column.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CalibrationModel::Platt(_) => "platt",
+            CalibrationModel::Isotonic(_) => "isotonic",
+            CalibrationModel::Histogram(_) => "histogram",
+        }
+    }
+}
+
+impl Calibrator for CalibrationModel {
+    fn apply(&self, raw_prob: f64) -> f64 {
+        match self {
+            CalibrationModel::Platt(m) => m.apply(raw_prob),
+            CalibrationModel::Isotonic(m) => m.apply(raw_prob),
+            CalibrationModel::Histogram(m) => m.apply(raw_prob),
+        }
+    }
+}
+
+/// One bin of a reliability diagram.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReliabilityBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    /// Mean calibrated probability of samples in the bin
+    pub mean_predicted: f64,
+    /// Observed win rate of samples in the bin
+    pub observed_rate: f64,
+}
+
+/// Fitting and model-selection tunables (see the `calibration_*` config options).
+#[derive(Debug, Clone, Copy)]
+pub struct CalibrationOptions {
+    pub max_iters: usize,
+    pub learning_rate: f64,
+    pub l2: f64,
+    pub cv_folds: usize,
+    pub histogram_bins: usize,
+}
+
+/// The calibrator chosen for a sample set.
+#[derive(Debug, Clone)]
+pub struct Selection {
+    pub model: CalibrationModel,
+    /// Raw vs out-of-fold metrics of the chosen kind
+    pub metrics: FitMetrics,
+    /// In-sample reliability diagram of the refitted model
+    pub reliability: Vec<ReliabilityBin>,
+}
+
+fn bin_index(p: f64, bins: usize) -> usize {
+    ((p.clamp(0.0, 1.0) * bins as f64) as usize).min(bins - 1)
+}
+
+/// Both classes present and enough rows to fit anything meaningful.
+fn fittable(samples: &[(f64, f64)]) -> bool {
+    let positives = samples.iter().filter(|(_, y)| *y > 0.5).count();
+    samples.len() >= 8 && positives > 0 && positives < samples.len()
+}
+
+/// Mean log loss and Brier score of `predict` over `samples`.
+pub fn score<F: Fn(f64) -> f64>(samples: &[(f64, f64)], predict: F) -> (f64, f64) {
+    let n = samples.len().max(1) as f64;
+    let (mut ll, mut br) = (0.0, 0.0);
+    for (raw_p, y) in samples {
+        let p = predict(*raw_p);
+        ll += logloss(p, *y);
+        br += (p - *y).powi(2);
+    }
+    (ll / n, br / n)
+}
+
+pub fn fit_isotonic(samples: &[(f64, f64)]) -> Option<IsotonicCalibration> {
+    if !fittable(samples) {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    // (sum of raw p, sum of labels, count) per pooled block
+    let mut blocks: Vec<(f64, f64, f64)> = Vec::new();
+    for (p, y) in sorted {
+        blocks.push((p, y, 1.0));
+        while blocks.len() >= 2 {
+            let (p1, y1, n1) = blocks[blocks.len() - 1];
+            let (p0, y0, n0) = blocks[blocks.len() - 2];
+            if y0 / n0 < y1 / n1 {
+                break;
+            }
+            blocks.pop();
+            let last = blocks.len() - 1;
+            blocks[last] = (p0 + p1, y0 + y1, n0 + n1);
+        }
+    }
+    // Small edge blocks are often pure wins or losses; a half-count prior
+    // keeps them off 0/1, and the running max restores monotonicity.
+    let mut floor = 0.0f64;
+    let y = blocks
+        .iter()
+        .map(|(_, y, n)| {
+            floor = floor.max((y + 0.5) / (n + 1.0));
+            floor
+        })
+        .collect();
+    Some(IsotonicCalibration {
+        x: blocks.iter().map(|(p, _, n)| p / n).collect(),
+        y,
+    })
+}
+
+pub fn fit_histogram(samples: &[(f64, f64)], bins: usize) -> Option<HistogramCalibration> {
+    if !fittable(samples) || bins == 0 {
+        return None;
+    }
+    let mut counts = vec![(0.0f64, 0.0f64); bins];
+    for (p, y) in samples {
+        let bin = &mut counts[bin_index(*p, bins)];
+        bin.0 += y;
+        bin.1 += 1.0;
+    }
+    let values = counts
+        .iter()
+        .enumerate()
+        .map(|(i, (positives, n))| {
+            let centre = (i as f64 + 0.5) / bins as f64;
+            (positives + HISTOGRAM_PRIOR_WEIGHT * centre) / (n + HISTOGRAM_PRIOR_WEIGHT)
+        })
+        .collect();
+    Some(HistogramCalibration { values })
+}
+
+fn fit_kind(
+    kind: &str,
+    samples: &[(f64, f64)],
+    opts: &CalibrationOptions,
+) -> Option<CalibrationModel> {
+    match kind {
+        "platt" => fit_platt(samples, opts.max_iters, opts.learning_rate, opts.l2)
+            .map(|fit| CalibrationModel::Platt(fit.calibration)),
+        "isotonic" => fit_isotonic(samples).map(CalibrationModel::Isotonic),
+        "histogram" => fit_histogram(samples, opts.histogram_bins).map(CalibrationModel::Histogram),
+        _ => None,
+    }
+}
+
+/// Out-of-fold log loss and Brier score of a calibrator kind.
+///
+/// Folds are assigned round-robin; `None` when any training fold cannot be
+/// fitted.
+pub fn cross_validate(
+    kind: &str,
+    samples: &[(f64, f64)],
+    opts: &CalibrationOptions,
+) -> Option<(f64, f64)> {
+    let folds = opts.cv_folds.clamp(2, samples.len().max(2));
+    let mut out_of_fold = Vec::with_capacity(samples.len());
+    for fold in 0..folds {
+        let (train, test): (Vec<_>, Vec<_>) = samples
+            .iter()
+            .enumerate()
+            .partition(|(i, _)| i % folds != fold);
+        let train: Vec<(f64, f64)> = train.into_iter().map(|(_, s)| *s).collect();
+        let model = fit_kind(kind, &train, opts)?;
+        out_of_fold.extend(test.into_iter().map(|(_, (p, y))| (model.apply(*p), *y)));
+    }
+    Some(score(&out_of_fold, |p| p))
+}
+
+/// Reliability diagram of `model` over `samples` in `bins` equal-width bins
+/// of calibrated probability.  Empty bins are omitted.
+pub fn reliability_diagram<C: Calibrator>(
+    samples: &[(f64, f64)],
+    model: &C,
+    bins: usize,
+) -> Vec<ReliabilityBin> {
+    let bins = bins.max(1);
+    let mut acc = vec![(0usize, 0.0f64, 0.0f64); bins];
+    for (raw_p, y) in samples {
+        let p = model.apply(*raw_p);
+        let bin = &mut acc[bin_index(p, bins)];
+        bin.0 += 1;
+        bin.1 += p;
+        bin.2 += y;
+    }
+    acc.into_iter()
+        .enumerate()
+        .filter(|(_, (count, _, _))| *count > 0)
+        .map(|(i, (count, sum_p, sum_y))| ReliabilityBin {
+            lower: i as f64 / bins as f64,
+            upper: (i + 1) as f64 / bins as f64,
+            count,
+            mean_predicted: sum_p / count as f64,
+            observed_rate: sum_y / count as f64,
+        })
+        .collect()
+}
+
+/// Pick the calibrator kind with the lowest cross-validated log loss (Brier
+/// score breaks ties) and refit it on every sample.
+pub fn select_calibrator(samples: &[(f64, f64)], opts: &CalibrationOptions) -> Option<Selection> {
+    let (logloss_before, brier_before) = score(samples, clamp_prob);
+    let (kind, (logloss_after, brier_after)) = ["platt", "isotonic", "histogram"]
+        .into_iter()
+        .filter_map(|kind| cross_validate(kind, samples, opts).map(|m| (kind, m)))
+        .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)))?;
+    let model = fit_kind(kind, samples, opts)?;
+    let reliability = reliability_diagram(samples, &model, opts.histogram_bins);
+    Some(Selection {
+        model,
+        metrics: FitMetrics {
+            logloss_before,
+            logloss_after,
+            brier_before,
+            brier_after,
+        },
+        reliability,
+    })
+}
+
 pub fn fit_platt(
     samples: &[(f64, f64)],
     max_iters: usize,
@@ -124,6 +530,33 @@ pub fn fit_platt(
 mod tests {
     use super::*;
 
+    #[test]
+    fn calibration_set_version_tracks_the_models() {
+        let level = |a| LevelCalibration {
+            model: CalibrationModel::Platt(PlattCalibration { a, b: 0.0 }),
+            samples: 100,
+        };
+        let mut levels = HashMap::new();
+        assert!(calibration_set_version(&levels).unwrap().is_none());
+        levels.insert("soccer".to_string(), level(1.1));
+        levels.insert("basketball".to_string(), level(0.9));
+        let (version, json) = calibration_set_version(&levels).unwrap().unwrap();
+        assert!(version.starts_with("cal-") && version.len() == 16);
+        assert!(json.find("basketball") < json.find("soccer"));
+
+        // Sample counts do not change the model; parameters do.
+        levels.get_mut("soccer").unwrap().samples = 500;
+        assert_eq!(
+            calibration_set_version(&levels).unwrap().unwrap().0,
+            version
+        );
+        levels.insert("soccer".to_string(), level(1.2));
+        assert_ne!(
+            calibration_set_version(&levels).unwrap().unwrap().0,
+            version
+        );
+    }
+
     #[test]
     fn platt_fit_improves_distorted_synthetic_probabilities() {
         // Synthetic dataset where raw probabilities are overconfident.
@@ -150,4 +583,69 @@ mod tests {
         let p = apply_platt(0.999_999, m);
         assert!((0.0..=1.0).contains(&p));
     }
+
+    /// Raw probabilities that are right in the middle but badly wrong at
+    /// both edges, which no monotone-in-logit map fixes.
+    fn edge_distorted_samples() -> Vec<(f64, f64)> {
+        let mut samples = Vec::new();
+        let mut seed: u64 = 42;
+        for i in 0..2000 {
+            let p_raw = (i % 100) as f64 / 100.0 + 0.005;
+            let p_true = p_raw.clamp(0.2, 0.8);
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            let u = (seed >> 11) as f64 / (1u64 << 53) as f64;
+            let y = if u < p_true { 1.0 } else { 0.0 };
+            samples.push((p_raw, y));
+        }
+        samples
+    }
+
+    #[test]
+    fn sparse_levels_shrink_towards_parent() {
+        let sport = LevelCalibration {
+            model: CalibrationModel::Platt(PlattCalibration { a: 1.0, b: 0.0 }),
+            samples: 1000,
+        };
+        let league = LevelCalibration {
+            model: CalibrationModel::Histogram(HistogramCalibration {
+                values: vec![0.9; 10],
+            }),
+            samples: 100,
+        };
+        let p = apply_hierarchical(&[Some(&sport), Some(&league)], 0.5, 100.0);
+        assert!((p - 0.7).abs() < 1e-9);
+        // A missing league level leaves the sport answer alone.
+        assert!((apply_hierarchical(&[Some(&sport), None], 0.5, 100.0) - 0.5).abs() < 1e-9);
+        assert_eq!(
+            level_keys("Soccer", Some("MLS"), Some(GamePhase::from_progress(0.8))),
+            vec!["soccer", "soccer/mls", "soccer/mls/late"]
+        );
+    }
+
+    #[test]
+    fn isotonic_fit_is_monotone() {
+        let fit = fit_isotonic(&edge_distorted_samples()).unwrap();
+        assert!(fit.y.windows(2).all(|w| w[0] <= w[1]));
+        assert!(fit.apply(0.01) > 0.1);
+        assert!(fit.apply(0.99) < 0.95);
+    }
+
+    #[test]
+    fn selection_beats_raw_on_edge_distortion() {
+        let opts = CalibrationOptions {
+            max_iters: 300,
+            learning_rate: 0.2,
+            l2: 1e-3,
+            cv_folds: 5,
+            histogram_bins: 10,
+        };
+        let samples = edge_distorted_samples();
+        let selection = select_calibrator(&samples, &opts).unwrap();
+        assert_ne!(selection.model.kind(), "platt");
+        assert!(selection.metrics.logloss_after < selection.metrics.logloss_before);
+        let total: usize = selection.reliability.iter().map(|b| b.count).sum();
+        assert_eq!(total, samples.len());
+    }
 }
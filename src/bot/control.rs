@@ -0,0 +1,286 @@
+//! Manual trading controls.
+//!
+//! The admin API sends [`ControlRequest`]s over a channel that the main
+//! loop drains alongside score events, so every command runs on the engine
+//! between other work rather than racing it.  Pauses and blacklists only
+//! stop new exposure (directional entries, arbitrage baskets and maker
+//! quotes); open positions keep being managed until they exit or are
+//! closed by hand.  The state is persisted so it survives a restart, and
+//! every command that changes anything is audited to `control_actions`.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use super::win_probability::model_family;
+
+/// A command from the admin API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Stop new entries everywhere, or only in `sport`
+    Pause {
+        #[serde(default)]
+        sport: Option<String>,
+    },
+    /// Lift a global or per-sport pause
+    Resume {
+        #[serde(default)]
+        sport: Option<String>,
+    },
+    /// Pause entries, cancel resting orders and close every open position
+    Flatten,
+    /// Close one open position at market
+    ClosePosition { position_id: i64 },
+    /// Stop trading a market and/or a team
+    Blacklist {
+        #[serde(default)]
+        market_id: Option<String>,
+        #[serde(default)]
+        team: Option<String>,
+    },
+    Unblacklist {
+        #[serde(default)]
+        market_id: Option<String>,
+        #[serde(default)]
+        team: Option<String>,
+    },
+    /// Report the current controls only
+    Status,
+}
+
+/// Pause and blacklist state.  Sports are keyed by [`sport_key`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ManualControls {
+    pub entries_paused: bool,
+    pub paused_sports: BTreeSet<String>,
+    pub blacklisted_markets: BTreeSet<String>,
+    pub blacklisted_teams: BTreeSet<String>,
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// Canonical key of a sport name, so a pause on "nba" covers markets and
+/// fixtures tagged "basketball".  Sports without a model keep their name.
+pub fn sport_key(sport: &str) -> String {
+    let sport = normalize(sport);
+    match model_family(&sport) {
+        "fallback" => sport,
+        family => family.to_string(),
+    }
+}
+
+const ENTRIES_PAUSED: &str = "entries_paused";
+const PAUSED_SPORT: &str = "paused_sport";
+const BLACKLISTED_MARKET: &str = "blacklisted_market";
+const BLACKLISTED_TEAM: &str = "blacklisted_team";
+
+/// Normalised non-empty `market_id` and `team` of a (un)blacklist command.
+fn blacklist_targets(
+    market_id: &Option<String>,
+    team: &Option<String>,
+) -> anyhow::Result<(Option<String>, Option<String>)> {
+    let market_id = market_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string);
+    let team = team.as_deref().map(normalize).filter(|t| !t.is_empty());
+    if market_id.is_none() && team.is_none() {
+        anyhow::bail!("market_id or team is required");
+    }
+    Ok((market_id, team))
+}
+
+impl ManualControls {
+    /// Apply the state change of `command`; commands that act on positions
+    /// leave the state alone (except `Flatten`, which also pauses).
+    pub fn apply(&mut self, command: &ControlCommand) -> anyhow::Result<()> {
+        match command {
+            ControlCommand::Pause { sport: None } | ControlCommand::Flatten => {
+                self.entries_paused = true;
+            }
+            ControlCommand::Pause { sport: Some(sport) } => {
+                let sport = sport_key(sport);
+                if sport.is_empty() {
+                    anyhow::bail!("sport must not be empty");
+                }
+                self.paused_sports.insert(sport);
+            }
+            ControlCommand::Resume { sport: None } => self.entries_paused = false,
+            ControlCommand::Resume { sport: Some(sport) } => {
+                self.paused_sports.remove(&sport_key(sport));
+            }
+            ControlCommand::Blacklist { market_id, team } => {
+                let (market_id, team) = blacklist_targets(market_id, team)?;
+                self.blacklisted_markets.extend(market_id);
+                self.blacklisted_teams.extend(team);
+            }
+            ControlCommand::Unblacklist { market_id, team } => {
+                let (market_id, team) = blacklist_targets(market_id, team)?;
+                if let Some(market_id) = market_id {
+                    self.blacklisted_markets.remove(&market_id);
+                }
+                if let Some(team) = team {
+                    self.blacklisted_teams.remove(&team);
+                }
+            }
+            ControlCommand::ClosePosition { .. } | ControlCommand::Status => {}
+        }
+        Ok(())
+    }
+
+    /// Why new entries in `sport` are blocked, if they are.
+    pub fn sport_block(&self, sport: &str) -> Option<String> {
+        if self.entries_paused {
+            return Some("entries paused".into());
+        }
+        let sport = sport_key(sport);
+        self.paused_sports
+            .contains(&sport)
+            .then(|| format!("{} paused", sport))
+    }
+
+    pub fn team_blocked(&self, team: &str) -> bool {
+        self.blacklisted_teams.contains(&normalize(team))
+    }
+
+    pub fn market_blocked(&self, market_id: &str) -> bool {
+        self.blacklisted_markets.contains(market_id)
+    }
+
+    /// `(kind, value)` rows of the `manual_controls` table.
+    pub fn to_rows(&self) -> Vec<(String, String)> {
+        let tagged = |kind: &str, values: &BTreeSet<String>| {
+            values
+                .iter()
+                .map(|v| (kind.to_string(), v.clone()))
+                .collect::<Vec<_>>()
+        };
+        let mut rows = Vec::new();
+        if self.entries_paused {
+            rows.push((ENTRIES_PAUSED.to_string(), String::new()));
+        }
+        rows.extend(tagged(PAUSED_SPORT, &self.paused_sports));
+        rows.extend(tagged(BLACKLISTED_MARKET, &self.blacklisted_markets));
+        rows.extend(tagged(BLACKLISTED_TEAM, &self.blacklisted_teams));
+        rows
+    }
+
+    /// Rebuild from `manual_controls` rows, skipping unknown kinds.
+    pub fn from_rows(rows: &[(String, String)]) -> Self {
+        let mut controls = ManualControls::default();
+        for (kind, value) in rows {
+            match kind.as_str() {
+                ENTRIES_PAUSED => controls.entries_paused = true,
+                PAUSED_SPORT => {
+                    controls.paused_sports.insert(sport_key(value));
+                }
+                BLACKLISTED_MARKET => {
+                    controls.blacklisted_markets.insert(value.clone());
+                }
+                BLACKLISTED_TEAM => {
+                    controls.blacklisted_teams.insert(normalize(value));
+                }
+                _ => {}
+            }
+        }
+        controls
+    }
+}
+
+/// Result of a command, returned to the API caller.
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlOutcome {
+    pub message: String,
+    pub controls: ManualControls,
+}
+
+/// A command with the admin who issued it and where to send the outcome.
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub actor: String,
+    pub command: ControlCommand,
+    pub reply: oneshot::Sender<Result<ControlOutcome, String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_from_json() {
+        let pause: ControlCommand = serde_json::from_str(r#"{"action":"pause"}"#).unwrap();
+        assert_eq!(pause, ControlCommand::Pause { sport: None });
+        let close: ControlCommand =
+            serde_json::from_str(r#"{"action":"close_position","position_id":7}"#).unwrap();
+        assert_eq!(close, ControlCommand::ClosePosition { position_id: 7 });
+        assert!(serde_json::from_str::<ControlCommand>(r#"{"action":"panic"}"#).is_err());
+    }
+
+    #[test]
+    fn pauses_and_blacklists_until_lifted() {
+        let mut controls = ManualControls::default();
+        controls
+            .apply(&ControlCommand::Pause {
+                sport: Some(" NBA ".into()),
+            })
+            .unwrap();
+        assert_eq!(
+            controls.sport_block("nba").as_deref(),
+            Some("basketball paused")
+        );
+        // Markets and fixtures tag the same sport differently.
+        assert!(controls.sport_block("Basketball").is_some());
+        assert_eq!(controls.sport_block("soccer"), None);
+
+        controls.apply(&ControlCommand::Flatten).unwrap();
+        assert!(controls.sport_block("soccer").is_some());
+        controls
+            .apply(&ControlCommand::Resume { sport: None })
+            .unwrap();
+        assert_eq!(controls.sport_block("soccer"), None);
+        assert!(controls.sport_block("basketball").is_some());
+        controls
+            .apply(&ControlCommand::Pause {
+                sport: Some("Cricket".into()),
+            })
+            .unwrap();
+        assert!(controls.sport_block("cricket").is_some());
+        assert!(controls
+            .apply(&ControlCommand::Pause {
+                sport: Some(" ".into()),
+            })
+            .is_err());
+
+        controls
+            .apply(&ControlCommand::Blacklist {
+                market_id: Some("0xabc".into()),
+                team: Some("Arsenal".into()),
+            })
+            .unwrap();
+        assert!(controls.market_blocked("0xabc"));
+        assert!(controls.team_blocked("arsenal "));
+        controls
+            .apply(&ControlCommand::Unblacklist {
+                market_id: None,
+                team: Some("ARSENAL".into()),
+            })
+            .unwrap();
+        assert!(!controls.team_blocked("Arsenal"));
+        assert!(controls.market_blocked("0xabc"));
+
+        let empty = ControlCommand::Blacklist {
+            market_id: None,
+            team: Some("  ".into()),
+        };
+        assert!(controls.apply(&empty).is_err());
+
+        assert_eq!(ManualControls::from_rows(&controls.to_rows()), controls);
+        controls.apply(&ControlCommand::Flatten).unwrap();
+        assert_eq!(ManualControls::from_rows(&controls.to_rows()), controls);
+    }
+}
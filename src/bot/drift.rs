@@ -0,0 +1,231 @@
+//! Drift monitors for model calibration, realised edge and feed quality.
+//!
+//! Calibration is refitted on a timer and the feed-health breaker reacts to
+//! acute outages, but neither notices slow decay: a calibration that has
+//! stopped matching outcomes, entries whose closing-line value has turned
+//! negative in one sport, or a price feed that has become steadily worse
+//! than it used to be.  Each monitor compares a recent window against a
+//! baseline and reports a [`DriftBreach`]; the engine raises an alert for
+//! each new breach and, depending on `drift_action`, widens the sport's edge
+//! requirement or pauses its entries until the metric recovers.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use super::calibration::score;
+
+/// Scope used for breaches that are not specific to one sport.
+pub const GLOBAL_SCOPE: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    /// Rolling log loss well above the last fit's
+    Calibration,
+    /// Recent closing-line value below the floor
+    EdgeDecay,
+    /// Price feed worse than its long-run baseline
+    FeedQuality,
+}
+
+impl DriftKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DriftKind::Calibration => "calibration",
+            DriftKind::EdgeDecay => "edge_decay",
+            DriftKind::FeedQuality => "feed_quality",
+        }
+    }
+}
+
+/// Response to an active breach (see `drift_action`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftAction {
+    /// Log and record the alert only
+    Alert,
+    /// Add `drift_min_edge_addon` to the affected sport's entry threshold
+    Tighten,
+    /// Stop new entries in the affected sport
+    Pause,
+}
+
+impl DriftAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DriftAction::Alert => "alert",
+            DriftAction::Tighten => "tighten",
+            DriftAction::Pause => "pause",
+        }
+    }
+}
+
+impl FromStr for DriftAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alert" => Ok(DriftAction::Alert),
+            "tighten" => Ok(DriftAction::Tighten),
+            "pause" => Ok(DriftAction::Pause),
+            other => anyhow::bail!("unknown drift action '{}'", other),
+        }
+    }
+}
+
+/// A monitor outside its tolerance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftBreach {
+    pub kind: DriftKind,
+    /// Sport key, or [`GLOBAL_SCOPE`]
+    pub scope: String,
+    /// Current value of the monitored metric
+    pub metric: f64,
+    /// Value it is compared against
+    pub baseline: f64,
+    /// Limit that was crossed
+    pub threshold: f64,
+    pub message: String,
+}
+
+/// Calibration drift: log loss of the calibrated probabilities over recent
+/// labelled `(prob, label)` samples against the log loss of the last fit.
+pub fn calibration_drift(
+    scope: &str,
+    recent: &[(f64, f64)],
+    fitted_logloss: f64,
+    tolerance: f64,
+    min_samples: usize,
+) -> Option<DriftBreach> {
+    if recent.len() < min_samples || fitted_logloss <= 0.0 {
+        return None;
+    }
+    let (logloss, _) = score(recent, |p| p);
+    let threshold = fitted_logloss * (1.0 + tolerance);
+    (logloss > threshold).then(|| DriftBreach {
+        kind: DriftKind::Calibration,
+        scope: scope.to_string(),
+        metric: logloss,
+        baseline: fitted_logloss,
+        threshold,
+        message: format!(
+            "rolling log loss {:.4} over {} samples vs {:.4} at last fit",
+            logloss,
+            recent.len(),
+            fitted_logloss
+        ),
+    })
+}
+
+/// Edge decay: mean CLV (bps) of the last `window` closed trades, oldest
+/// first in `clv_bps`, below `min_clv_bps`.  The baseline is the mean of the
+/// trades before the window, so the alert shows the trend.
+pub fn edge_decay(
+    scope: &str,
+    clv_bps: &[f64],
+    window: usize,
+    min_clv_bps: f64,
+) -> Option<DriftBreach> {
+    if window == 0 || clv_bps.len() < window {
+        return None;
+    }
+    let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len().max(1) as f64;
+    let (earlier, recent) = clv_bps.split_at(clv_bps.len() - window);
+    let recent_clv = mean(recent);
+    let baseline = if earlier.is_empty() {
+        recent_clv
+    } else {
+        mean(earlier)
+    };
+    (recent_clv < min_clv_bps).then(|| DriftBreach {
+        kind: DriftKind::EdgeDecay,
+        scope: scope.to_string(),
+        metric: recent_clv,
+        baseline,
+        threshold: min_clv_bps,
+        message: format!(
+            "mean CLV {:.1} bps over last {} trades (earlier {:.1} bps)",
+            recent_clv, window, baseline
+        ),
+    })
+}
+
+/// Feed-quality drift: the short-run REST-fallback rate or WS quote age
+/// against their long-run baselines.
+pub fn feed_drift(
+    fallback_rate: f64,
+    baseline_fallback_rate: f64,
+    ws_age_ms: f64,
+    baseline_ws_age_ms: f64,
+    max_fallback_increase: f64,
+    max_ws_age_ratio: f64,
+) -> Option<DriftBreach> {
+    let fallback_threshold = baseline_fallback_rate + max_fallback_increase;
+    if fallback_rate > fallback_threshold {
+        return Some(DriftBreach {
+            kind: DriftKind::FeedQuality,
+            scope: GLOBAL_SCOPE.to_string(),
+            metric: fallback_rate,
+            baseline: baseline_fallback_rate,
+            threshold: fallback_threshold,
+            message: format!(
+                "REST fallback rate {:.3} vs baseline {:.3}",
+                fallback_rate, baseline_fallback_rate
+            ),
+        });
+    }
+    let age_threshold = baseline_ws_age_ms * max_ws_age_ratio;
+    (baseline_ws_age_ms > 0.0 && ws_age_ms > age_threshold).then(|| DriftBreach {
+        kind: DriftKind::FeedQuality,
+        scope: GLOBAL_SCOPE.to_string(),
+        metric: ws_age_ms,
+        baseline: baseline_ws_age_ms,
+        threshold: age_threshold,
+        message: format!(
+            "WS quote age {:.0} ms vs baseline {:.0} ms",
+            ws_age_ms, baseline_ws_age_ms
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_calibration_worse_than_last_fit() {
+        // Confident and wrong half the time: log loss ~1.2 vs a 0.6 fit.
+        let recent: Vec<(f64, f64)> = (0..60)
+            .map(|i| (0.8, if i % 2 == 0 { 1.0 } else { 0.0 }))
+            .collect();
+        let breach = calibration_drift("soccer", &recent, 0.6, 0.15, 50).unwrap();
+        assert_eq!(breach.kind, DriftKind::Calibration);
+        assert!(breach.metric > breach.threshold);
+
+        assert!(calibration_drift("soccer", &recent[..10], 0.6, 0.15, 50).is_none());
+        assert!(calibration_drift("soccer", &recent, 1.5, 0.15, 50).is_none());
+    }
+
+    #[test]
+    fn flags_recent_clv_below_floor_with_trend() {
+        let mut clv = vec![40.0; 30];
+        clv.extend([-20.0; 10]);
+        let breach = edge_decay("nba", &clv, 10, 0.0).unwrap();
+        assert_eq!(breach.metric, -20.0);
+        assert_eq!(breach.baseline, 40.0);
+
+        assert!(edge_decay("nba", &clv[..30], 10, 0.0).is_none());
+        assert!(edge_decay("nba", &clv[..5], 10, 0.0).is_none());
+    }
+
+    #[test]
+    fn flags_feed_worse_than_baseline() {
+        assert!(feed_drift(0.1, 0.05, 400.0, 300.0, 0.2, 2.0).is_none());
+        let fallback = feed_drift(0.4, 0.05, 400.0, 300.0, 0.2, 2.0).unwrap();
+        assert_eq!(fallback.scope, GLOBAL_SCOPE);
+        assert_eq!(fallback.metric, 0.4);
+        let age = feed_drift(0.1, 0.05, 900.0, 300.0, 0.2, 2.0).unwrap();
+        assert_eq!(age.metric, 900.0);
+        assert_eq!("pause".parse::<DriftAction>().unwrap(), DriftAction::Pause);
+    }
+}
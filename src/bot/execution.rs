@@ -0,0 +1,281 @@
+//! Lifecycle of live entry orders.
+//!
+//! `place_order` only submits a limit order; nothing guarantees it fills.
+//! After a score event the market reprices within seconds, and an entry left
+//! resting at the pre-move price tends to fill only once the news is priced
+//! in, when it is no longer worth having.  Live entries are therefore held
+//! as pending until they fill, and polled on every sweep: cancelled once they
+//! time out or the edge is gone, or (optionally) chased with a cancel/replace
+//! while the edge persists.  Dry-run entries fill immediately as before.
+//! Hedge orders and arbitrage legs go through the same lifecycle, capped at
+//! the highest price that still beats selling, or keeps the basket
+//! profitable, instead of the model edge.  Arbitrage legs are only booked
+//! once every leg of their basket has finished, sized to the smallest fill.
+//! Pending entries are persisted so a restart reconciles them against the
+//! exchange instead of forgetting orders that may still fill.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::models::Position;
+
+use super::kelly::edge;
+
+/// Polymarket price tick.
+const TICK: f64 = 0.01;
+
+/// What a pending order is for.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Model-driven entry or pyramid add
+    #[default]
+    Directional,
+    /// Hedge for the stopped-out position `hedged_id`, grouped with it once
+    /// it fills
+    Hedge { hedged_id: i64 },
+    /// One leg of the arbitrage basket on the event `basket`
+    ArbitrageLeg { basket: String },
+}
+
+/// A live entry order that has not finished filling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingEntry {
+    /// Resting CLOB order
+    pub order_id: String,
+    /// Position booked on fill; `size_usd` is the full stake
+    pub position: Position,
+    /// Open position this order adds to (pyramiding)
+    pub add_to: Option<i64>,
+    /// Limit price of the resting order
+    pub limit_price: f64,
+    /// Shares matched on the resting order so far
+    pub order_matched: f64,
+    /// Shares and cost filled across every order for this entry
+    pub filled_shares: f64,
+    pub filled_usd: f64,
+    /// Model probability for the bought outcome at entry
+    pub model_prob: f64,
+    /// Raw edge (see `kelly::edge`) the entry had to clear
+    pub threshold_edge: f64,
+    pub placed_at: DateTime<Utc>,
+    /// Cancel/replace cycles so far
+    pub reprices: u32,
+    #[serde(default)]
+    pub kind: EntryKind,
+    /// Highest price worth paying; replaces the model edge check for orders
+    /// that are not directional bets
+    #[serde(default)]
+    pub max_price: Option<f64>,
+    /// Why the order finished, for an arbitrage leg waiting on the rest of
+    /// its basket
+    #[serde(default)]
+    pub finished: Option<String>,
+}
+
+impl PendingEntry {
+    /// Stake not yet filled.
+    pub fn unfilled_usd(&self) -> f64 {
+        (self.position.size_usd - self.filled_usd).max(0.0)
+    }
+
+    /// Stake to re-place at `price`: directional entries keep their USD
+    /// budget, hedges and arbitrage legs their share count.
+    pub fn remaining_usd_at(&self, price: f64) -> f64 {
+        match self.kind {
+            EntryKind::Directional => self.unfilled_usd(),
+            EntryKind::Hedge { .. } | EntryKind::ArbitrageLeg { .. } => {
+                let target_shares = self.position.size_usd / self.position.entry_price;
+                (target_shares - self.filled_shares).max(0.0) * price
+            }
+        }
+    }
+
+    /// Record `matched` shares on the resting order.  Returns the new shares.
+    pub fn record_matched(&mut self, matched: f64) -> f64 {
+        let new_shares = (matched - self.order_matched).max(0.0);
+        self.order_matched = self.order_matched.max(matched);
+        self.filled_shares += new_shares;
+        self.filled_usd += new_shares * self.limit_price;
+        new_shares
+    }
+}
+
+/// Execution tunables (see the `order_*` config options).
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionParams {
+    pub timeout_secs: u64,
+    pub reprice_enabled: bool,
+    pub max_reprices: u32,
+}
+
+/// What to do with a pending entry on this sweep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryAction {
+    Wait,
+    /// Cancel the remainder; the reason is logged and recorded on fills.
+    Cancel(&'static str),
+    /// Cancel and re-place the remainder at this price.
+    Replace(f64),
+}
+
+/// Decide the next step for `entry` given the outcome's current price.
+///
+/// The edge is re-checked against the entry threshold first (or the price
+/// against `max_price`): once the market has caught up to the model, the
+/// order is pulled whatever its age.  A market that ran away from the
+/// limit, or an order that timed out, is chased at the new price when
+/// re-pricing is enabled and budget remains.
+pub fn next_action(
+    entry: &PendingEntry,
+    market_price: Option<f64>,
+    now: DateTime<Utc>,
+    params: &ExecutionParams,
+) -> EntryAction {
+    if let Some(price) = market_price {
+        let gone = match entry.max_price {
+            Some(max_price) => price > max_price + 1e-9,
+            None => edge(entry.model_prob, price) < entry.threshold_edge,
+        };
+        if gone {
+            return EntryAction::Cancel("edge_gone");
+        }
+    }
+    let timed_out = (now - entry.placed_at).num_seconds() >= params.timeout_secs as i64;
+    let can_reprice = params.reprice_enabled && entry.reprices < params.max_reprices;
+    if let Some(price) = market_price {
+        let new_price = round_to_tick(price);
+        let ran_away = new_price >= entry.limit_price + TICK - 1e-9;
+        let affordable = entry
+            .max_price
+            .is_none_or(|max_price| new_price <= max_price + 1e-9);
+        if can_reprice && ran_away && affordable {
+            return EntryAction::Replace(new_price);
+        }
+    }
+    if timed_out {
+        EntryAction::Cancel("timeout")
+    } else {
+        EntryAction::Wait
+    }
+}
+
+fn round_to_tick(price: f64) -> f64 {
+    ((price * 100.0).round() / 100.0).clamp(TICK, 1.0 - TICK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn params(reprice_enabled: bool) -> ExecutionParams {
+        ExecutionParams {
+            timeout_secs: 10,
+            reprice_enabled,
+            max_reprices: 2,
+        }
+    }
+
+    fn entry(placed_at: DateTime<Utc>) -> PendingEntry {
+        PendingEntry {
+            order_id: "ord1".into(),
+            position: Position {
+                entry_model_prob: Some(0.6),
+                stop_loss_price: 0.4,
+                take_profit_price: 0.6,
+                opened_at: placed_at,
+                yes_is_home: Some(true),
+                ..Position::new_open("mkt1", "YES", "directional", 10.0, 0.5, false)
+            },
+            add_to: None,
+            limit_price: 0.5,
+            order_matched: 0.0,
+            filled_shares: 0.0,
+            filled_usd: 0.0,
+            model_prob: 0.6,
+            threshold_edge: 0.1,
+            placed_at,
+            reprices: 0,
+            kind: EntryKind::Directional,
+            max_price: None,
+            finished: None,
+        }
+    }
+
+    #[test]
+    fn cancels_when_edge_is_gone_or_order_times_out() {
+        let now = Utc::now();
+        let fresh = entry(now);
+        assert_eq!(
+            next_action(&fresh, Some(0.5), now, &params(false)),
+            EntryAction::Wait
+        );
+        // Market caught up: 0.6 / 0.57 - 1 < 0.1.
+        assert_eq!(
+            next_action(&fresh, Some(0.57), now, &params(true)),
+            EntryAction::Cancel("edge_gone")
+        );
+        let stale = entry(now - Duration::seconds(11));
+        assert_eq!(
+            next_action(&stale, Some(0.5), now, &params(true)),
+            EntryAction::Cancel("timeout")
+        );
+    }
+
+    #[test]
+    fn chases_a_moving_market_while_edge_persists() {
+        let now = Utc::now();
+        let mut pending = entry(now);
+        assert_eq!(
+            next_action(&pending, Some(0.53), now, &params(true)),
+            EntryAction::Replace(0.53)
+        );
+        assert_eq!(
+            next_action(&pending, Some(0.53), now, &params(false)),
+            EntryAction::Wait
+        );
+        pending.reprices = 2;
+        assert_eq!(
+            next_action(&pending, Some(0.53), now, &params(true)),
+            EntryAction::Wait
+        );
+    }
+
+    #[test]
+    fn hedges_are_capped_by_price_and_keep_their_share_count() {
+        let now = Utc::now();
+        let mut hedge = PendingEntry {
+            kind: EntryKind::Hedge { hedged_id: 3 },
+            max_price: Some(0.55),
+            model_prob: 0.0,
+            threshold_edge: 0.0,
+            ..entry(now)
+        };
+        // No model edge to lose; only the price cap matters.
+        assert_eq!(
+            next_action(&hedge, Some(0.5), now, &params(true)),
+            EntryAction::Wait
+        );
+        assert_eq!(
+            next_action(&hedge, Some(0.54), now, &params(true)),
+            EntryAction::Replace(0.54)
+        );
+        assert_eq!(
+            next_action(&hedge, Some(0.56), now, &params(true)),
+            EntryAction::Cancel("edge_gone")
+        );
+
+        // 20 shares wanted, 8 filled: the rest costs 12 * 0.54.
+        hedge.record_matched(8.0);
+        assert!((hedge.remaining_usd_at(0.54) - 6.48).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tracks_fills_across_orders() {
+        let mut pending = entry(Utc::now());
+        assert!((pending.record_matched(8.0) - 8.0).abs() < 1e-9);
+        assert!((pending.record_matched(8.0)).abs() < 1e-9);
+        assert!((pending.unfilled_usd() - 6.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,133 @@
+//! Cross-market hedging for positions that have gone against us.
+//!
+//! Instead of selling a losing position into a possibly thin book, buy a leg
+//! that pays out exactly when the position loses: the opposite token of the
+//! same market, or (in sports without draws) the other team's winner market.
+//! Holding both legs to resolution pays one dollar per share whichever way
+//! the game ends, so the outcome is locked in.  The hedge is only worth it
+//! when that locked P&L beats selling now.
+
+use crate::db::models::Position;
+
+/// Whether a game in this sport can end without a winner, leaving a pair of
+/// team-winner markets uncovered.
+pub fn sport_has_draws(sport: &str) -> bool {
+    matches!(
+        sport.to_lowercase().as_str(),
+        "soccer" | "football" | "football_eu"
+    )
+}
+
+/// A tradable leg that pays out exactly when the position loses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeCandidate {
+    pub market_id: String,
+    /// "YES" or "NO"
+    pub outcome: String,
+    /// Current price of the hedge token (0.0–1.0)
+    pub price: f64,
+    /// Whether YES on the hedge market pays out on a home win
+    pub yes_is_home: Option<bool>,
+}
+
+/// A sized hedge for one position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgePlan {
+    pub candidate: HedgeCandidate,
+    /// Hedge notional per dollar of position cost basis.
+    pub hedge_ratio: f64,
+    pub hedge_size_usd: f64,
+    /// Net P&L of holding position and hedge to resolution, whoever wins.
+    pub locked_pnl: f64,
+}
+
+/// Size a hedge that equalises the payout of `pos` across both outcomes.
+///
+/// Both legs pay $1 per share, so the hedge buys as many shares as the
+/// position holds.  Only entry costs are charged since both legs are held to
+/// resolution; `one_way_cost` is the fee + slippage fraction of the hedge buy.
+pub fn plan_hedge(
+    pos: &Position,
+    candidate: HedgeCandidate,
+    one_way_cost: f64,
+) -> Option<HedgePlan> {
+    if pos.entry_price <= 0.0 || !(candidate.price > 0.0 && candidate.price < 1.0) {
+        return None;
+    }
+    let shares = pos.size_usd / pos.entry_price;
+    let hedge_size_usd = shares * candidate.price;
+    let entry_cost = pos.size_usd * (pos.estimated_round_trip_cost_bps / 2.0 / 10_000.0);
+    let locked_pnl =
+        shares - pos.size_usd - entry_cost - hedge_size_usd - hedge_size_usd * one_way_cost;
+    Some(HedgePlan {
+        hedge_ratio: hedge_size_usd / pos.size_usd,
+        hedge_size_usd,
+        locked_pnl,
+        candidate,
+    })
+}
+
+/// The candidate that locks in the best P&L.
+pub fn best_hedge(
+    pos: &Position,
+    candidates: Vec<HedgeCandidate>,
+    one_way_cost: f64,
+) -> Option<HedgePlan> {
+    candidates
+        .into_iter()
+        .filter_map(|c| plan_hedge(pos, c, one_way_cost))
+        .max_by(|a, b| a.locked_pnl.total_cmp(&b.locked_pnl))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn position(size_usd: f64, entry: f64) -> Position {
+        Position {
+            id: Some(1),
+            stop_loss_price: 0.3,
+            take_profit_price: 0.7,
+            sport: Some("basketball".into()),
+            yes_is_home: Some(true),
+            ..Position::new_open("mkt1", "YES", "directional", size_usd, entry, true)
+        }
+    }
+
+    fn candidate(market_id: &str, outcome: &str, price: f64) -> HedgeCandidate {
+        HedgeCandidate {
+            market_id: market_id.into(),
+            outcome: outcome.into(),
+            price,
+            yes_is_home: Some(false),
+        }
+    }
+
+    #[test]
+    fn hedge_locks_the_same_pnl_either_way() {
+        // 20 shares bought for $10; NO at 0.6 costs $12 for 20 shares.
+        let plan = plan_hedge(&position(10.0, 0.5), candidate("mkt1", "NO", 0.6), 0.0).unwrap();
+        assert_relative_eq!(plan.hedge_size_usd, 12.0);
+        assert_relative_eq!(plan.hedge_ratio, 1.2);
+        assert_relative_eq!(plan.locked_pnl, 20.0 - 10.0 - 12.0);
+    }
+
+    #[test]
+    fn best_hedge_picks_cheapest_leg() {
+        let pos = position(10.0, 0.5);
+        let plan = best_hedge(
+            &pos,
+            vec![
+                candidate("mkt1", "NO", 0.62),
+                candidate("mkt2", "YES", 0.58),
+                candidate("mkt3", "YES", 1.0),
+            ],
+            0.01,
+        )
+        .unwrap();
+        assert_eq!(plan.candidate.market_id, "mkt2");
+        assert!(sport_has_draws("soccer"));
+        assert!(!sport_has_draws("basketball"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Maker-side quoting around the model's fair value.
+//!
+//! Instead of crossing the spread after a score event, rest a bid and an ask
+//! on the YES token of a live fixture's winner market, centred on the
+//! calibrated model probability.  Quotes are skewed away from accumulated
+//! inventory, sized from the market's depth, and pulled whenever a score
+//! event arrives or a risk breaker trips.
+
+use chrono::{DateTime, Utc};
+
+/// Polymarket price tick.
+const TICK: f64 = 0.01;
+
+/// Which side of the book a quote rests on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteSide {
+    Bid,
+    Ask,
+}
+
+impl QuoteSide {
+    /// CLOB order side.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuoteSide::Bid => "buy",
+            QuoteSide::Ask => "sell",
+        }
+    }
+}
+
+/// A limit order we have resting on the book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestingQuote {
+    pub order_id: String,
+    pub side: QuoteSide,
+    pub price: f64,
+    pub shares: f64,
+    /// Shares already booked as filled
+    pub filled_shares: f64,
+    pub placed_at: DateTime<Utc>,
+}
+
+impl RestingQuote {
+    /// Shares still open on the book.
+    pub fn remaining_shares(&self) -> f64 {
+        (self.shares - self.filled_shares).max(0.0)
+    }
+
+    /// Whether the quote already rests as `target` `(price, shares)`: same
+    /// price, never more shares than the target and short of it by less
+    /// than the $1 minimum quote.
+    pub fn matches(&self, (price, shares): (f64, f64)) -> bool {
+        let remaining = self.remaining_shares();
+        (self.price - price).abs() < 1e-9
+            && remaining <= shares + 1e-9
+            && (shares - remaining) * price < 1.0
+    }
+}
+
+/// Quoting tunables (see the `mm_*` config options).
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteParams {
+    /// Distance of each quote from the (skewed) centre.
+    pub half_spread: f64,
+    /// Centre shift at full inventory, away from the side we are long.
+    pub inventory_skew: f64,
+    pub max_inventory_usd: f64,
+    pub max_quote_usd: f64,
+    /// Fraction of market liquidity a quote may show.
+    pub depth_fraction: f64,
+}
+
+/// Target bid and ask as `(price, shares)`; `None` means no quote on that side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MakerQuotes {
+    pub bid: Option<(f64, f64)>,
+    pub ask: Option<(f64, f64)>,
+}
+
+/// Quotes around `fair` for a book holding `inventory_shares` YES shares
+/// costing `inventory_usd`, in a market with `depth_usd` of liquidity.
+///
+/// The bid shrinks to nothing as inventory approaches the cap; the ask never
+/// offers more than we hold.
+pub fn compute_quotes(
+    fair: f64,
+    inventory_usd: f64,
+    inventory_shares: f64,
+    depth_usd: Option<f64>,
+    params: &QuoteParams,
+) -> MakerQuotes {
+    if !(fair > 0.0 && fair < 1.0) || params.max_inventory_usd <= 0.0 {
+        return MakerQuotes {
+            bid: None,
+            ask: None,
+        };
+    }
+    let fill = (inventory_usd / params.max_inventory_usd).clamp(0.0, 1.0);
+    let centre = fair - params.inventory_skew * fill;
+    let quote_usd = match depth_usd {
+        Some(depth) => params.max_quote_usd.min(depth * params.depth_fraction),
+        None => params.max_quote_usd,
+    };
+
+    let bid_price = round_down(centre - params.half_spread);
+    let bid_usd = quote_usd.min(params.max_inventory_usd - inventory_usd);
+    let bid = (bid_price >= TICK && bid_usd >= 1.0).then(|| (bid_price, bid_usd / bid_price));
+
+    let ask_price = round_up(centre + params.half_spread);
+    let ask_shares = inventory_shares.min(quote_usd / ask_price);
+    let ask = (ask_price <= 1.0 - TICK && ask_shares * ask_price >= 1.0)
+        .then_some((ask_price, ask_shares));
+
+    MakerQuotes { bid, ask }
+}
+
+/// Dry-run fill simulation: a resting quote fills once the opposite side of
+/// the book trades through its price.
+pub fn quote_crossed(quote: &RestingQuote, best_bid: f64, best_ask: f64) -> bool {
+    match quote.side {
+        QuoteSide::Bid => best_ask > 0.0 && best_ask <= quote.price,
+        QuoteSide::Ask => best_bid > 0.0 && best_bid >= quote.price,
+    }
+}
+
+fn round_down(price: f64) -> f64 {
+    ((price / TICK + 1e-9).floor() * TICK).clamp(0.0, 1.0)
+}
+
+fn round_up(price: f64) -> f64 {
+    ((price / TICK - 1e-9).ceil() * TICK).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> QuoteParams {
+        QuoteParams {
+            half_spread: 0.02,
+            inventory_skew: 0.02,
+            max_inventory_usd: 25.0,
+            max_quote_usd: 5.0,
+            depth_fraction: 0.01,
+        }
+    }
+
+    #[test]
+    fn flat_book_quotes_bid_only_around_fair() {
+        let q = compute_quotes(0.555, 0.0, 0.0, None, &params());
+        let (bid, shares) = q.bid.unwrap();
+        assert!((bid - 0.53).abs() < 1e-9);
+        assert!((shares * bid - 5.0).abs() < 1e-9);
+        // Nothing to sell yet.
+        assert_eq!(q.ask, None);
+    }
+
+    #[test]
+    fn inventory_skews_quotes_down_and_caps_bid() {
+        // Full inventory: centre shifts down by the skew, bid disappears.
+        let q = compute_quotes(0.55, 25.0, 50.0, None, &params());
+        assert_eq!(q.bid, None);
+        let (ask, shares) = q.ask.unwrap();
+        assert!((ask - 0.55).abs() < 1e-9);
+        assert!((shares * ask - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn thin_markets_get_smaller_quotes() {
+        let q = compute_quotes(0.5, 0.0, 0.0, Some(200.0), &params());
+        let (bid, shares) = q.bid.unwrap();
+        assert!((shares * bid - 2.0).abs() < 1e-9);
+        assert_eq!(
+            compute_quotes(0.5, 0.0, 0.0, Some(50.0), &params()).bid,
+            None
+        );
+    }
+
+    #[test]
+    fn crossed_quotes_fill() {
+        let quote = RestingQuote {
+            order_id: "1".into(),
+            side: QuoteSide::Bid,
+            price: 0.5,
+            shares: 10.0,
+            filled_shares: 0.0,
+            placed_at: Utc::now(),
+        };
+        assert!(!quote_crossed(&quote, 0.49, 0.51));
+        assert!(quote_crossed(&quote, 0.48, 0.50));
+    }
+
+    #[test]
+    fn resized_targets_do_not_match_the_resting_quote() {
+        let mut quote = RestingQuote {
+            order_id: "1".into(),
+            side: QuoteSide::Ask,
+            price: 0.5,
+            shares: 10.0,
+            filled_shares: 0.0,
+            placed_at: Utc::now(),
+        };
+        assert!(quote.matches((0.5, 10.0)));
+        assert!(quote.matches((0.5, 11.0)));
+        assert!(!quote.matches((0.51, 10.0)));
+        // Inventory shrank: never offer more than the new target.
+        assert!(!quote.matches((0.5, 6.0)));
+        // Depth grew by more than the minimum quote.
+        assert!(!quote.matches((0.5, 14.0)));
+        quote.filled_shares = 4.0;
+        assert!(quote.matches((0.5, 6.0)));
+    }
+}
@@ -1,6 +1,16 @@
+pub mod arbitrage;
 pub mod calibration;
+pub mod control;
+pub mod drift;
+pub mod execution;
+pub mod hedging;
 pub mod kelly;
+pub mod market_making;
+pub mod model_fit;
+pub mod model_store;
 pub mod position;
+pub mod profiles;
+pub mod shadow;
 pub mod strategy;
 pub mod win_probability;
 
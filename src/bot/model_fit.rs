@@ -0,0 +1,381 @@
+//! Offline fitting of the win-probability model parameters.
+//!
+//! `fit-models` turns archived fixtures (or a CSV export of the same data)
+//! into one training row per known game state, labelled with whether the
+//! home side went on to win, and refits the coefficients in
+//! [`WinProbParams`] by maximum likelihood:
+//!
+//! - the logistic `k` of each sport family by golden-section search on the
+//!   mean negative log-likelihood of the full model;
+//! - the soccer table by projected gradient descent on the cells the
+//!   interpolation touches, with a ridge penalty towards the current table
+//!   so that sparsely populated cells stay close to the prior.
+//!
+//! A family is only replaced when it has enough rows and the fit actually
+//! lowers its log-loss; everything else keeps the current value.
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::db::models::{GameStatus, LiveGame};
+use crate::db::{ArchivedFixture, Database};
+
+use super::win_probability::{
+    model_family, sport_win_probability_with, WinProbParams, SOCCER_MINUTES,
+};
+
+/// Search interval for the logistic coefficients.
+const K_RANGE: (f64, f64) = (0.01, 5.0);
+/// Golden-section iterations (interval shrinks by 0.618 each).
+const K_SEARCH_ITERS: usize = 60;
+/// Soccer table optimiser.
+const TABLE_ITERS: usize = 500;
+const TABLE_LEARNING_RATE: f64 = 0.05;
+/// Ridge weight towards the prior table, in equivalent rows per cell.
+const TABLE_PRIOR_ROWS: f64 = 20.0;
+
+/// One game state with its eventual outcome.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TrainingRow {
+    pub sport: String,
+    pub minute: i32,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub home_won: bool,
+}
+
+/// CSV input layout: one game state per line.
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    sport: String,
+    minute: i32,
+    home_score: i32,
+    away_score: i32,
+    final_home_score: i32,
+    final_away_score: i32,
+}
+
+/// Outcome of fitting one model family.
+#[derive(Debug, Clone, Serialize)]
+pub struct FamilyFit {
+    pub family: String,
+    pub samples: usize,
+    pub logloss_before: f64,
+    pub logloss_after: f64,
+    /// Whether the fitted values replaced the previous ones
+    pub accepted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FitReport {
+    pub params: WinProbParams,
+    pub families: Vec<FamilyFit>,
+}
+
+/// Training rows from every archived fixture: the 0-0 start plus each
+/// timeline entry with a known minute.
+pub fn rows_from_fixtures(fixtures: &[ArchivedFixture]) -> Vec<TrainingRow> {
+    let mut rows = Vec::new();
+    for fixture in fixtures {
+        let home_won = fixture.home_score > fixture.away_score;
+        rows.push(TrainingRow {
+            sport: fixture.sport.clone(),
+            minute: 0,
+            home_score: 0,
+            away_score: 0,
+            home_won,
+        });
+        for entry in &fixture.timeline {
+            let Some(minute) = entry.minute else { continue };
+            rows.push(TrainingRow {
+                sport: fixture.sport.clone(),
+                minute,
+                home_score: entry.home_score,
+                away_score: entry.away_score,
+                home_won,
+            });
+        }
+    }
+    rows
+}
+
+/// Load training rows from a `.csv` file or a bot SQLite database.
+pub fn load_rows(source: &str) -> anyhow::Result<Vec<TrainingRow>> {
+    if source.to_ascii_lowercase().ends_with(".csv") {
+        let mut reader =
+            csv::Reader::from_path(source).with_context(|| format!("Failed to open {}", source))?;
+        reader
+            .deserialize::<CsvRow>()
+            .map(|row| {
+                let row = row.with_context(|| format!("Invalid row in {}", source))?;
+                Ok(TrainingRow {
+                    sport: row.sport,
+                    minute: row.minute,
+                    home_score: row.home_score,
+                    away_score: row.away_score,
+                    home_won: row.final_home_score > row.final_away_score,
+                })
+            })
+            .collect()
+    } else {
+        let db = Database::open(source)?;
+        Ok(rows_from_fixtures(&db.list_fixtures()?))
+    }
+}
+
+/// Refit every family with at least `min_samples` rows, starting from `base`.
+pub fn fit(rows: &[TrainingRow], base: &WinProbParams, min_samples: usize) -> FitReport {
+    let mut by_family: HashMap<&'static str, Vec<&TrainingRow>> = HashMap::new();
+    for row in rows {
+        by_family
+            .entry(model_family(&row.sport))
+            .or_default()
+            .push(row);
+    }
+
+    let mut params = base.clone();
+    let mut families = Vec::new();
+    for family in ["soccer", "basketball", "nfl", "baseball", "hockey"] {
+        let Some(family_rows) = by_family.get(family) else {
+            continue;
+        };
+        let before = mean_logloss(&params, family_rows);
+        let mut fit = FamilyFit {
+            family: family.into(),
+            samples: family_rows.len(),
+            logloss_before: before,
+            logloss_after: before,
+            accepted: false,
+        };
+        if family_rows.len() >= min_samples {
+            let mut candidate = params.clone();
+            if family == "soccer" {
+                candidate.soccer_table = fit_soccer_table(&params.soccer_table, family_rows);
+            } else {
+                let k = golden_section(|k| {
+                    let mut trial = params.clone();
+                    *family_k(&mut trial, family) = k;
+                    mean_logloss(&trial, family_rows)
+                });
+                *family_k(&mut candidate, family) = k;
+            }
+            let after = mean_logloss(&candidate, family_rows);
+            if after < before {
+                params = candidate;
+                fit.logloss_after = after;
+                fit.accepted = true;
+            }
+        }
+        families.push(fit);
+    }
+    params.version = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    FitReport { params, families }
+}
+
+fn family_k<'a>(params: &'a mut WinProbParams, family: &str) -> &'a mut f64 {
+    match family {
+        "basketball" => &mut params.nba_k,
+        "nfl" => &mut params.nfl_k,
+        "baseball" => &mut params.mlb_k,
+        _ => &mut params.nhl_k,
+    }
+}
+
+fn row_game(row: &TrainingRow) -> LiveGame {
+    LiveGame {
+        event_id: String::new(),
+        sport: row.sport.clone(),
+        league: String::new(),
+        home_team: String::new(),
+        away_team: String::new(),
+        home_score: row.home_score,
+        away_score: row.away_score,
+        minute: Some(row.minute),
+        status: GameStatus::InProgress,
+        start_time: None,
+        period: None,
+        added_time: None,
+        home_red_cards: 0,
+        away_red_cards: 0,
+        power_play: None,
+    }
+}
+
+fn logloss(p: f64, home_won: bool) -> f64 {
+    let p = p.clamp(1e-6, 1.0 - 1e-6);
+    if home_won {
+        -p.ln()
+    } else {
+        -(1.0 - p).ln()
+    }
+}
+
+fn mean_logloss(params: &WinProbParams, rows: &[&TrainingRow]) -> f64 {
+    if rows.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = rows
+        .iter()
+        .map(|row| {
+            let p = sport_win_probability_with(params, &row.sport, &row_game(row), true);
+            logloss(p, row.home_won)
+        })
+        .sum();
+    total / rows.len() as f64
+}
+
+/// Minimise a unimodal `f` over `K_RANGE`.
+fn golden_section(f: impl Fn(f64) -> f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut lo, mut hi) = K_RANGE;
+    let mut x1 = hi - ratio * (hi - lo);
+    let mut x2 = lo + ratio * (hi - lo);
+    let (mut f1, mut f2) = (f(x1), f(x2));
+    for _ in 0..K_SEARCH_ITERS {
+        if f1 < f2 {
+            hi = x2;
+            x2 = x1;
+            f2 = f1;
+            x1 = hi - ratio * (hi - lo);
+            f1 = f(x1);
+        } else {
+            lo = x1;
+            x1 = x2;
+            f1 = f2;
+            x2 = lo + ratio * (hi - lo);
+            f2 = f(x2);
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// Table cells and weights a soccer row interpolates between: its goal
+/// difference row and the two minute columns around it.
+fn soccer_cells(row: &TrainingRow) -> Option<(usize, [(usize, f64); 2])> {
+    let diff = row.home_score - row.away_score;
+    if diff.abs() > 3 {
+        return None;
+    }
+    let minute = (row.minute as f64).clamp(0.0, 90.0);
+    let hi = SOCCER_MINUTES
+        .iter()
+        .position(|m| *m >= minute)
+        .unwrap_or(SOCCER_MINUTES.len() - 1)
+        .max(1);
+    let lo = hi - 1;
+    let frac = (minute - SOCCER_MINUTES[lo]) / (SOCCER_MINUTES[hi] - SOCCER_MINUTES[lo]);
+    Some(((diff + 3) as usize, [(lo, 1.0 - frac), (hi, frac)]))
+}
+
+fn fit_soccer_table(prior: &[[f64; 8]; 7], rows: &[&TrainingRow]) -> [[f64; 8]; 7] {
+    let samples: Vec<_> = rows
+        .iter()
+        .filter_map(|row| soccer_cells(row).map(|cells| (cells, row.home_won)))
+        .collect();
+    let mut table = *prior;
+    if samples.is_empty() {
+        return table;
+    }
+    for _ in 0..TABLE_ITERS {
+        let mut grad = [[0.0; 8]; 7];
+        let mut weight = [[0.0; 8]; 7];
+        for ((r, cells), home_won) in &samples {
+            let p: f64 = cells
+                .iter()
+                .map(|(c, w)| w * table[*r][*c])
+                .sum::<f64>()
+                .clamp(1e-3, 1.0 - 1e-3);
+            let y = if *home_won { 1.0 } else { 0.0 };
+            // d(-log-likelihood)/dp
+            let dp = (p - y) / (p * (1.0 - p));
+            for (c, w) in cells {
+                grad[*r][*c] += w * dp;
+                weight[*r][*c] += w;
+            }
+        }
+        for r in 0..7 {
+            for c in 0..8 {
+                let g = grad[r][c] + 2.0 * TABLE_PRIOR_ROWS * (table[r][c] - prior[r][c]);
+                // Normalise by cell support so rare and common cells move alike.
+                let step = TABLE_LEARNING_RATE / (weight[r][c] + TABLE_PRIOR_ROWS);
+                table[r][c] = (table[r][c] - step * g).clamp(0.001, 0.999);
+            }
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random stream in [0, 1).
+    fn lcg(seed: &mut u64) -> f64 {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (*seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    #[test]
+    fn recovers_a_steeper_basketball_coefficient() {
+        let truth = WinProbParams {
+            nba_k: 1.5,
+            ..WinProbParams::default()
+        };
+        let mut seed = 7;
+        let rows: Vec<TrainingRow> = (0..4000i32)
+            .map(|i| {
+                let minute = i % 48;
+                let margin = i % 21 - 10;
+                let mut row = TrainingRow {
+                    sport: "nba".into(),
+                    minute,
+                    home_score: 50 + margin.max(0),
+                    away_score: 50 + (-margin).max(0),
+                    home_won: false,
+                };
+                let p = sport_win_probability_with(&truth, "nba", &row_game(&row), true);
+                row.home_won = lcg(&mut seed) < p;
+                row
+            })
+            .collect();
+
+        let report = fit(&rows, &WinProbParams::default(), 200);
+        let nba = &report.families[0];
+        assert_eq!(nba.family, "basketball");
+        assert!(nba.accepted);
+        assert!(nba.logloss_after < nba.logloss_before);
+        assert!((report.params.nba_k - 1.5).abs() < 0.3);
+        // Families without rows keep their values.
+        assert_eq!(report.params.nfl_k, WinProbParams::default().nfl_k);
+    }
+
+    #[test]
+    fn soccer_table_moves_towards_observed_outcomes_only_above_min_samples() {
+        // Home sides always hold a one-goal half-time lead.
+        let rows: Vec<TrainingRow> = (0..300)
+            .map(|_| TrainingRow {
+                sport: "soccer".into(),
+                minute: 45,
+                home_score: 1,
+                away_score: 0,
+                home_won: true,
+            })
+            .collect();
+        let base = WinProbParams::default();
+
+        let report = fit(&rows, &base, 200);
+        assert!(report.families[0].accepted);
+        assert!(report.params.soccer_table[4][3] > base.soccer_table[4][3]);
+        assert_eq!(report.params.soccer_table[0], base.soccer_table[0]);
+        assert!(report.params.validate().is_ok());
+
+        let skipped = fit(&rows, &base, 1000);
+        assert!(!skipped.families[0].accepted);
+        assert_eq!(skipped.params.soccer_table, base.soccer_table);
+    }
+}
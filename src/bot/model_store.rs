@@ -0,0 +1,412 @@
+//! Versioned store of win-probability parameter sets.
+//!
+//! A store is a directory of parameter files, each with a SHA-256 sidecar,
+//! plus a `PROMOTED` pointer naming the version the engine should run:
+//!
+//! ```text
+//! models/
+//!   win_prob_params_20261018T120000Z.json
+//!   win_prob_params_20261018T120000Z.json.sha256
+//!   win_prob_params_manual.toml
+//!   PROMOTED
+//! ```
+//!
+//! Files may be JSON or TOML.  A file whose contents no longer match its
+//! sidecar is refused, so a half-written or hand-edited artifact can never
+//! be installed.  [`ModelSource::load`] resolves the active artifact and
+//! [`spawn_reloader`] re-runs it whenever the directory changes or the
+//! process receives SIGHUP, installing the result without restarting the
+//! engine.  Positions record the version they were opened under, suffixed
+//! with the version of the calibration set in force (archived in
+//! `calibration_versions`), so the version names the full model.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use super::win_probability::{install_params, WinProbParams};
+
+const FILE_PREFIX: &str = "win_prob_params_";
+const PROMOTED_FILE: &str = "PROMOTED";
+/// Quiet period after a file event before reloading, so that a burst of
+/// writes (file, sidecar, pointer) results in one reload.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// A parameter set read from disk and checked against its checksum.
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub params: WinProbParams,
+    pub path: PathBuf,
+    /// Hex SHA-256 of the file contents
+    pub sha256: String,
+}
+
+/// One file in the store, as listed by `list-models`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactInfo {
+    pub version: String,
+    pub path: PathBuf,
+    pub sha256: String,
+    /// Sidecar present and matching
+    pub verified: bool,
+    pub promoted: bool,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// Expected checksum from `path`'s sidecar, if it has one.
+fn read_sidecar(path: &Path) -> anyhow::Result<Option<String>> {
+    match std::fs::read_to_string(sidecar_path(path)) {
+        Ok(raw) => Ok(raw.split_whitespace().next().map(str::to_ascii_lowercase)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Read a JSON or TOML parameter file, verifying its sidecar checksum when
+/// present (always, for files inside a store).
+pub fn load_artifact(path: &Path, require_checksum: bool) -> anyhow::Result<Artifact> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read model parameters {}", path.display()))?;
+    let sha256 = sha256_hex(&bytes);
+    match read_sidecar(path)? {
+        Some(expected) if expected != sha256 => anyhow::bail!(
+            "checksum mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected,
+            sha256
+        ),
+        None if require_checksum => {
+            anyhow::bail!("{} has no .sha256 checksum file", path.display())
+        }
+        _ => {}
+    }
+    let text =
+        std::str::from_utf8(&bytes).with_context(|| format!("{} is not UTF-8", path.display()))?;
+    let params: WinProbParams = if path.extension().is_some_and(|e| e == "toml") {
+        toml::from_str(text)
+            .with_context(|| format!("Invalid model parameters in {}", path.display()))?
+    } else {
+        serde_json::from_str(text)
+            .with_context(|| format!("Invalid model parameters in {}", path.display()))?
+    };
+    params.validate()?;
+    Ok(Artifact {
+        params,
+        path: path.to_path_buf(),
+        sha256,
+    })
+}
+
+/// Directory of versioned parameter files.
+#[derive(Debug, Clone)]
+pub struct ModelStore {
+    dir: PathBuf,
+}
+
+impl ModelStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ModelStore { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write `params` as a new JSON version with its checksum sidecar.
+    pub fn save(&self, params: &WinProbParams) -> anyhow::Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self
+            .dir
+            .join(format!("{}{}.json", FILE_PREFIX, params.version));
+        if path.exists() {
+            anyhow::bail!("model version {} already exists", params.version);
+        }
+        let body = serde_json::to_string_pretty(params)?;
+        // Sidecar first: a file without one is never loaded from the store,
+        // so a crash in between cannot leave an unverifiable artifact behind.
+        std::fs::write(
+            sidecar_path(&path),
+            format!("{}\n", sha256_hex(body.as_bytes())),
+        )?;
+        std::fs::write(&path, body)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// File holding `version`, JSON preferred over TOML.
+    fn version_path(&self, version: &str) -> Option<PathBuf> {
+        ["json", "toml"]
+            .iter()
+            .map(|ext| self.dir.join(format!("{}{}.{}", FILE_PREFIX, version, ext)))
+            .find(|p| p.exists())
+    }
+
+    pub fn promoted_version(&self) -> anyhow::Result<Option<String>> {
+        match std::fs::read_to_string(self.dir.join(PROMOTED_FILE)) {
+            Ok(raw) => Ok(Some(raw.trim().to_string()).filter(|v| !v.is_empty())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Point `PROMOTED` at `version` once it loads cleanly.
+    pub fn promote(&self, version: &str) -> anyhow::Result<Artifact> {
+        let artifact = self.load_version(version)?;
+        if artifact.params.version != version {
+            anyhow::bail!(
+                "{} declares version {}",
+                artifact.path.display(),
+                artifact.params.version
+            );
+        }
+        // Write-then-rename so the watcher never sees a partial pointer.
+        let tmp = self.dir.join(format!("{}.tmp", PROMOTED_FILE));
+        std::fs::write(&tmp, format!("{}\n", version))?;
+        std::fs::rename(&tmp, self.dir.join(PROMOTED_FILE))?;
+        Ok(artifact)
+    }
+
+    /// A stored version, verified against its checksum.
+    pub fn load_version(&self, version: &str) -> anyhow::Result<Artifact> {
+        let path = self
+            .version_path(version)
+            .with_context(|| format!("no model version {} in {}", version, self.dir.display()))?;
+        load_artifact(&path, true)
+    }
+
+    /// The promoted artifact, or `None` when nothing has been promoted.
+    pub fn load_promoted(&self) -> anyhow::Result<Option<Artifact>> {
+        let Some(version) = self.promoted_version()? else {
+            return Ok(None);
+        };
+        let path = self.version_path(&version).with_context(|| {
+            format!(
+                "promoted model version {} not found in {}",
+                version,
+                self.dir.display()
+            )
+        })?;
+        load_artifact(&path, true).map(Some)
+    }
+
+    /// Every parameter file in the store, oldest version first.
+    pub fn list(&self) -> anyhow::Result<Vec<ArtifactInfo>> {
+        let promoted = self.promoted_version()?;
+        let mut infos = Vec::new();
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(infos),
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Some(version) = name
+                .strip_prefix(FILE_PREFIX)
+                .and_then(|rest| rest.strip_suffix(".json").or(rest.strip_suffix(".toml")))
+            else {
+                continue;
+            };
+            let sha256 = sha256_hex(&std::fs::read(&path)?);
+            let verified = read_sidecar(&path)?.is_some_and(|expected| expected == sha256);
+            infos.push(ArtifactInfo {
+                version: version.to_string(),
+                promoted: promoted.as_deref() == Some(version),
+                path,
+                sha256,
+                verified,
+            });
+        }
+        infos.sort_by(|a, b| a.version.cmp(&b.version));
+        Ok(infos)
+    }
+}
+
+/// Where the engine's parameters come from.
+#[derive(Debug, Clone)]
+pub enum ModelSource {
+    /// A single file pinned by `WIN_PROB_PARAMS_PATH`
+    Pinned(PathBuf),
+    /// The promoted version of a store
+    Store(ModelStore),
+}
+
+impl ModelSource {
+    /// Resolve the active artifact.  `None` means "use the built-in defaults".
+    pub fn load(&self) -> anyhow::Result<Option<Artifact>> {
+        match self {
+            ModelSource::Pinned(path) => load_artifact(path, false).map(Some),
+            ModelSource::Store(store) => store.load_promoted(),
+        }
+    }
+
+    /// Directory to watch for changes.  A pinned file's parent is watched
+    /// rather than the file, since editors replace files by renaming.
+    fn watch_dir(&self) -> PathBuf {
+        match self {
+            ModelSource::Pinned(path) => match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => PathBuf::from("."),
+            },
+            ModelSource::Store(store) => store.dir().to_path_buf(),
+        }
+    }
+}
+
+/// Reload `source` whenever its directory changes or the process receives
+/// SIGHUP, installing the new parameters when the checksum differs from
+/// `current`.  A version that fails to load or verify is logged and the
+/// running parameters are kept.
+pub fn spawn_reloader(source: ModelSource, mut current: Option<String>) {
+    tokio::spawn(async move {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watch_dir = source.watch_dir();
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if res.is_ok() {
+                let _ = tx.send(());
+            }
+        })
+        .and_then(|mut w| w.watch(&watch_dir, RecursiveMode::NonRecursive).map(|_| w));
+        let watcher = match watcher {
+            Ok(w) => Some(w),
+            Err(e) => {
+                warn!(
+                    "Not watching {} for model changes ({}); SIGHUP still reloads",
+                    watch_dir.display(),
+                    e
+                );
+                None
+            }
+        };
+        // Either trigger alone keeps the reloader useful.
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                warn!("SIGHUP model reload unavailable: {}", e);
+                None
+            }
+        };
+        if watcher.is_none() && hangup.is_none() {
+            return;
+        }
+
+        loop {
+            let sighup = async {
+                match hangup.as_mut() {
+                    Some(hangup) => hangup.recv().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                Some(()) = sighup => info!("SIGHUP received; reloading model parameters"),
+                Some(()) = rx.recv() => {
+                    tokio::time::sleep(RELOAD_DEBOUNCE).await;
+                    while rx.try_recv().is_ok() {}
+                }
+            }
+            match source.load() {
+                Ok(Some(artifact)) if current.as_deref() != Some(artifact.sha256.as_str()) => {
+                    info!(
+                        "Win-probability model {} reloaded from {}",
+                        artifact.params.version,
+                        artifact.path.display()
+                    );
+                    current = Some(artifact.sha256);
+                    install_params(artifact.params);
+                }
+                Ok(None) if current.is_some() => {
+                    info!("No promoted model; reverting to built-in win-probability parameters");
+                    current = None;
+                    install_params(WinProbParams::default());
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Model reload failed, keeping current parameters: {:#}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> ModelStore {
+        let dir = std::env::temp_dir().join(format!(
+            "livesports-model-store-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        ModelStore::new(dir)
+    }
+
+    fn params(version: &str, nba_k: f64) -> WinProbParams {
+        WinProbParams {
+            version: version.into(),
+            nba_k,
+            ..WinProbParams::default()
+        }
+    }
+
+    #[test]
+    fn promotes_and_loads_saved_versions() {
+        let store = temp_store("promote");
+        assert!(store.load_promoted().unwrap().is_none());
+
+        store.save(&params("v1", 0.4)).unwrap();
+        store.save(&params("v2", 0.6)).unwrap();
+        assert!(store.save(&params("v2", 0.7)).is_err());
+        assert!(store.promote("v3").is_err());
+
+        store.promote("v1").unwrap();
+        let active = store.load_promoted().unwrap().unwrap();
+        assert_eq!(active.params, params("v1", 0.4));
+
+        store.promote("v2").unwrap();
+        let listed = store.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().all(|a| a.verified));
+        assert!(listed[1].promoted && !listed[0].promoted);
+        let _ = std::fs::remove_dir_all(store.dir());
+    }
+
+    #[test]
+    fn refuses_tampered_or_unchecksummed_files() {
+        let store = temp_store("tamper");
+        let path = store.save(&params("v1", 0.4)).unwrap();
+        store.promote("v1").unwrap();
+
+        let edited = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("0.4", "0.9");
+        std::fs::write(&path, edited).unwrap();
+        assert!(store.load_promoted().is_err());
+
+        // TOML without a sidecar loads only when pinned.
+        let toml_path = store.dir().join("manual.toml");
+        std::fs::write(&toml_path, toml::to_string(&params("manual", 0.5)).unwrap()).unwrap();
+        assert!(load_artifact(&toml_path, true).is_err());
+        let pinned = ModelSource::Pinned(toml_path).load().unwrap().unwrap();
+        assert_eq!(pinned.params.nba_k, 0.5);
+        let _ = std::fs::remove_dir_all(store.dir());
+    }
+}
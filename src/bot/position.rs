@@ -1,4 +1,5 @@
 use crate::db::models::Position;
+use std::str::FromStr;
 
 /// Decision made by the position manager for a live position
 #[derive(Debug, Clone, PartialEq)]
@@ -9,6 +10,41 @@ pub enum PositionAction {
     TakeProfit { exit_price: f64, pnl: f64 },
     /// Exit at a stop-loss
     StopLoss { exit_price: f64, pnl: f64 },
+    /// Exit at a trailing stop below the high-water mark
+    TrailingStop { exit_price: f64, pnl: f64 },
+    /// Exit at the break-even stop after the target was partly reached
+    BreakevenStop { exit_price: f64, pnl: f64 },
+    /// Exit because the model says the remaining edge is gone
+    ModelExit {
+        exit_price: f64,
+        pnl: f64,
+        reason: ModelExitReason,
+    },
+}
+
+/// Why a model-driven exit fired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelExitReason {
+    /// Market price has caught up with fair value (net of costs).
+    FairValueReached,
+    /// Market price is above fair value: holding is now negative-edge.
+    EdgeFlipped,
+}
+
+impl ModelExitReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelExitReason::FairValueReached => "fair_value_reached",
+            ModelExitReason::EdgeFlipped => "edge_flipped",
+        }
+    }
+}
+
+fn net_pnl(pos: &Position, current_price: f64) -> f64 {
+    let shares = pos.size_usd / pos.entry_price; // number of outcome tokens held
+    let gross_pnl = shares * current_price - pos.size_usd;
+    let estimated_cost = pos.size_usd * (pos.estimated_round_trip_cost_bps / 10_000.0);
+    gross_pnl - estimated_cost
 }
 
 /// Evaluate whether to close an open position based on the current market price.
@@ -19,11 +55,7 @@ pub enum PositionAction {
 ///
 /// Returns a `PositionAction` indicating what to do.
 pub fn evaluate_position(pos: &Position, current_price: f64) -> PositionAction {
-    let shares = pos.size_usd / pos.entry_price; // number of outcome tokens held
-    let current_value = shares * current_price;
-    let gross_pnl = current_value - pos.size_usd;
-    let estimated_cost = pos.size_usd * (pos.estimated_round_trip_cost_bps / 10_000.0);
-    let pnl = gross_pnl - estimated_cost;
+    let pnl = net_pnl(pos, current_price);
 
     if current_price >= pos.take_profit_price {
         PositionAction::TakeProfit {
@@ -40,6 +72,182 @@ pub fn evaluate_position(pos: &Position, current_price: f64) -> PositionAction {
     }
 }
 
+/// Model-driven exit check.
+///
+/// # Arguments
+/// * `pos`           – The open position.
+/// * `current_price` – Current market price of the held outcome token.
+/// * `fair_value`    – Model probability that the held outcome pays out.
+/// * `cost_edge`     – Round-trip trading cost in probability units.
+/// * `buffer`        – Extra margin below fair value at which we already exit.
+///
+/// Exits when the price exceeds fair value (edge flipped) or comes within
+/// costs + buffer of it (nothing left worth holding for).
+pub fn evaluate_model_exit(
+    pos: &Position,
+    current_price: f64,
+    fair_value: f64,
+    cost_edge: f64,
+    buffer: f64,
+) -> PositionAction {
+    let reason = if current_price > fair_value {
+        ModelExitReason::EdgeFlipped
+    } else if current_price >= fair_value - cost_edge - buffer {
+        ModelExitReason::FairValueReached
+    } else {
+        return PositionAction::Hold;
+    };
+    PositionAction::ModelExit {
+        exit_price: current_price,
+        pnl: net_pnl(pos, current_price),
+        reason,
+    }
+}
+
+/// Named exit policy stored per position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitPolicy {
+    /// Static stop-loss / take-profit from `compute_levels`.
+    Fixed,
+    /// Stop ratchets up with the high-water mark; no take-profit cap.
+    Trailing,
+    /// Fixed bands that tighten towards entry as game time runs out.
+    TimeDecay,
+    /// Fixed bands, with the stop moved to break-even once the target is
+    /// partly reached.
+    Breakeven,
+    /// Time-decaying bands plus trailing and break-even stops.
+    Composite,
+}
+
+impl ExitPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitPolicy::Fixed => "fixed",
+            ExitPolicy::Trailing => "trailing",
+            ExitPolicy::TimeDecay => "time_decay",
+            ExitPolicy::Breakeven => "breakeven",
+            ExitPolicy::Composite => "composite",
+        }
+    }
+}
+
+impl FromStr for ExitPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "fixed" => Ok(ExitPolicy::Fixed),
+            "trailing" => Ok(ExitPolicy::Trailing),
+            "time_decay" => Ok(ExitPolicy::TimeDecay),
+            "breakeven" => Ok(ExitPolicy::Breakeven),
+            "composite" => Ok(ExitPolicy::Composite),
+            other => anyhow::bail!(
+                "unknown exit policy '{}' (expected fixed, trailing, time_decay, breakeven or composite)",
+                other
+            ),
+        }
+    }
+}
+
+/// Tunables shared by all exit policies.
+#[derive(Debug, Clone, Copy)]
+pub struct ExitPolicyParams {
+    /// Trailing stop distance as a fraction of the high-water price.
+    pub trailing_stop_fraction: f64,
+    /// Band width left at the end of the game, as a fraction of the entry width.
+    pub time_decay_min_band_fraction: f64,
+    /// Fraction of the entry→take-profit distance after which the stop moves
+    /// to break-even.
+    pub breakeven_trigger_fraction: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StopKind {
+    Fixed,
+    Trailing,
+    Breakeven,
+}
+
+/// Effective (stop, take, stop kind) for a position under its exit policy.
+///
+/// `game_progress` is the fraction of regulation time elapsed (0.0–1.0), when
+/// known; time decay is skipped without it.
+fn effective_levels(
+    pos: &Position,
+    policy: ExitPolicy,
+    params: &ExitPolicyParams,
+    game_progress: Option<f64>,
+) -> (f64, f64, StopKind) {
+    let entry = pos.entry_price;
+    let mut stop = pos.stop_loss_price;
+    let mut take = pos.take_profit_price;
+    let mut kind = StopKind::Fixed;
+    let high_water = pos.high_water_price.unwrap_or(entry).max(entry);
+
+    if matches!(policy, ExitPolicy::TimeDecay | ExitPolicy::Composite) {
+        if let Some(progress) = game_progress {
+            let scale =
+                1.0 - (1.0 - params.time_decay_min_band_fraction) * progress.clamp(0.0, 1.0);
+            stop = entry - (entry - pos.stop_loss_price) * scale;
+            take = entry + (pos.take_profit_price - entry) * scale;
+        }
+    }
+
+    if matches!(policy, ExitPolicy::Trailing | ExitPolicy::Composite) {
+        let trailing = high_water * (1.0 - params.trailing_stop_fraction);
+        if trailing > stop {
+            stop = trailing;
+            kind = StopKind::Trailing;
+        }
+        if policy == ExitPolicy::Trailing {
+            take = f64::INFINITY;
+        }
+    }
+
+    if matches!(policy, ExitPolicy::Breakeven | ExitPolicy::Composite) {
+        let trigger = entry + (pos.take_profit_price - entry) * params.breakeven_trigger_fraction;
+        // Break-even covers the round-trip cost, not just the entry price.
+        let breakeven = entry * (1.0 + pos.estimated_round_trip_cost_bps / 10_000.0);
+        if high_water >= trigger && breakeven > stop {
+            stop = breakeven;
+            kind = StopKind::Breakeven;
+        }
+    }
+
+    (stop, take, kind)
+}
+
+/// Evaluate a position against its named exit policy.
+///
+/// Unknown policy names fall back to [`ExitPolicy::Fixed`].
+pub fn evaluate_exit_policy(
+    pos: &Position,
+    current_price: f64,
+    params: &ExitPolicyParams,
+    game_progress: Option<f64>,
+) -> PositionAction {
+    let policy = pos.exit_policy.parse().unwrap_or(ExitPolicy::Fixed);
+    if policy == ExitPolicy::Fixed {
+        return evaluate_position(pos, current_price);
+    }
+    let (stop, take, kind) = effective_levels(pos, policy, params, game_progress);
+    let exit_price = current_price;
+    let pnl = net_pnl(pos, current_price);
+
+    if current_price >= take {
+        PositionAction::TakeProfit { exit_price, pnl }
+    } else if current_price <= stop {
+        match kind {
+            StopKind::Fixed => PositionAction::StopLoss { exit_price, pnl },
+            StopKind::Trailing => PositionAction::TrailingStop { exit_price, pnl },
+            StopKind::Breakeven => PositionAction::BreakevenStop { exit_price, pnl },
+        }
+    } else {
+        PositionAction::Hold
+    }
+}
+
 /// Build stop-loss and take-profit prices for a new YES bet.
 ///
 /// # Arguments
@@ -60,6 +268,22 @@ pub fn compute_levels(
     (stop_loss_price, take_profit_price)
 }
 
+/// Share-weighted average entry price after buying `add_size_usd` more at
+/// `add_price` on top of `size_usd` held at `entry_price`.
+pub fn average_entry_price(
+    size_usd: f64,
+    entry_price: f64,
+    add_size_usd: f64,
+    add_price: f64,
+) -> f64 {
+    let shares = size_usd / entry_price + add_size_usd / add_price;
+    if shares > 0.0 {
+        (size_usd + add_size_usd) / shares
+    } else {
+        entry_price
+    }
+}
+
 // Win probability estimation has moved to `super::win_probability` with
 // sport-specific models (soccer lookup table, NBA/NFL/MLB logistic, etc.).
 
@@ -67,37 +291,13 @@ pub fn compute_levels(
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
-    use chrono::Utc;
 
     fn make_position(entry: f64, stop: f64, take: f64) -> Position {
         Position {
-            id: None,
-            market_id: "mkt1".into(),
-            asset_id: None,
-            outcome: "YES".into(),
-            side: "buy".into(),
-            size_usd: 10.0,
-            entry_price: entry,
-            entry_price_source: None,
-            entry_model_prob_raw: None,
-            entry_model_prob: None,
-            entry_ws_age_ms: None,
-            estimated_round_trip_cost_bps: 0.0,
             stop_loss_price: stop,
             take_profit_price: take,
-            status: "open".into(),
-            opened_at: Utc::now(),
-            closed_at: None,
-            exit_price: None,
-            pnl: None,
-            dry_run: true,
-            ws_used_count: 0,
-            rest_fallback_count: 0,
-            last_ws_age_ms: None,
-            sport: None,
-            league: None,
-            event_name: None,
-            market_slug: None,
+            high_water_price: None,
+            ..Position::new_open("mkt1", "YES", "directional", 10.0, entry, true)
         }
     }
 
@@ -135,6 +335,13 @@ mod tests {
         assert_relative_eq!(take, 0.65, epsilon = 1e-9);
     }
 
+    #[test]
+    fn test_average_entry_price_is_share_weighted() {
+        // $10 at 0.50 (20 shares) + $12 at 0.60 (20 shares) → 40 shares for $22.
+        assert_relative_eq!(average_entry_price(10.0, 0.5, 12.0, 0.6), 0.55);
+        assert_relative_eq!(average_entry_price(10.0, 0.5, 0.0, 0.6), 0.5);
+    }
+
     #[test]
     fn test_compute_levels_take_profit_capped() {
         let (_, take) = compute_levels(0.95, 0.5, 0.3);
@@ -155,6 +362,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_model_exit_actions() {
+        let pos = make_position(0.5, 0.3, 0.7);
+        // Plenty of edge left: hold.
+        assert_eq!(
+            evaluate_model_exit(&pos, 0.55, 0.70, 0.02, 0.0),
+            PositionAction::Hold
+        );
+        // Price within costs of fair value: take it.
+        match evaluate_model_exit(&pos, 0.69, 0.70, 0.02, 0.0) {
+            PositionAction::ModelExit { reason, pnl, .. } => {
+                assert_eq!(reason, ModelExitReason::FairValueReached);
+                assert!(pnl > 0.0);
+            }
+            other => panic!("Expected ModelExit, got {:?}", other),
+        }
+        // Model now values the outcome below the market: edge flipped.
+        match evaluate_model_exit(&pos, 0.45, 0.35, 0.02, 0.0) {
+            PositionAction::ModelExit { reason, .. } => {
+                assert_eq!(reason, ModelExitReason::EdgeFlipped)
+            }
+            other => panic!("Expected ModelExit, got {:?}", other),
+        }
+    }
+
+    fn params() -> ExitPolicyParams {
+        ExitPolicyParams {
+            trailing_stop_fraction: 0.2,
+            time_decay_min_band_fraction: 0.25,
+            breakeven_trigger_fraction: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_exit_policy_round_trip() {
+        for name in ["fixed", "trailing", "time_decay", "breakeven", "composite"] {
+            let policy: ExitPolicy = name.parse().unwrap();
+            assert_eq!(policy.as_str(), name);
+        }
+        assert!("sideways".parse::<ExitPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_trailing_stop_ratchets_with_high_water() {
+        let mut pos = make_position(0.5, 0.25, 0.65);
+        pos.exit_policy = "trailing".into();
+        pos.high_water_price = Some(0.8);
+        // Fixed take-profit no longer caps the trade.
+        assert_eq!(
+            evaluate_exit_policy(&pos, 0.7, &params(), None),
+            PositionAction::Hold
+        );
+        // 20% below the 0.80 high-water mark.
+        match evaluate_exit_policy(&pos, 0.63, &params(), None) {
+            PositionAction::TrailingStop { pnl, .. } => assert!(pnl > 0.0),
+            other => panic!("Expected TrailingStop, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_time_decay_tightens_bands_late() {
+        let mut pos = make_position(0.5, 0.25, 0.65);
+        pos.exit_policy = "time_decay".into();
+        assert_eq!(
+            evaluate_exit_policy(&pos, 0.6, &params(), Some(0.1)),
+            PositionAction::Hold
+        );
+        // Near full time the take-profit band has shrunk to ~0.54.
+        match evaluate_exit_policy(&pos, 0.6, &params(), Some(0.95)) {
+            PositionAction::TakeProfit { .. } => {}
+            other => panic!("Expected TakeProfit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_breakeven_stop_after_partial_target() {
+        let mut pos = make_position(0.5, 0.25, 0.7);
+        pos.exit_policy = "breakeven".into();
+        // Not yet triggered: a dip to 0.45 is within the fixed band.
+        pos.high_water_price = Some(0.55);
+        assert_eq!(
+            evaluate_exit_policy(&pos, 0.45, &params(), None),
+            PositionAction::Hold
+        );
+        // Reached halfway to target, then fell back to entry.
+        pos.high_water_price = Some(0.61);
+        match evaluate_exit_policy(&pos, 0.5, &params(), None) {
+            PositionAction::BreakevenStop { .. } => {}
+            other => panic!("Expected BreakevenStop, got {:?}", other),
+        }
+    }
+
     // Win probability tests are now in super::win_probability::tests
     // with comprehensive sport-specific test coverage.
 }
@@ -0,0 +1,360 @@
+//! Per-sport and per-league strategy profiles.
+//!
+//! Strategy thresholds are global flags by default.  A profile file
+//! (`STRATEGY_PROFILES_PATH`, TOML or JSON) overrides them for a sport and,
+//! below that, for a league.  Unset parameters inherit from the level above:
+//! `soccer/efl league two` falls back to `soccer`, then to the global flags.
+//!
+//! ```toml
+//! [sports.basketball]
+//! min_edge = 0.02
+//! stop_loss_fraction = 0.25
+//!
+//! [leagues."soccer/efl league two"]
+//! kelly_fraction = 0.1
+//! ```
+//!
+//! Keys are matched case-insensitively, the same way as calibration levels.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::config::Config;
+
+use super::calibration::level_keys;
+
+/// Profile key of the global flags.
+pub const GLOBAL_PROFILE: &str = "*";
+
+/// Parameters a profile may override; `None` inherits.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileOverrides {
+    pub min_edge: Option<f64>,
+    pub kelly_fraction: Option<f64>,
+    pub stop_loss_fraction: Option<f64>,
+    pub take_profit_fraction: Option<f64>,
+    pub exit_policy: Option<String>,
+    pub trailing_stop_fraction: Option<f64>,
+    pub time_decay_min_band_fraction: Option<f64>,
+    pub breakeven_trigger_fraction: Option<f64>,
+    pub partial_take_profit_fraction: Option<f64>,
+    pub partial_take_profit_trigger: Option<f64>,
+    pub latency_max_score_age_ms: Option<u64>,
+    pub latency_min_expected_move: Option<f64>,
+    pub latency_min_residual_move: Option<f64>,
+    pub latency_max_priced_in_ratio: Option<f64>,
+    pub max_entry_quote_divergence: Option<f64>,
+    pub adaptive_min_edge_max_addon: Option<f64>,
+    pub max_positions_per_event: Option<u32>,
+    pub pyramiding_enabled: Option<bool>,
+    pub max_adds_per_position: Option<u32>,
+    pub model_exit_edge_buffer: Option<f64>,
+    /// Minimum model probability shift for a score event to be traded
+    pub probability_delta_threshold: Option<f64>,
+}
+
+impl ProfileOverrides {
+    fn apply(&self, profile: &mut StrategyProfile) {
+        let config = &mut profile.config;
+        if let Some(v) = self.min_edge {
+            config.min_edge = v;
+        }
+        if let Some(v) = self.kelly_fraction {
+            config.kelly_fraction = v;
+        }
+        if let Some(v) = self.stop_loss_fraction {
+            config.stop_loss_fraction = v;
+        }
+        if let Some(v) = self.take_profit_fraction {
+            config.take_profit_fraction = v;
+        }
+        if let Some(v) = &self.exit_policy {
+            config.exit_policy = v.clone();
+        }
+        if let Some(v) = self.trailing_stop_fraction {
+            config.trailing_stop_fraction = v;
+        }
+        if let Some(v) = self.time_decay_min_band_fraction {
+            config.time_decay_min_band_fraction = v;
+        }
+        if let Some(v) = self.breakeven_trigger_fraction {
+            config.breakeven_trigger_fraction = v;
+        }
+        if let Some(v) = self.partial_take_profit_fraction {
+            config.partial_take_profit_fraction = v;
+        }
+        if let Some(v) = self.partial_take_profit_trigger {
+            config.partial_take_profit_trigger = v;
+        }
+        if let Some(v) = self.latency_max_score_age_ms {
+            config.latency_max_score_age_ms = v;
+        }
+        if let Some(v) = self.latency_min_expected_move {
+            config.latency_min_expected_move = v;
+        }
+        if let Some(v) = self.latency_min_residual_move {
+            config.latency_min_residual_move = v;
+        }
+        if let Some(v) = self.latency_max_priced_in_ratio {
+            config.latency_max_priced_in_ratio = v;
+        }
+        if let Some(v) = self.max_entry_quote_divergence {
+            config.max_entry_quote_divergence = v;
+        }
+        if let Some(v) = self.adaptive_min_edge_max_addon {
+            config.adaptive_min_edge_max_addon = v;
+        }
+        if let Some(v) = self.max_positions_per_event {
+            config.max_positions_per_event = v;
+        }
+        if let Some(v) = self.pyramiding_enabled {
+            config.pyramiding_enabled = v;
+        }
+        if let Some(v) = self.max_adds_per_position {
+            config.max_adds_per_position = v;
+        }
+        if let Some(v) = self.model_exit_edge_buffer {
+            config.model_exit_edge_buffer = v;
+        }
+        if let Some(v) = self.probability_delta_threshold {
+            profile.probability_delta_threshold = Some(v);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    #[serde(default)]
+    sports: BTreeMap<String, ProfileOverrides>,
+    /// Keyed `sport/league`
+    #[serde(default)]
+    leagues: BTreeMap<String, ProfileOverrides>,
+}
+
+/// Effective strategy parameters for one profile level.
+#[derive(Debug, Clone)]
+pub struct StrategyProfile {
+    /// [`GLOBAL_PROFILE`], a sport or `sport/league`
+    pub key: String,
+    /// The global config with this level's overrides applied
+    pub config: Config,
+    /// `None` uses the built-in per-sport threshold
+    pub probability_delta_threshold: Option<f64>,
+}
+
+/// Resolved profiles, looked up league first, then sport, then global.
+#[derive(Debug, Clone)]
+pub struct StrategyProfiles {
+    global: Arc<StrategyProfile>,
+    profiles: HashMap<String, Arc<StrategyProfile>>,
+    /// Overrides as loaded, kept to re-derive over changed globals
+    file: Arc<ProfileFile>,
+}
+
+impl StrategyProfiles {
+    /// Only the global flags.
+    pub fn global(config: &Config) -> Self {
+        StrategyProfiles {
+            global: Arc::new(StrategyProfile {
+                key: GLOBAL_PROFILE.to_string(),
+                config: config.clone(),
+                probability_delta_threshold: None,
+            }),
+            profiles: HashMap::new(),
+            file: Default::default(),
+        }
+    }
+
+    /// Profiles from `config.strategy_profiles_path`, or only the globals.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        match config.strategy_profiles_path.as_deref() {
+            Some(path) => Self::load(Path::new(path), config),
+            None => Ok(Self::global(config)),
+        }
+    }
+
+    /// Load and validate a TOML or JSON profile file over `config`.
+    pub fn load(path: &Path, config: &Config) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read strategy profiles {}", path.display()))?;
+        let file: ProfileFile = if path.extension().is_some_and(|e| e == "json") {
+            serde_json::from_str(&text)
+                .with_context(|| format!("Invalid strategy profiles in {}", path.display()))?
+        } else {
+            toml::from_str(&text)
+                .with_context(|| format!("Invalid strategy profiles in {}", path.display()))?
+        };
+        Self::from_file(file, config)
+    }
+
+    fn from_file(file: ProfileFile, config: &Config) -> anyhow::Result<Self> {
+        let mut resolved = Self::global(config);
+        resolved.file = Arc::new(file.clone());
+        for (sport, overrides) in &file.sports {
+            let key = normalize_key(sport);
+            if key.is_empty() || key.contains('/') {
+                anyhow::bail!("invalid sport profile key '{}'", sport);
+            }
+            let profile = Self::derive(&resolved.global, key, overrides)?;
+            resolved.profiles.insert(profile.key.clone(), profile);
+        }
+        for (league, overrides) in &file.leagues {
+            let key = normalize_key(league);
+            let Some((sport, name)) = key.split_once('/') else {
+                anyhow::bail!("league profile '{}' must be keyed 'sport/league'", league);
+            };
+            if sport.is_empty() || name.is_empty() {
+                anyhow::bail!("league profile '{}' must be keyed 'sport/league'", league);
+            }
+            let parent = resolved
+                .profiles
+                .get(sport)
+                .cloned()
+                .unwrap_or_else(|| resolved.global.clone());
+            let profile = Self::derive(&parent, key, overrides)?;
+            resolved.profiles.insert(profile.key.clone(), profile);
+        }
+        Ok(resolved)
+    }
+
+    fn derive(
+        parent: &StrategyProfile,
+        key: String,
+        overrides: &ProfileOverrides,
+    ) -> anyhow::Result<Arc<StrategyProfile>> {
+        let mut profile = StrategyProfile {
+            key,
+            ..parent.clone()
+        };
+        overrides.apply(&mut profile);
+        profile
+            .config
+            .validate()
+            .with_context(|| format!("strategy profile '{}'", profile.key))?;
+        if let Some(threshold) = profile.probability_delta_threshold {
+            if !(0.0..1.0).contains(&threshold) {
+                anyhow::bail!(
+                    "strategy profile '{}': probability_delta_threshold must be in [0, 1)",
+                    profile.key
+                );
+            }
+        }
+        Ok(Arc::new(profile))
+    }
+
+    /// The same overrides re-derived over changed global flags, without
+    /// re-reading the file.
+    pub fn rebase(&self, config: &Config) -> anyhow::Result<Self> {
+        Self::from_file((*self.file).clone(), config)
+    }
+
+    /// Most specific profile for `sport` and `league`.
+    pub fn resolve(&self, sport: &str, league: Option<&str>) -> Arc<StrategyProfile> {
+        level_keys(sport, league, None)
+            .iter()
+            .rev()
+            .find_map(|key| self.profiles.get(key))
+            .unwrap_or(&self.global)
+            .clone()
+    }
+
+    /// Keys of the configured sport and league profiles, sorted.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    key.split('/')
+        .map(|part| part.trim().to_lowercase())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn config() -> Config {
+        Config::parse_from(["livesports-bot", "--dry-run"])
+    }
+
+    fn profiles(text: &str) -> anyhow::Result<StrategyProfiles> {
+        StrategyProfiles::from_file(toml::from_str(text)?, &config())
+    }
+
+    #[test]
+    fn leagues_inherit_from_their_sport_then_global() {
+        let profiles = profiles(
+            r#"
+            [sports.Basketball]
+            min_edge = 0.02
+            stop_loss_fraction = 0.25
+            probability_delta_threshold = 0.01
+
+            [leagues."soccer/EFL League Two"]
+            kelly_fraction = 0.1
+
+            [leagues."basketball/nba"]
+            take_profit_fraction = 0.3
+            "#,
+        )
+        .unwrap();
+        let global = config();
+
+        let nba = profiles.resolve("basketball", Some("NBA"));
+        assert_eq!(nba.key, "basketball/nba");
+        assert_eq!(nba.config.min_edge, 0.02);
+        assert_eq!(nba.config.stop_loss_fraction, 0.25);
+        assert_eq!(nba.config.take_profit_fraction, 0.3);
+        assert_eq!(nba.probability_delta_threshold, Some(0.01));
+
+        let euroleague = profiles.resolve("basketball", Some("Euroleague"));
+        assert_eq!(euroleague.key, "basketball");
+        assert_eq!(
+            euroleague.config.take_profit_fraction,
+            global.take_profit_fraction
+        );
+
+        let league_two = profiles.resolve("soccer", Some(" efl league two "));
+        assert_eq!(league_two.config.kelly_fraction, 0.1);
+        assert_eq!(league_two.config.min_edge, global.min_edge);
+
+        let tennis = profiles.resolve("tennis", None);
+        assert_eq!(tennis.key, GLOBAL_PROFILE);
+        assert_eq!(tennis.probability_delta_threshold, None);
+    }
+
+    #[test]
+    fn rejects_invalid_overrides_and_keys() {
+        assert!(profiles("[sports.nba]\nkelly_fraction = 1.5\n").is_err());
+        assert!(profiles("[sports.nba]\nmin_edg = 0.02\n").is_err());
+        assert!(profiles("[leagues.premier]\nmin_edge = 0.02\n").is_err());
+        assert!(profiles("[sports.nba]\nprobability_delta_threshold = -0.1\n").is_err());
+    }
+
+    #[test]
+    fn rebase_keeps_overrides_over_new_globals() {
+        let profiles = profiles("[sports.basketball]\nmin_edge = 0.02\n").unwrap();
+        let mut patched = config();
+        patched.min_edge = 0.09;
+        patched.kelly_fraction = 0.05;
+        let rebased = profiles.rebase(&patched).unwrap();
+
+        let nba = rebased.resolve("basketball", Some("nba"));
+        assert_eq!(nba.config.min_edge, 0.02);
+        assert_eq!(nba.config.kelly_fraction, 0.05);
+        let global = rebased.resolve("tennis", None);
+        assert_eq!(global.config.min_edge, 0.09);
+        assert_eq!(rebased.keys(), vec!["basketball"]);
+    }
+}
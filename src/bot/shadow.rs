@@ -0,0 +1,261 @@
+//! Shadow evaluation of challenger models.
+//!
+//! Challengers are alternative win-probability parameter sets and/or
+//! calibration settings that are priced on every score event next to the
+//! live (champion) model but never trade.  For each winner market on the
+//! fixture, every model's probability, best-side edge and the trade it
+//! would have taken are written to `shadow_evaluations`; once the final
+//! score is known the rows are labelled and can be compared on log loss,
+//! Brier score and simulated hold-to-resolution PnL.
+//!
+//! The hypothetical trade uses the entry threshold and Kelly sizing of the
+//! live strategy but none of its latency, exposure or risk gates, so the
+//! champion is evaluated under the same simplified rule for a like-for-like
+//! comparison.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::db::ShadowEvaluation;
+
+use super::calibration::score;
+use super::kelly::{edge, kelly_stake};
+use super::model_store::{load_artifact, ModelStore};
+use super::win_probability::WinProbParams;
+
+/// Spec suffix that turns calibration off for a challenger.
+const UNCALIBRATED_SUFFIX: &str = ":uncalibrated";
+
+/// A challenger configuration.
+#[derive(Debug, Clone)]
+pub struct ShadowModel {
+    /// The spec it was configured with, e.g. `20261018T120000Z:uncalibrated`
+    pub name: String,
+    /// Win-probability parameters; `None` prices with the live parameters
+    pub params: Option<WinProbParams>,
+    /// Whether the live calibration chain is applied
+    pub calibrated: bool,
+}
+
+impl ShadowModel {
+    /// Resolve a spec: `live`, `builtin`, a store version or a JSON/TOML
+    /// file path, optionally suffixed with `:uncalibrated`.
+    pub fn from_spec(spec: &str, store: &ModelStore) -> anyhow::Result<Self> {
+        let spec = spec.trim();
+        let (base, calibrated) = match spec.strip_suffix(UNCALIBRATED_SUFFIX) {
+            Some(base) => (base, false),
+            None => (spec, true),
+        };
+        let params = match base {
+            "" => anyhow::bail!("empty shadow model spec"),
+            "live" => None,
+            "builtin" => Some(WinProbParams::default()),
+            path if path.ends_with(".json") || path.ends_with(".toml") => {
+                Some(load_artifact(Path::new(path), false)?.params)
+            }
+            version => Some(store.load_version(version)?.params),
+        };
+        if params.is_none() && calibrated {
+            anyhow::bail!("shadow model 'live' is the champion; use 'live:uncalibrated'");
+        }
+        Ok(ShadowModel {
+            name: spec.to_string(),
+            params,
+            calibrated,
+        })
+    }
+}
+
+/// The trade a model would take on one market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowDecision {
+    /// Side with the larger raw edge: "YES" or "NO"
+    pub outcome: &'static str,
+    pub price: f64,
+    pub edge: f64,
+    pub would_trade: bool,
+    pub stake_usd: f64,
+}
+
+/// Best-side decision for `p_yes` at the given prices: a Kelly-sized trade
+/// when the raw edge clears `threshold_edge` and the stake is at least $1.
+pub fn decide(
+    p_yes: f64,
+    yes_price: f64,
+    no_price: f64,
+    threshold_edge: f64,
+    balance: f64,
+    kelly_fraction: f64,
+) -> ShadowDecision {
+    let yes_edge = edge(p_yes, yes_price);
+    let no_edge = edge(1.0 - p_yes, no_price);
+    let (outcome, prob, price, best_edge) = if yes_edge >= no_edge {
+        ("YES", p_yes, yes_price, yes_edge)
+    } else {
+        ("NO", 1.0 - p_yes, no_price, no_edge)
+    };
+    let stake_usd = if best_edge >= threshold_edge {
+        balance.max(0.0) * kelly_stake(prob, price, kelly_fraction)
+    } else {
+        0.0
+    };
+    ShadowDecision {
+        outcome,
+        price,
+        edge: best_edge,
+        would_trade: stake_usd >= 1.0,
+        stake_usd: if stake_usd >= 1.0 { stake_usd } else { 0.0 },
+    }
+}
+
+/// PnL of holding a hypothetical trade to resolution.
+pub fn simulated_pnl(stake_usd: f64, price: f64, won: bool) -> f64 {
+    if won {
+        stake_usd * (1.0 - price) / price
+    } else {
+        -stake_usd
+    }
+}
+
+/// Champion-vs-challenger comparison for one model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowSummary {
+    pub model_name: String,
+    /// "champion" or "challenger"
+    pub role: String,
+    pub evaluations: usize,
+    /// Evaluations whose outcome is known
+    pub labelled: usize,
+    pub logloss: Option<f64>,
+    pub brier: Option<f64>,
+    pub hypothetical_trades: usize,
+    /// Hypothetical trades whose outcome is known
+    pub settled_trades: usize,
+    pub simulated_pnl: f64,
+    /// Simulated PnL over settled stake
+    pub simulated_roi: Option<f64>,
+}
+
+/// Summarise evaluations per model, champions first.
+pub fn summarize(rows: &[ShadowEvaluation]) -> Vec<ShadowSummary> {
+    let mut by_model: BTreeMap<(bool, &str), Vec<&ShadowEvaluation>> = BTreeMap::new();
+    for row in rows {
+        by_model
+            .entry((row.role != "champion", row.model_name.as_str()))
+            .or_default()
+            .push(row);
+    }
+    by_model
+        .into_iter()
+        .map(|((_, model_name), rows)| {
+            let samples: Vec<(f64, f64)> = rows
+                .iter()
+                .filter_map(|r| Some((r.model_prob_yes, if r.yes_won? { 1.0 } else { 0.0 })))
+                .collect();
+            let (logloss, brier) = if samples.is_empty() {
+                (None, None)
+            } else {
+                let (ll, br) = score(&samples, |p| p);
+                (Some(ll), Some(br))
+            };
+            let trades: Vec<_> = rows.iter().filter(|r| r.would_trade).collect();
+            let (mut settled_trades, mut settled_stake, mut pnl) = (0, 0.0, 0.0);
+            for trade in &trades {
+                if let Some(yes_won) = trade.yes_won {
+                    let won = (trade.outcome == "YES") == yes_won;
+                    settled_trades += 1;
+                    settled_stake += trade.stake_usd;
+                    pnl += simulated_pnl(trade.stake_usd, trade.entry_price, won);
+                }
+            }
+            ShadowSummary {
+                model_name: model_name.to_string(),
+                role: rows[0].role.clone(),
+                evaluations: rows.len(),
+                labelled: samples.len(),
+                logloss,
+                brier,
+                hypothetical_trades: trades.len(),
+                settled_trades,
+                simulated_pnl: pnl,
+                simulated_roi: (settled_stake > 0.0).then(|| pnl / settled_stake),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn evaluation(
+        model_name: &str,
+        role: &str,
+        p_yes: f64,
+        outcome: &str,
+        stake_usd: f64,
+        yes_won: Option<bool>,
+    ) -> ShadowEvaluation {
+        ShadowEvaluation {
+            id: None,
+            model_name: model_name.into(),
+            role: role.into(),
+            score_event_id: None,
+            fixture_id: "fx1".into(),
+            market_id: "mkt1".into(),
+            sport: "soccer".into(),
+            yes_is_home: true,
+            model_prob_yes: p_yes,
+            yes_price: 0.5,
+            no_price: 0.5,
+            outcome: outcome.into(),
+            edge: 0.2,
+            threshold_edge: 0.1,
+            would_trade: stake_usd > 0.0,
+            stake_usd,
+            entry_price: 0.5,
+            evaluated_at: Utc::now(),
+            yes_won,
+            labelled_at: None,
+        }
+    }
+
+    #[test]
+    fn trades_the_side_with_the_larger_edge_above_threshold() {
+        let yes = decide(0.7, 0.5, 0.5, 0.1, 100.0, 0.25);
+        assert_eq!(yes.outcome, "YES");
+        assert!(yes.would_trade && yes.stake_usd > 1.0);
+
+        let no = decide(0.3, 0.5, 0.5, 0.1, 100.0, 0.25);
+        assert_eq!(no.outcome, "NO");
+        assert!(no.would_trade);
+
+        let flat = decide(0.52, 0.5, 0.5, 0.1, 100.0, 0.25);
+        assert!(!flat.would_trade);
+        assert_eq!(flat.stake_usd, 0.0);
+    }
+
+    #[test]
+    fn summarizes_champion_first_with_simulated_pnl() {
+        let rows = vec![
+            evaluation("v2", "challenger", 0.8, "YES", 10.0, Some(true)),
+            evaluation("v2", "challenger", 0.8, "YES", 10.0, None),
+            evaluation("builtin", "champion", 0.4, "NO", 10.0, Some(true)),
+        ];
+        let summary = summarize(&rows);
+        assert_eq!(summary[0].role, "champion");
+        assert_eq!(summary[1].model_name, "v2");
+
+        // Bought NO at 0.5 and YES won.
+        assert!((summary[0].simulated_pnl + 10.0).abs() < 1e-9);
+        // Bought YES at 0.5 and won: +10; the open trade is not settled.
+        assert_eq!(summary[1].hypothetical_trades, 2);
+        assert_eq!(summary[1].settled_trades, 1);
+        assert!((summary[1].simulated_pnl - 10.0).abs() < 1e-9);
+        assert_eq!(summary[1].labelled, 1);
+        assert!(summary[1].logloss.unwrap() < summary[0].logloss.unwrap());
+    }
+}
@@ -1,16 +1,45 @@
 use anyhow::Result;
 use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use tracing::{error, info, warn};
 
 use crate::config::Config;
-use crate::db::models::{Market, Position, ScoreEvent};
-use crate::db::{models::LiveGame, Database, ModelCalibration};
+use crate::db::models::{GameStatus, Market, Position, ScoreEvent};
+use crate::db::{
+    models::LiveGame, ControlAction, Database, DriftAlert, ModelCalibration, ModelObservation,
+    ShadowEvaluation,
+};
+use crate::live_scores::clock::{game_progress, MAX_EXTRAPOLATION_SECS};
+use crate::live_scores::{GameClock, GameStateChange, GameStateEvent};
 use crate::polymarket::{MarketCache, PolymarketClient, PriceFeed};
 
-use super::calibration::{apply_platt, fit_platt, PlattCalibration};
+use super::arbitrage::{
+    event_teams, find_opportunity, group_exclusive_markets, ArbitrageMode, ArbitrageOpportunity,
+    Leg, LegQuote,
+};
+use super::calibration::{
+    apply_hierarchical, calibration_set_version, level_keys, select_calibrator, CalibrationModel,
+    CalibrationOptions, GamePhase, LevelCalibration, PlattCalibration,
+};
+use super::control::{ControlCommand, ControlOutcome, ManualControls};
+use super::drift::{
+    calibration_drift, edge_decay, feed_drift, DriftAction, DriftBreach, DriftKind, GLOBAL_SCOPE,
+};
+use super::execution::{next_action, EntryAction, EntryKind, ExecutionParams, PendingEntry};
+use super::hedging::{best_hedge, sport_has_draws, HedgeCandidate};
 use super::kelly::{edge, kelly_stake};
-use super::position::{compute_levels, evaluate_position, PositionAction};
+use super::market_making::{compute_quotes, quote_crossed, QuoteParams, QuoteSide, RestingQuote};
+use super::model_store::ModelStore;
+use super::position::{
+    average_entry_price, compute_levels, evaluate_exit_policy, evaluate_model_exit, ExitPolicy,
+    ExitPolicyParams, PositionAction,
+};
+use super::profiles::{StrategyProfile, StrategyProfiles};
+use super::shadow::{decide, ShadowModel};
+
+/// Most liquid events watched by the arbitrage scanner.
+const MAX_ARBITRAGE_EVENTS: usize = 50;
 
 /// The core bot engine.  Runs continuously; evaluates live score events,
 /// finds matching Polymarket markets, and manages positions.
@@ -33,6 +62,12 @@ pub struct BotEngine {
     outcome_asset_ids: HashMap<(String, String), String>,
     /// Tracks which assets were already subscribed on the CLOB WS feed.
     subscribed_asset_ids: HashSet<String>,
+    /// YES assets of arbitrage baskets being watched (kept subscribed).
+    arbitrage_asset_ids: HashSet<String>,
+    /// Resting maker quotes per market (market-making mode).
+    maker_books: HashMap<String, MakerBook>,
+    /// Live entry orders not yet fully filled.
+    pending_entries: Vec<PendingEntry>,
     /// Real-time CLOB price stream.
     price_feed: PriceFeed,
     /// Feed-health state for entry circuit-breaker decisions.
@@ -41,8 +76,46 @@ pub struct BotEngine {
     recent_event_keys: HashMap<String, DateTime<Utc>>,
     /// Last observed score per provider event ID.
     last_score_by_event: HashMap<String, (i32, i32, DateTime<Utc>)>,
-    /// Per-sport Platt calibration models.
-    probability_calibrations: HashMap<String, PlattCalibration>,
+    /// Calibration models keyed by level (see `calibration::level_keys`).
+    probability_calibrations: HashMap<String, LevelCalibration>,
+    /// Archived version of `probability_calibrations` (see `model_version`).
+    calibration_version: Option<String>,
+    /// Challenger models evaluated on every score event (see `shadow`).
+    shadow_models: Vec<ShadowModel>,
+    /// Per-sport and per-league strategy parameters (see `profiles`).
+    profiles: StrategyProfiles,
+    /// Pauses and blacklists set through the admin API.
+    controls: ManualControls,
+    /// Latest game state per fixture, for model re-pricing on sweeps.
+    fixture_states: HashMap<String, FixtureState>,
+    /// Active drift breaches by (kind, scope), with their alert row IDs.
+    drift_breaches: HashMap<(DriftKind, String), (DriftBreach, i64)>,
+}
+
+/// Observations linked to a market are labelled from its resolution once
+/// this long has passed (the game is over by then).
+const OBSERVATION_LABEL_DELAY_HOURS: i64 = 3;
+
+/// Home-win label implied by a winner market's resolution.  NO on an
+/// away-side market means a draw or a home win, so in sports with draws it
+/// labels nothing.
+fn resolution_home_won(resolved_yes: bool, yes_is_home: bool, sport: &str) -> Option<bool> {
+    match (resolved_yes, yes_is_home) {
+        (yes, true) => Some(yes),
+        (true, false) => Some(false),
+        (false, false) => (!sport_has_draws(sport)).then_some(true),
+    }
+}
+
+/// One labelled raw model probability for calibration training.
+#[derive(Debug, Clone)]
+struct CalibrationSample {
+    sport: String,
+    league: Option<String>,
+    minute: Option<i32>,
+    prob_raw: f64,
+    /// 1.0 when the predicted side won
+    label: f64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -60,11 +133,31 @@ struct DailyRiskState {
     trades_today: u32,
 }
 
+#[derive(Debug, Clone)]
+struct FixtureState {
+    game: LiveGame,
+    clock: GameClock,
+    last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct MakerBook {
+    fixture_id: String,
+    yes_is_home: bool,
+    /// YES token asset, kept subscribed while quoting.
+    asset_id: Option<String>,
+    bid: Option<RestingQuote>,
+    ask: Option<RestingQuote>,
+}
+
 #[derive(Debug, Clone, Default)]
 struct FeedHealthState {
     samples: u64,
     ewma_rest_fallback_rate: f64,
     ewma_ws_age_ms: f64,
+    /// Slow-moving averages of the same metrics, the feed-drift baseline
+    baseline_rest_fallback_rate: f64,
+    baseline_ws_age_ms: f64,
     block_entries_until: Option<DateTime<Utc>>,
     degraded_since: Option<DateTime<Utc>>,
 }
@@ -93,15 +186,63 @@ impl BotEngine {
         let trades_today = db.count_positions_opened_since(day_start)?;
         let probability_calibrations = db
             .load_model_calibrations()?
-            .into_iter()
-            .map(|c| {
-                (
-                    Self::normalize_sport_key(&c.sport),
-                    PlattCalibration { a: c.a, b: c.b },
-                )
+            .iter()
+            .filter_map(|c| {
+                Self::stored_calibration_model(c).map(|model| {
+                    (
+                        Self::normalize_sport_key(&c.key),
+                        LevelCalibration {
+                            model,
+                            samples: c.samples.max(0) as usize,
+                        },
+                    )
+                })
             })
             .collect::<HashMap<_, _>>();
+        let profiles = Self::load_profiles(&config)?;
+        let store = ModelStore::new(&config.model_dir);
+        let shadow_models = config
+            .shadow_models
+            .iter()
+            .filter(|spec| !spec.trim().is_empty())
+            .filter_map(|spec| match ShadowModel::from_spec(spec, &store) {
+                Ok(model) => Some(model),
+                Err(e) => {
+                    warn!("Ignoring shadow model '{}': {:#}", spec, e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        if !shadow_models.is_empty() {
+            info!(
+                "Shadow-evaluating {} challenger model(s): {}",
+                shadow_models.len(),
+                shadow_models
+                    .iter()
+                    .map(|m| m.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        let pending_entries: Vec<PendingEntry> = db
+            .list_pending_entries()?
+            .iter()
+            .filter_map(|json| match serde_json::from_str(json) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!("Dropping unreadable pending entry: {}", e);
+                    None
+                }
+            })
+            .collect();
+        let controls = ManualControls::from_rows(&db.list_manual_controls()?);
+        if controls != ManualControls::default() {
+            warn!("Restored manual controls: {:?}", controls);
+        }
+        // Drift breaches are re-raised by the first check if they persist.
+        db.resolve_open_drift_alerts()?;
         let price_feed = PriceFeed::new(&config.polymarket_ws_url);
+        let calibration_version = Self::archive_calibrations(&db, &probability_calibrations)?;
         if !probability_calibrations.is_empty() {
             info!(
                 "Loaded {} probability calibration model(s)",
@@ -123,14 +264,145 @@ impl BotEngine {
             },
             outcome_asset_ids: HashMap::new(),
             subscribed_asset_ids: HashSet::new(),
+            arbitrage_asset_ids: HashSet::new(),
+            maker_books: HashMap::new(),
+            pending_entries,
             price_feed,
             feed_health: FeedHealthState::default(),
             recent_event_keys: HashMap::new(),
             last_score_by_event: HashMap::new(),
             probability_calibrations,
+            calibration_version,
+            shadow_models,
+            profiles,
+            controls,
+            fixture_states: HashMap::new(),
+            drift_breaches: HashMap::new(),
+        })
+    }
+
+    fn load_profiles(config: &Config) -> Result<StrategyProfiles> {
+        let profiles = StrategyProfiles::from_config(config)?;
+        if let Some(path) = config.strategy_profiles_path.as_deref() {
+            info!(
+                "Loaded strategy profiles from {}: {}",
+                path,
+                profiles.keys().join(", ")
+            );
+        }
+        Ok(profiles)
+    }
+
+    /// Swap in a config changed at runtime, keeping all in-memory state.
+    /// Strategy profiles are re-derived over the new globals; the admin API
+    /// has already checked that they are valid.
+    pub fn apply_config(&mut self, config: Config) -> Result<()> {
+        self.profiles = self.profiles.rebase(&config)?;
+        self.config = config;
+        info!("Applied runtime config change");
+        Ok(())
+    }
+
+    /// Run a manual control command from the admin API, auditing every
+    /// command but `Status` whether or not it succeeds.
+    pub async fn handle_control(
+        &mut self,
+        actor: &str,
+        command: ControlCommand,
+    ) -> Result<ControlOutcome> {
+        let result = self.run_control(&command).await;
+        if command != ControlCommand::Status {
+            let outcome = match &result {
+                Ok(message) => {
+                    warn!("Manual control by {}: {:?} ({})", actor, command, message);
+                    message.clone()
+                }
+                Err(e) => format!("rejected: {:#}", e),
+            };
+            let action = ControlAction {
+                id: None,
+                actor: actor.to_string(),
+                command: serde_json::to_string(&command)?,
+                outcome,
+                acted_at: Utc::now(),
+            };
+            if let Err(e) = self.db.insert_control_action(&action) {
+                error!("Failed to audit manual control {:?}: {}", command, e);
+            }
+        }
+        Ok(ControlOutcome {
+            message: result?,
+            controls: self.controls.clone(),
         })
     }
 
+    /// Apply `command` to the (persisted) controls, then act on it.
+    async fn run_control(&mut self, command: &ControlCommand) -> Result<String> {
+        let before = self.controls.clone();
+        self.controls.apply(command)?;
+        if self.controls != before {
+            self.db.replace_manual_controls(&self.controls.to_rows())?;
+        }
+        let message = match command {
+            ControlCommand::Flatten => {
+                let (closed, failed) = self.flatten_all().await?;
+                format!(
+                    "entries paused; closed {} position(s), {} could not be closed",
+                    closed, failed
+                )
+            }
+            ControlCommand::ClosePosition { position_id } => {
+                self.close_position_manually(*position_id).await?
+            }
+            ControlCommand::Status => "ok".to_string(),
+            _ => "applied".to_string(),
+        };
+        Ok(message)
+    }
+
+    /// Cancel resting entry and maker orders, then close every open position
+    /// at market.  Returns `(closed, failed)`; failed positions stay open and
+    /// managed.
+    async fn flatten_all(&mut self) -> Result<(usize, usize)> {
+        self.cancel_pending_entries("manual_flatten").await?;
+        self.cancel_maker_quotes(None).await?;
+        let (mut closed, mut failed) = (0, 0);
+        for pos in self.db.list_open_positions()? {
+            let Some(price) = self.current_position_price(&pos).await else {
+                warn!("Manual flatten: no usable price for position {:?}", pos.id);
+                failed += 1;
+                continue;
+            };
+            if self.exit_position(&pos, "closed_manual", price).await? {
+                closed += 1;
+            } else {
+                failed += 1;
+            }
+        }
+        Ok((closed, failed))
+    }
+
+    async fn close_position_manually(&mut self, position_id: i64) -> Result<String> {
+        let Some(pos) = self
+            .db
+            .list_open_positions()?
+            .into_iter()
+            .find(|p| p.id == Some(position_id))
+        else {
+            anyhow::bail!("position {} is not open", position_id);
+        };
+        let Some(price) = self.current_position_price(&pos).await else {
+            anyhow::bail!("no usable price for position {}", position_id);
+        };
+        if !self.exit_position(&pos, "closed_manual", price).await? {
+            anyhow::bail!(
+                "close order for position {} failed; it is still open",
+                position_id
+            );
+        }
+        Ok(format!("closed position {} at {:.3}", position_id, price))
+    }
+
     /// Minimum absolute probability shift required to treat a score event as
     /// materially important for pricing.
     fn probability_delta_threshold(sport: &str) -> f64 {
@@ -149,12 +421,57 @@ impl BotEngine {
         sport.trim().to_lowercase()
     }
 
-    fn calibrate_probability(&self, sport: &str, raw_prob: f64) -> f64 {
-        let key = Self::normalize_sport_key(sport);
-        if let Some(model) = self.probability_calibrations.get(&key).copied() {
-            apply_platt(raw_prob, model)
-        } else {
-            raw_prob.clamp(0.0, 1.0)
+    /// Game phase used for phase-level calibration.
+    fn calibration_phase(sport: &str, minute: Option<i32>) -> Option<GamePhase> {
+        minute
+            .and_then(|m| game_progress(sport, m))
+            .map(GamePhase::from_progress)
+    }
+
+    /// Calibrate through the sport → league → phase chain for `game`.
+    fn calibrate_probability(&self, game: &LiveGame, raw_prob: f64) -> f64 {
+        self.calibrate_for(&game.sport, Some(&game.league), game.minute, raw_prob)
+    }
+
+    fn calibrate_for(
+        &self,
+        sport: &str,
+        league: Option<&str>,
+        minute: Option<i32>,
+        raw_prob: f64,
+    ) -> f64 {
+        let keys = level_keys(sport, league, Self::calibration_phase(sport, minute));
+        let levels: Vec<Option<&LevelCalibration>> = keys
+            .iter()
+            .map(|key| self.probability_calibrations.get(key))
+            .collect();
+        apply_hierarchical(&levels, raw_prob, self.config.calibration_shrinkage_samples)
+    }
+
+    /// Decode a stored calibration row; legacy rows carry only Platt `a`/`b`.
+    fn stored_calibration_model(row: &ModelCalibration) -> Option<CalibrationModel> {
+        let Some(json) = row.params_json.as_deref() else {
+            return Some(CalibrationModel::Platt(PlattCalibration {
+                a: row.a,
+                b: row.b,
+            }));
+        };
+        match serde_json::from_str(json) {
+            Ok(model) => Some(model),
+            Err(e) => {
+                warn!("Ignoring unreadable calibration for {}: {}", row.key, e);
+                None
+            }
+        }
+    }
+
+    fn calibration_options(&self) -> CalibrationOptions {
+        CalibrationOptions {
+            max_iters: self.config.calibration_max_iters,
+            learning_rate: self.config.calibration_learning_rate,
+            l2: self.config.calibration_l2,
+            cv_folds: self.config.calibration_cv_folds,
+            histogram_bins: self.config.calibration_histogram_bins,
         }
     }
 
@@ -162,9 +479,128 @@ impl BotEngine {
         if !self.config.calibration_enabled {
             return Ok(());
         }
+        let samples = match self.config.calibration_training_source.as_str() {
+            "trades" => self.trade_calibration_samples().await?,
+            _ => self.observation_calibration_samples().await?,
+        };
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        let mut grouped: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+        for sample in samples {
+            if !(0.0..=1.0).contains(&sample.prob_raw) {
+                continue;
+            }
+            // Every sample trains its sport, league and phase levels.
+            let phase = Self::calibration_phase(&sample.sport, sample.minute);
+            for key in level_keys(&sample.sport, sample.league.as_deref(), phase) {
+                grouped
+                    .entry(key)
+                    .or_default()
+                    .push((sample.prob_raw, sample.label));
+            }
+        }
+
+        for (key, level_samples) in grouped {
+            if level_samples.len() < self.config.calibration_min_samples_per_level {
+                continue;
+            }
+            // Platt, isotonic and histogram compete on cross-validated log
+            // loss; the improvement check below uses the same out-of-fold
+            // metrics.
+            let Some(fit) = select_calibrator(&level_samples, &self.calibration_options()) else {
+                continue;
+            };
+            let ll_before = fit.metrics.logloss_before.max(1e-9);
+            let br_before = fit.metrics.brier_before.max(1e-9);
+            let ll_improvement =
+                ((fit.metrics.logloss_before - fit.metrics.logloss_after) / ll_before).max(0.0);
+            let br_improvement =
+                ((fit.metrics.brier_before - fit.metrics.brier_after) / br_before).max(0.0);
+
+            if ll_improvement < self.config.calibration_min_relative_improvement
+                && br_improvement < self.config.calibration_min_relative_improvement
+            {
+                info!(
+                    "Calibration candidate rejected for {}: rel_improve logloss={:.4}, brier={:.4}",
+                    key, ll_improvement, br_improvement
+                );
+                continue;
+            }
+
+            let (a, b) = match &fit.model {
+                CalibrationModel::Platt(platt) => (platt.a, platt.b),
+                _ => (1.0, 0.0),
+            };
+            let model = ModelCalibration {
+                key: key.clone(),
+                a,
+                b,
+                samples: level_samples.len() as i64,
+                logloss_before: fit.metrics.logloss_before,
+                logloss_after: fit.metrics.logloss_after,
+                brier_before: fit.metrics.brier_before,
+                brier_after: fit.metrics.brier_after,
+                fitted_at: Utc::now(),
+                model_kind: fit.model.kind().to_string(),
+                params_json: Some(serde_json::to_string(&fit.model)?),
+                reliability_json: Some(serde_json::to_string(&fit.reliability)?),
+            };
+            self.db.upsert_model_calibration(&model)?;
+            self.probability_calibrations.insert(
+                key.clone(),
+                LevelCalibration {
+                    model: fit.model,
+                    samples: level_samples.len(),
+                },
+            );
+            info!(
+                "Calibration promoted for {}: model={}, samples={}, cv logloss {:.4}->{:.4}, cv brier {:.4}->{:.4}",
+                key,
+                model.model_kind,
+                model.samples,
+                model.logloss_before,
+                model.logloss_after,
+                model.brier_before,
+                model.brier_after
+            );
+        }
+        self.calibration_version =
+            Self::archive_calibrations(&self.db, &self.probability_calibrations)?;
+        Ok(())
+    }
+
+    /// Archive a calibration set under its content version and return it.
+    fn archive_calibrations(
+        db: &Database,
+        levels: &HashMap<String, LevelCalibration>,
+    ) -> Result<Option<String>> {
+        let Some((version, models_json)) = calibration_set_version(levels)? else {
+            return Ok(None);
+        };
+        db.insert_calibration_version(&version, &models_json)?;
+        Ok(Some(version))
+    }
+
+    /// Version of the full model behind an entry: the win-probability
+    /// parameters plus the calibration set applied on top of them.
+    fn model_version(&self) -> String {
+        let params = super::win_probability::active_params().version.clone();
+        match &self.calibration_version {
+            Some(calibrations) => format!("{}+{}", params, calibrations),
+            None => params,
+        }
+    }
+
+    /// Calibration samples from closed trades, labelled by market resolution.
+    ///
+    /// Only covers games we chose to bet on, so it is biased towards the
+    /// situations where the model disagreed with the market.
+    async fn trade_calibration_samples(&self) -> Result<Vec<CalibrationSample>> {
         let candidates = self.db.list_calibration_candidates()?;
         if candidates.is_empty() {
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         let mut unique_market_ids: HashSet<String> = HashSet::new();
@@ -194,15 +630,12 @@ impl BotEngine {
         }
         if resolved_by_market.is_empty() {
             info!("Calibration skipped: no resolved market outcomes available");
-            return Ok(());
+            return Ok(Vec::new());
         }
 
-        let mut grouped: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+        let mut samples = Vec::new();
         let mut unresolved_rows = 0usize;
         for row in candidates {
-            if !(0.0..=1.0).contains(&row.model_prob_raw) {
-                continue;
-            }
             let Some(resolved_outcome) = resolved_by_market.get(&row.market_id) else {
                 unresolved_rows += 1;
                 continue;
@@ -212,10 +645,13 @@ impl BotEngine {
             } else {
                 0.0
             };
-            grouped
-                .entry(Self::normalize_sport_key(&row.sport))
-                .or_default()
-                .push((row.model_prob_raw, label));
+            samples.push(CalibrationSample {
+                sport: row.sport,
+                league: row.league,
+                minute: row.minute,
+                prob_raw: row.model_prob_raw,
+                label,
+            });
         }
         if unresolved_rows > 0 {
             info!(
@@ -223,68 +659,182 @@ impl BotEngine {
                 unresolved_rows
             );
         }
+        Ok(samples)
+    }
 
-        for (sport, sport_samples) in grouped {
-            if sport_samples.len() < self.config.calibration_min_samples_per_sport {
-                continue;
-            }
-            let Some(fit) = fit_platt(
-                &sport_samples,
-                self.config.calibration_max_iters,
-                self.config.calibration_learning_rate,
-                self.config.calibration_l2,
-            ) else {
+    /// Calibration samples from every recorded score event whose fixture
+    /// result is known.  Unlabelled observations linked to a winner market
+    /// are labelled from its resolution first.
+    async fn observation_calibration_samples(&self) -> Result<Vec<CalibrationSample>> {
+        let cutoff = Utc::now() - chrono::Duration::hours(OBSERVATION_LABEL_DELAY_HOURS);
+        for (market_id, yes_is_home, sport) in
+            self.db.list_unlabelled_observation_markets(cutoff)?
+        {
+            let resolved = match self
+                .polymarket
+                .get_market_resolved_outcome(&market_id)
+                .await
+            {
+                Ok(Some(outcome)) => outcome,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Failed to fetch resolution for {}: {}", market_id, e);
+                    continue;
+                }
+            };
+            let Some(home_won) =
+                resolution_home_won(resolved.eq_ignore_ascii_case("YES"), yes_is_home, &sport)
+            else {
+                // The fixture's final score can still label them.
+                self.db.unlink_market_observations(&market_id)?;
                 continue;
             };
-            let ll_before = fit.metrics.logloss_before.max(1e-9);
-            let br_before = fit.metrics.brier_before.max(1e-9);
-            let ll_improvement =
-                ((fit.metrics.logloss_before - fit.metrics.logloss_after) / ll_before).max(0.0);
-            let br_improvement =
-                ((fit.metrics.brier_before - fit.metrics.brier_after) / br_before).max(0.0);
-
-            if ll_improvement < self.config.calibration_min_relative_improvement
-                && br_improvement < self.config.calibration_min_relative_improvement
-            {
+            let labelled = self.db.label_market_observations(&market_id, home_won)?;
+            if labelled > 0 {
                 info!(
-                    "Calibration candidate rejected for {}: rel_improve logloss={:.4}, brier={:.4}",
-                    sport, ll_improvement, br_improvement
+                    "Labelled {} model observations from resolution of {}",
+                    labelled, market_id
                 );
-                continue;
             }
+        }
+        Ok(self
+            .db
+            .list_labelled_observations(None)?
+            .into_iter()
+            .filter_map(|obs| {
+                Some(CalibrationSample {
+                    sport: obs.sport,
+                    league: obs.league,
+                    minute: obs.minute,
+                    prob_raw: obs.model_prob_home_raw,
+                    label: if obs.home_won? { 1.0 } else { 0.0 },
+                })
+            })
+            .collect())
+    }
 
-            let model = ModelCalibration {
-                sport: sport.clone(),
-                a: fit.calibration.a,
-                b: fit.calibration.b,
-                samples: sport_samples.len() as i64,
-                logloss_before: fit.metrics.logloss_before,
-                logloss_after: fit.metrics.logloss_after,
-                brier_before: fit.metrics.brier_before,
-                brier_after: fit.metrics.brier_after,
-                fitted_at: Utc::now(),
+    /// Price the score event with the live model and every challenger and
+    /// record what each would trade on the fixture's cached winner markets.
+    /// Uses cached or already-streaming prices only, so it adds no network
+    /// round trip ahead of live entries.
+    async fn evaluate_shadow_models(
+        &self,
+        event: &ScoreEvent,
+        game: &LiveGame,
+        score_event_id: i64,
+        p_home_raw: f64,
+    ) -> Result<()> {
+        let markets = self
+            .market_cache
+            .search(&event.home_team, &event.away_team, &event.league)
+            .await;
+        if markets.is_empty() {
+            return Ok(());
+        }
+
+        let mut models = vec![(
+            self.model_version(),
+            "champion",
+            self.calibrate_probability(game, p_home_raw),
+        )];
+        for model in &self.shadow_models {
+            let raw = match &model.params {
+                Some(params) => super::win_probability::sport_win_probability_with(
+                    params,
+                    &event.sport,
+                    game,
+                    true,
+                ),
+                None => p_home_raw,
             };
-            self.db.upsert_model_calibration(&model)?;
-            self.probability_calibrations.insert(
-                sport.clone(),
-                PlattCalibration {
-                    a: model.a,
-                    b: model.b,
-                },
-            );
+            let p_home = if model.calibrated {
+                self.calibrate_probability(game, raw)
+            } else {
+                raw
+            };
+            models.push((model.name.clone(), "challenger", p_home));
+        }
+
+        let now_ms = Utc::now().timestamp_millis().max(0) as u64;
+        let mut rows = Vec::new();
+        for market in &markets {
+            let Some(yes_is_home) =
+                Self::infer_yes_is_home(market, &event.home_team, &event.away_team)
+            else {
+                continue;
+            };
+            let mut prices = [market.yes_price, market.no_price];
+            for (price, outcome) in prices.iter_mut().zip(["YES", "NO"]) {
+                let key = Self::outcome_asset_key(&market.id, outcome);
+                if let Some(asset_id) = self.outcome_asset_ids.get(&key) {
+                    if let Some(snapshot) = self.price_feed.get_price(asset_id).await {
+                        if now_ms.saturating_sub(snapshot.last_updated_ms)
+                            <= self.config.ws_price_max_age_ms
+                        {
+                            *price = Some(snapshot.mid_price);
+                        }
+                    }
+                }
+            }
+            let Some(yes_price) = prices[0].filter(|p| *p > 0.0 && *p < 1.0) else {
+                continue;
+            };
+            let no_price = prices[1]
+                .filter(|p| *p > 0.0 && *p < 1.0)
+                .unwrap_or((1.0 - yes_price).clamp(0.01, 0.99));
+            let threshold_edge = self.config.min_edge
+                + self.round_trip_cost_edge()
+                + Self::liquidity_edge_buffer(market.volume);
+
+            for (model_name, role, p_home) in &models {
+                let p_yes = if yes_is_home { *p_home } else { 1.0 - p_home };
+                let decision = decide(
+                    p_yes,
+                    yes_price,
+                    no_price,
+                    threshold_edge,
+                    self.balance,
+                    self.config.kelly_fraction,
+                );
+                rows.push(ShadowEvaluation {
+                    id: None,
+                    model_name: model_name.clone(),
+                    role: role.to_string(),
+                    score_event_id: Some(score_event_id),
+                    fixture_id: event.event_id.clone(),
+                    market_id: market.id.clone(),
+                    sport: event.sport.clone(),
+                    yes_is_home,
+                    model_prob_yes: p_yes,
+                    yes_price,
+                    no_price,
+                    outcome: decision.outcome.to_string(),
+                    edge: decision.edge,
+                    threshold_edge,
+                    would_trade: decision.would_trade,
+                    stake_usd: decision.stake_usd,
+                    entry_price: decision.price,
+                    evaluated_at: event.detected_at,
+                    yes_won: None,
+                    labelled_at: None,
+                });
+            }
+        }
+        for row in rows
+            .iter()
+            .filter(|r| r.would_trade && r.role == "challenger")
+        {
             info!(
-                "Calibration promoted for {}: samples={}, a={:.4}, b={:.4}, logloss {:.4}->{:.4}, brier {:.4}->{:.4}",
-                sport,
-                model.samples,
-                model.a,
-                model.b,
-                model.logloss_before,
-                model.logloss_after,
-                model.brier_before,
-                model.brier_after
+                "Shadow {} would buy {} in {} at {:.3} (edge {:.3}, ${:.2})",
+                row.model_name,
+                row.outcome,
+                row.market_id,
+                row.entry_price,
+                row.edge,
+                row.stake_usd
             );
         }
-        Ok(())
+        self.db.insert_shadow_evaluations(&rows)
     }
 
     /// Add-on to the minimum probability shift requirement for lower-confidence
@@ -459,7 +1009,11 @@ impl BotEngine {
     /// - Some(true): YES = home wins
     /// - Some(false): YES = away wins
     /// - None: ambiguous or non-winner market
-    fn infer_yes_is_home(market: &Market, home_team: &str, away_team: &str) -> Option<bool> {
+    pub(crate) fn infer_yes_is_home(
+        market: &Market,
+        home_team: &str,
+        away_team: &str,
+    ) -> Option<bool> {
         let question = Self::normalize_text(&market.question);
 
         // Skip non moneyline/winner markets to avoid semantic mismatches.
@@ -581,10 +1135,10 @@ impl BotEngine {
         }
     }
 
-    fn adaptive_latency_gate(&self, sport: &str) -> (f64, f64, f64) {
-        let mut max_age_ms = self.config.latency_max_score_age_ms as f64;
-        let mut min_residual = self.config.latency_min_residual_move;
-        let mut max_priced_in = self.config.latency_max_priced_in_ratio;
+    fn adaptive_latency_gate(&self, config: &Config, sport: &str) -> (f64, f64, f64) {
+        let mut max_age_ms = config.latency_max_score_age_ms as f64;
+        let mut min_residual = config.latency_min_residual_move;
+        let mut max_priced_in = config.latency_max_priced_in_ratio;
         if let Some(stats) = self.latency_stats.get(sport) {
             if stats.samples >= 20 {
                 if stats.ewma_priced_in_ratio > 0.90 {
@@ -592,12 +1146,12 @@ impl BotEngine {
                     min_residual += 0.005;
                     max_priced_in = (max_priced_in - 0.10).max(0.35);
                 } else if stats.ewma_priced_in_ratio < 0.50
-                    && stats.ewma_residual_move > self.config.latency_min_residual_move * 1.3
+                    && stats.ewma_residual_move > config.latency_min_residual_move * 1.3
                 {
                     max_age_ms *= 1.10;
                     max_priced_in = (max_priced_in + 0.05).min(1.20);
                 }
-                if stats.ewma_processing_ms > self.config.latency_max_score_age_ms as f64 {
+                if stats.ewma_processing_ms > config.latency_max_score_age_ms as f64 {
                     max_age_ms *= 0.90;
                 }
             }
@@ -605,9 +1159,9 @@ impl BotEngine {
         (max_age_ms, min_residual, max_priced_in)
     }
 
-    fn adaptive_edge_addon(&self, sport: &str) -> f64 {
+    fn adaptive_edge_addon(&self, config: &Config, sport: &str) -> f64 {
         let mut priced_in_ratio = 0.7;
-        let mut residual_move = self.config.latency_min_residual_move;
+        let mut residual_move = config.latency_min_residual_move;
         if let Some(stats) = self.latency_stats.get(sport) {
             if stats.samples >= 10 {
                 priced_in_ratio = stats.ewma_priced_in_ratio;
@@ -629,13 +1183,20 @@ impl BotEngine {
             residual_move,
             fallback_rate,
             ws_age_ms,
-            self.config.latency_min_residual_move,
+            config.latency_min_residual_move,
             self.config.ws_price_max_age_ms as f64,
-            self.config.adaptive_min_edge_max_addon,
+            config.adaptive_min_edge_max_addon,
         )
     }
 
-    fn adaptive_divergence_limit(&self, sport: &str) -> f64 {
+    /// Minimum model edge (before costs) for entering `sport` under the
+    /// resolved profile `config`: its `min_edge` plus the adaptive and drift
+    /// add-ons.
+    fn entry_min_edge(&self, config: &Config, sport: &str) -> f64 {
+        config.min_edge + self.adaptive_edge_addon(config, sport) + self.drift_edge_addon(sport)
+    }
+
+    fn adaptive_divergence_limit(&self, config: &Config, sport: &str) -> f64 {
         let fallback_rate = if self.feed_health.samples >= self.config.feed_health_min_samples {
             self.feed_health.ewma_rest_fallback_rate
         } else {
@@ -648,7 +1209,7 @@ impl BotEngine {
             .map(|s| s.ewma_priced_in_ratio)
             .unwrap_or(0.7);
         Self::compute_adaptive_divergence_limit(
-            self.config.max_entry_quote_divergence,
+            config.max_entry_quote_divergence,
             self.config.adaptive_divergence_tightening,
             fallback_rate,
             priced_in_ratio,
@@ -666,14 +1227,23 @@ impl BotEngine {
         }
         let fallback_rate = rest_fallback_count as f64 / open_positions as f64;
         const ALPHA: f64 = 0.2;
+        const BASELINE_ALPHA: f64 = 0.01;
         if self.feed_health.samples == 0 {
             self.feed_health.ewma_rest_fallback_rate = fallback_rate;
             self.feed_health.ewma_ws_age_ms = avg_ws_age_ms;
+            self.feed_health.baseline_rest_fallback_rate = fallback_rate;
+            self.feed_health.baseline_ws_age_ms = avg_ws_age_ms;
         } else {
             self.feed_health.ewma_rest_fallback_rate =
                 (1.0 - ALPHA) * self.feed_health.ewma_rest_fallback_rate + ALPHA * fallback_rate;
             self.feed_health.ewma_ws_age_ms =
                 (1.0 - ALPHA) * self.feed_health.ewma_ws_age_ms + ALPHA * avg_ws_age_ms;
+            self.feed_health.baseline_rest_fallback_rate = (1.0 - BASELINE_ALPHA)
+                * self.feed_health.baseline_rest_fallback_rate
+                + BASELINE_ALPHA * fallback_rate;
+            self.feed_health.baseline_ws_age_ms = (1.0 - BASELINE_ALPHA)
+                * self.feed_health.baseline_ws_age_ms
+                + BASELINE_ALPHA * avg_ws_age_ms;
         }
         self.feed_health.samples += 1;
 
@@ -702,30 +1272,172 @@ impl BotEngine {
         }
     }
 
-    fn feed_health_blocking_entries(&self) -> bool {
-        self.feed_health
-            .block_entries_until
-            .is_some_and(|until| until > Utc::now())
+    fn drift_action(&self) -> DriftAction {
+        self.config
+            .drift_action
+            .parse()
+            .unwrap_or(DriftAction::Alert)
     }
 
-    fn cleanup_event_quality_maps(&mut self) {
-        let ttl = chrono::Duration::seconds(self.config.score_event_dedup_window_secs as i64 * 3);
-        let cutoff = Utc::now() - ttl;
-        self.recent_event_keys.retain(|_, ts| *ts >= cutoff);
-        self.last_score_by_event
-            .retain(|_, (_, _, ts)| *ts >= cutoff);
-        if self.recent_event_keys.len() > 100_000 {
-            self.recent_event_keys.clear();
-        }
-        if self.last_score_by_event.len() > 100_000 {
-            self.last_score_by_event.clear();
+    /// Whether a drift breach covers `sport` (its own or a global one).
+    fn sport_drifting(&self, sport: &str) -> bool {
+        let sport = Self::normalize_sport_key(sport);
+        self.drift_breaches
+            .keys()
+            .any(|(_, scope)| *scope == sport || scope == GLOBAL_SCOPE)
+    }
+
+    /// Entry threshold add-on while `sport` is drifting under "tighten".
+    fn drift_edge_addon(&self, sport: &str) -> f64 {
+        if self.drift_action() == DriftAction::Tighten && self.sport_drifting(sport) {
+            self.config.drift_min_edge_addon
+        } else {
+            0.0
         }
     }
 
-    fn event_dedup_key(event: &ScoreEvent) -> String {
-        format!(
-            "{}|{}|{}|{}|{}|{}",
-            event.event_id,
+    fn drift_pausing_entries(&self, sport: &str) -> bool {
+        self.drift_action() == DriftAction::Pause && self.sport_drifting(sport)
+    }
+
+    /// Run the drift monitors, raise alerts for new breaches and resolve the
+    /// ones that recovered.  A new calibration breach triggers a retrain
+    /// rather than waiting for the next scheduled one.
+    pub async fn check_drift(&mut self) -> Result<()> {
+        let mut breaches = Vec::new();
+
+        // Calibration: rolling log loss per sport against its last fit.
+        let cutoff = Utc::now() - chrono::Duration::hours(self.config.drift_window_hours);
+        let mut recent: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+        for obs in self.db.list_labelled_observations(Some(cutoff))? {
+            let Some(home_won) = obs.home_won else {
+                continue;
+            };
+            let p = self.calibrate_for(
+                &obs.sport,
+                obs.league.as_deref(),
+                obs.minute,
+                obs.model_prob_home_raw,
+            );
+            recent
+                .entry(Self::normalize_sport_key(&obs.sport))
+                .or_default()
+                .push((p, if home_won { 1.0 } else { 0.0 }));
+        }
+        for fit in self.db.load_model_calibrations()? {
+            if fit.key.contains('/') {
+                continue;
+            }
+            if let Some(breach) = recent.get(&fit.key).and_then(|samples| {
+                calibration_drift(
+                    &fit.key,
+                    samples,
+                    fit.logloss_after,
+                    self.config.drift_logloss_tolerance,
+                    self.config.drift_min_samples,
+                )
+            }) {
+                breaches.push(breach);
+            }
+        }
+
+        // Edge decay: recent closing-line value per sport.
+        let mut clv_by_sport: HashMap<String, Vec<f64>> = HashMap::new();
+        for (sport, clv_bps) in self.db.list_closed_clv()? {
+            clv_by_sport
+                .entry(Self::normalize_sport_key(&sport))
+                .or_default()
+                .push(clv_bps);
+        }
+        for (sport, clv) in &clv_by_sport {
+            breaches.extend(edge_decay(
+                sport,
+                clv,
+                self.config.drift_clv_window_trades,
+                self.config.drift_min_clv_bps,
+            ));
+        }
+
+        // Feed quality: short-run feed health against its long-run baseline.
+        if self.feed_health.samples >= self.config.feed_health_min_samples {
+            breaches.extend(feed_drift(
+                self.feed_health.ewma_rest_fallback_rate,
+                self.feed_health.baseline_rest_fallback_rate,
+                self.feed_health.ewma_ws_age_ms,
+                self.feed_health.baseline_ws_age_ms,
+                self.config.drift_feed_fallback_increase,
+                self.config.drift_feed_ws_age_ratio,
+            ));
+        }
+
+        let action = self.drift_action();
+        let mut active = HashMap::new();
+        let mut calibration_breached = false;
+        for breach in breaches {
+            let key = (breach.kind, breach.scope.clone());
+            let alert_id = match self.drift_breaches.remove(&key) {
+                Some((_, id)) => id,
+                None => {
+                    warn!(
+                        "Drift alert [{}] {}: {} (action: {})",
+                        breach.kind.as_str(),
+                        breach.scope,
+                        breach.message,
+                        action.as_str()
+                    );
+                    calibration_breached |= breach.kind == DriftKind::Calibration;
+                    self.db.insert_drift_alert(&DriftAlert {
+                        id: None,
+                        kind: breach.kind.as_str().to_string(),
+                        scope: breach.scope.clone(),
+                        metric: breach.metric,
+                        baseline: breach.baseline,
+                        threshold: breach.threshold,
+                        action: action.as_str().to_string(),
+                        message: breach.message.clone(),
+                        raised_at: Utc::now(),
+                        resolved_at: None,
+                    })?
+                }
+            };
+            active.insert(key, (breach, alert_id));
+        }
+        for ((kind, scope), (_, alert_id)) in self.drift_breaches.drain() {
+            info!("Drift resolved [{}] {}", kind.as_str(), scope);
+            self.db.resolve_drift_alert(alert_id)?;
+        }
+        self.drift_breaches = active;
+
+        if calibration_breached {
+            self.retrain_probability_calibration().await?;
+        }
+        Ok(())
+    }
+
+    fn feed_health_blocking_entries(&self) -> bool {
+        self.feed_health
+            .block_entries_until
+            .is_some_and(|until| until > Utc::now())
+    }
+
+    fn cleanup_event_quality_maps(&mut self) {
+        let ttl = chrono::Duration::seconds(self.config.score_event_dedup_window_secs as i64 * 3);
+        let cutoff = Utc::now() - ttl;
+        self.recent_event_keys.retain(|_, ts| *ts >= cutoff);
+        self.last_score_by_event
+            .retain(|_, (_, _, ts)| *ts >= cutoff);
+        if self.recent_event_keys.len() > 100_000 {
+            self.recent_event_keys.clear();
+        }
+        if self.last_score_by_event.len() > 100_000 {
+            self.last_score_by_event.clear();
+        }
+    }
+
+    fn event_dedup_key(event: &ScoreEvent) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            event.event_id,
             event.home_score,
             event.away_score,
             event.minute.unwrap_or(-1),
@@ -931,6 +1643,17 @@ impl BotEngine {
                 let key = Self::outcome_asset_key(&pos.market_id, &pos.outcome);
                 self.outcome_asset_ids.get(&key).cloned()
             })
+            .chain(self.arbitrage_asset_ids.iter().cloned())
+            .chain(
+                self.pending_entries
+                    .iter()
+                    .filter_map(|p| p.position.asset_id.clone()),
+            )
+            .chain(
+                self.maker_books
+                    .values()
+                    .filter_map(|book| book.asset_id.clone()),
+            )
             .collect();
 
         let to_unsubscribe: Vec<String> = self
@@ -975,17 +1698,66 @@ impl BotEngine {
             event.event_type,
         );
 
+        // Resting maker quotes become stale the moment the score changes.
+        self.cancel_maker_quotes(Some(&event.event_id)).await?;
+
         if self.should_skip_event(event) {
             return Ok(());
         }
 
         if event.event_type.contains("correction") {
-            warn!("Skipping score correction event '{}'", event.event_type);
-            return Ok(());
+            // Persist corrections too so correction-induced trades can be audited.
+            self.db.insert_score_event(event)?;
+            self.track_fixture_game(game, Utc::now());
+            return self.on_score_correction(event, game).await;
         }
 
         // Persist de-duplicated score events.
-        self.db.insert_score_event(event)?;
+        let score_event_id = self.db.insert_score_event(event)?;
+
+        // Record the raw model view of every score event for calibration,
+        // whether or not we trade it.
+        let p_home_now_raw = super::win_probability::estimate_win_probability(event, game, true);
+        let observation_id = self.db.insert_model_observation(&ModelObservation {
+            id: None,
+            score_event_id: Some(score_event_id),
+            fixture_id: event.event_id.clone(),
+            sport: event.sport.clone(),
+            league: Some(event.league.clone()),
+            minute: game.minute,
+            home_score: game.home_score,
+            away_score: game.away_score,
+            model_prob_home_raw: p_home_now_raw,
+            market_id: None,
+            yes_is_home: None,
+            observed_at: event.detected_at,
+            home_won: None,
+            label_source: None,
+            labelled_at: None,
+        })?;
+
+        // Link the observation to a winner market before any trading gate,
+        // so it can be labelled from the resolution if the final score is
+        // never seen.  Cached markets only; the entry path below links it
+        // again if a REST search finds the markets.
+        let cached = self
+            .market_cache
+            .search(&event.home_team, &event.away_team, &event.league)
+            .await;
+        self.link_observation_market(observation_id, event, &cached)?;
+
+        if !self.shadow_models.is_empty() {
+            self.evaluate_shadow_models(event, game, score_event_id, p_home_now_raw)
+                .await?;
+        }
+
+        // Re-price what we already hold on this fixture before looking for
+        // new entries.
+        self.track_fixture_game(game, Utc::now());
+        if self.config.model_exit_enabled {
+            self.reprice_fixture_positions(game, &event.event_type, "closed_model_exit")
+                .await?;
+        }
 
         let mut prev_game = game.clone();
         prev_game.home_score = event.prev_home_score.unwrap_or(game.home_score);
@@ -998,11 +1770,13 @@ impl BotEngine {
 
         let p_home_prev_raw =
             super::win_probability::estimate_win_probability(event, &prev_game, true);
-        let p_home_now_raw = super::win_probability::estimate_win_probability(event, game, true);
-        let p_home_prev = self.calibrate_probability(&event.sport, p_home_prev_raw);
-        let p_home_now = self.calibrate_probability(&event.sport, p_home_now_raw);
+        let p_home_prev = self.calibrate_probability(game, p_home_prev_raw);
+        let p_home_now = self.calibrate_probability(game, p_home_now_raw);
         let probability_shift = (p_home_now - p_home_prev).abs();
-        let base_min_shift = Self::probability_delta_threshold(&event.sport);
+        let profile = self.profiles.resolve(&event.sport, Some(&event.league));
+        let base_min_shift = profile
+            .probability_delta_threshold
+            .unwrap_or_else(|| Self::probability_delta_threshold(&event.sport));
         let quality_addon = Self::score_event_quality_shift_addon(event);
         let min_shift = base_min_shift + quality_addon;
         if probability_shift < min_shift {
@@ -1038,6 +1812,30 @@ impl BotEngine {
             warn!("Feed-health breaker active: skipping new entries");
             return Ok(());
         }
+        if self.drift_pausing_entries(&event.sport) {
+            warn!(
+                "Drift pause active for {}: skipping new entries",
+                event.sport
+            );
+            return Ok(());
+        }
+        if let Some(reason) = self.controls.sport_block(&event.sport) {
+            warn!("Manual control active ({}): skipping new entries", reason);
+            return Ok(());
+        }
+        if self.controls.team_blocked(&event.home_team)
+            || self.controls.team_blocked(&event.away_team)
+        {
+            info!(
+                "Blacklisted team in {} vs {}: skipping new entries",
+                event.home_team, event.away_team
+            );
+            return Ok(());
+        }
+
+        // Pending entries count against caps and cash like open positions.
+        let mut pending_usd = self.pending_stake_usd();
+        open_positions.extend(self.pending_entries.iter().map(|p| p.position.clone()));
 
         // Collect market IDs that already have open positions to avoid duplicates
         let mut open_market_ids: HashSet<String> =
@@ -1082,15 +1880,44 @@ impl BotEngine {
             return Ok(());
         }
 
+        if cached.is_empty() {
+            self.link_observation_market(observation_id, event, &markets)?;
+        }
+
         for market in &markets {
-            // Skip markets where we already have an open position
-            if open_market_ids.contains(&market.id) {
-                info!(
-                    "Already have open position in '{}', skipping",
-                    market.question
-                );
+            if self.controls.market_blocked(&market.id) {
+                info!("Market '{}' is blacklisted, skipping", market.question);
                 continue;
             }
+            // Skip markets where we already have an open position, unless
+            // it can still be pyramided on a confirming event.
+            let add_to = if open_market_ids.contains(&market.id) {
+                match open_positions
+                    .iter()
+                    .find(|p| p.market_id == market.id && p.id.is_some())
+                {
+                    Some(held)
+                        if profile.config.pyramiding_enabled
+                            && Self::uses_exit_policies(held)
+                            && !self
+                                .pending_entries
+                                .iter()
+                                .any(|p| p.position.market_id == market.id)
+                            && (held.add_count as u32) < profile.config.max_adds_per_position =>
+                    {
+                        Some(held.clone())
+                    }
+                    _ => {
+                        info!(
+                            "Already have open position in '{}', skipping",
+                            market.question
+                        );
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
 
             let Some(yes_is_home) =
                 Self::infer_yes_is_home(market, &event.home_team, &event.away_team)
@@ -1243,9 +2070,9 @@ impl BotEngine {
             );
 
             let (adaptive_max_age_ms, adaptive_min_residual, adaptive_max_priced_in) =
-                self.adaptive_latency_gate(&event.sport);
+                self.adaptive_latency_gate(&profile.config, &event.sport);
             let latency_pass = processing_ms <= adaptive_max_age_ms
-                && expected_yes_move >= self.config.latency_min_expected_move
+                && expected_yes_move >= profile.config.latency_min_expected_move
                 && residual_yes_move >= adaptive_min_residual
                 && priced_in_ratio <= adaptive_max_priced_in;
             if !latency_pass {
@@ -1267,7 +2094,7 @@ impl BotEngine {
             // Net-edge model with costs and liquidity-adjusted buffer.
             let cost_edge = self.round_trip_cost_edge();
             let liquidity_buffer = Self::liquidity_edge_buffer(market.volume);
-            let adaptive_min_edge = self.config.min_edge + self.adaptive_edge_addon(&event.sport);
+            let adaptive_min_edge = self.entry_min_edge(&profile.config, &event.sport);
             let threshold_edge = adaptive_min_edge + cost_edge + liquidity_buffer;
             let yes_net_edge = yes_edge - cost_edge - liquidity_buffer;
             let no_net_edge = no_edge - cost_edge - liquidity_buffer;
@@ -1306,6 +2133,23 @@ impl BotEngine {
                 continue;
             }
 
+            // Only add to a position when the event moved the model further
+            // towards the outcome we already hold.
+            if let Some(held) = add_to.as_ref() {
+                let (p_held_prev, p_held_now) = if held.outcome == "YES" {
+                    (p_yes_prev, p_yes_now)
+                } else {
+                    (1.0 - p_yes_prev, 1.0 - p_yes_now)
+                };
+                if outcome != held.outcome || p_held_now <= p_held_prev {
+                    info!(
+                        "Score event does not confirm open {} position in '{}', not adding",
+                        held.outcome, market.question
+                    );
+                    continue;
+                }
+            }
+
             let chosen_source = if outcome == "YES" {
                 yes_source.as_str()
             } else {
@@ -1315,7 +2159,7 @@ impl BotEngine {
                 match self.polymarket.get_token_price(&market.id, &outcome).await {
                     Ok(rest_price) if rest_price > 0.0 && rest_price < 1.0 => {
                         let divergence = (price - rest_price).abs();
-                        let divergence_limit = self.adaptive_divergence_limit(&event.sport);
+                        let divergence_limit = self.adaptive_divergence_limit(&profile.config, &event.sport);
                         if divergence > divergence_limit {
                             warn!(
                                 "Entry quote divergence too high for {} {}: ws={:.5}, rest={:.5}, diff={:.5} > {:.5}; skipping",
@@ -1341,8 +2185,13 @@ impl BotEngine {
             }
 
             // Kelly-size the bet
-            let stake_fraction = kelly_stake(true_win_prob, price, self.config.kelly_fraction);
-            let stake_usd = self.balance * stake_fraction;
+            let stake_fraction = kelly_stake(true_win_prob, price, profile.config.kelly_fraction);
+            let available = self.balance - pending_usd;
+            let mut stake_usd = available * stake_fraction;
+            // Adds top the position up towards the Kelly stake, never beyond it.
+            if let Some(held) = add_to.as_ref() {
+                stake_usd -= held.size_usd;
+            }
 
             if stake_usd < 1.0 {
                 info!("Stake too small (${:.2}), skipping", stake_usd);
@@ -1350,10 +2199,10 @@ impl BotEngine {
             }
 
             // Guard: never let balance go negative
-            if stake_usd > self.balance {
+            if stake_usd > available {
                 warn!(
                     "Stake ${:.2} exceeds available balance ${:.2}, skipping",
-                    stake_usd, self.balance
+                    stake_usd, available
                 );
                 continue;
             }
@@ -1361,7 +2210,7 @@ impl BotEngine {
             // Portfolio exposure caps (event / sport / league), based on notional
             // exposure over current equity proxy (cash + open notional).
             let total_open_notional: f64 = open_positions.iter().map(|p| p.size_usd).sum();
-            let total_equity = (self.balance + total_open_notional).max(1.0);
+            let total_equity = (available + total_open_notional).max(1.0);
             let event_exposure = open_positions
                 .iter()
                 .filter(|p| p.event_name.as_deref() == Some(event_key.as_str()))
@@ -1405,15 +2254,17 @@ impl BotEngine {
                 );
                 continue;
             }
+            // Each add consumes an event slot like a fresh entry does.
             let positions_for_event = open_positions
                 .iter()
                 .filter(|p| p.event_name.as_deref() == Some(event_key.as_str()))
-                .count() as u32
+                .map(|p| 1 + p.add_count.max(0) as u32)
+                .sum::<u32>()
                 + 1;
-            if positions_for_event > self.config.max_positions_per_event {
+            if positions_for_event > profile.config.max_positions_per_event {
                 info!(
                     "Per-event position count cap hit for '{}': {} > {}",
-                    event_key, positions_for_event, self.config.max_positions_per_event
+                    event_key, positions_for_event, profile.config.max_positions_per_event
                 );
                 continue;
             }
@@ -1457,32 +2308,29 @@ impl BotEngine {
                 continue;
             }
 
+            // Levels follow the averaged entry when adding.
+            let level_basis = match add_to.as_ref() {
+                Some(held) => {
+                    average_entry_price(held.size_usd, held.entry_price, stake_usd, price)
+                }
+                None => price,
+            };
             let (stop_loss, take_profit) = compute_levels(
-                price,
-                self.config.stop_loss_fraction,
-                self.config.take_profit_fraction,
-            );
-
-            info!(
-                "Opening {} position in '{}': stake=${:.2}, entry={:.3}, SL={:.3}, TP={:.3}",
-                outcome, market.question, stake_usd, price, stop_loss, take_profit
+                level_basis,
+                profile.config.stop_loss_fraction,
+                profile.config.take_profit_fraction,
             );
 
-            if !self.config.dry_run {
-                // Live trade: place order on Polymarket
-                match self
-                    .polymarket
-                    .place_order(&market.id, &outcome, stake_usd, price)
-                    .await
-                {
-                    Ok(_) => info!("Order placed successfully"),
-                    Err(e) => {
-                        error!("Failed to place order: {}", e);
-                        continue;
-                    }
-                }
+            if add_to.is_some() {
+                info!(
+                    "Adding to {} position in '{}': stake=${:.2} at {:.3}, avg entry={:.3}, SL={:.3}, TP={:.3}",
+                    outcome, market.question, stake_usd, price, level_basis, stop_loss, take_profit
+                );
             } else {
-                info!("[DRY RUN] Would place order – no real funds used");
+                info!(
+                    "Opening {} position in '{}': stake=${:.2}, entry={:.3}, SL={:.3}, TP={:.3}",
+                    outcome, market.question, stake_usd, price, stop_loss, take_profit
+                );
             }
 
             let (entry_price_source, entry_ws_age_ms, asset_id) = if outcome == "YES" {
@@ -1500,13 +2348,7 @@ impl BotEngine {
             };
 
             let pos = Position {
-                id: None,
-                market_id: market.id.clone(),
                 asset_id,
-                outcome,
-                side: "buy".into(),
-                size_usd: stake_usd,
-                entry_price: price,
                 entry_price_source: Some(entry_price_source),
                 entry_model_prob_raw: Some(true_win_prob_raw),
                 entry_model_prob: Some(true_win_prob),
@@ -1514,21 +2356,92 @@ impl BotEngine {
                 estimated_round_trip_cost_bps: self.estimated_round_trip_cost_bps(),
                 stop_loss_price: stop_loss,
                 take_profit_price: take_profit,
-                status: "open".into(),
-                opened_at: Utc::now(),
-                closed_at: None,
-                exit_price: None,
-                pnl: None,
-                dry_run: self.config.dry_run,
-                ws_used_count: 0,
-                rest_fallback_count: 0,
-                last_ws_age_ms: None,
                 sport: Some(event.sport.clone()),
                 league: Some(event.league.clone()),
                 event_name: Some(format!("{} vs {}", event.home_team, event.away_team)),
                 market_slug: market.slug.clone(),
+                fixture_id: Some(event.event_id.clone()),
+                entry_score_event_id: Some(score_event_id),
+                yes_is_home: Some(yes_is_home),
+                exit_policy: profile
+                    .config
+                    .exit_policy
+                    .parse::<ExitPolicy>()
+                    .unwrap_or(ExitPolicy::Fixed)
+                    .as_str()
+                    .to_string(),
+                model_version: Some(self.model_version()),
+                ..Position::new_open(
+                    market.id.clone(),
+                    outcome,
+                    "directional",
+                    stake_usd,
+                    price,
+                    self.config.dry_run,
+                )
             };
 
+            if !self.config.dry_run {
+                // Live trade: the order rests until it fills and is booked by
+                // `manage_pending_entries`.
+                let order_id = match self
+                    .polymarket
+                    .place_order(&market.id, &pos.outcome, stake_usd, price)
+                    .await
+                {
+                    Ok(order_id) => order_id,
+                    Err(e) => {
+                        error!("Failed to place order: {}", e);
+                        continue;
+                    }
+                };
+                info!("Entry order {} resting at {:.3}", order_id, price);
+                self.pending_entries.push(PendingEntry {
+                    order_id,
+                    position: pos.clone(),
+                    add_to: add_to.as_ref().and_then(|held| held.id),
+                    limit_price: price,
+                    order_matched: 0.0,
+                    filled_shares: 0.0,
+                    filled_usd: 0.0,
+                    model_prob: true_win_prob,
+                    threshold_edge,
+                    placed_at: Utc::now(),
+                    reprices: 0,
+                    kind: EntryKind::Directional,
+                    max_price: None,
+                    finished: None,
+                });
+                self.persist_pending_entries()?;
+                pending_usd += stake_usd;
+                open_market_ids.insert(market.id.clone());
+                open_positions.push(pos);
+                self.daily_risk.trades_today = self.daily_risk.trades_today.saturating_add(1);
+                continue;
+            }
+            info!("[DRY RUN] Would place order – no real funds used");
+
+            if let Some(held) = add_to {
+                let held_id = held.id.expect("pyramided position has an id");
+                self.db.add_to_position(
+                    held_id,
+                    stake_usd,
+                    price,
+                    level_basis,
+                    (stop_loss, take_profit),
+                    "pyramid_add",
+                )?;
+                self.balance -= stake_usd;
+                self.db.record_balance(self.balance)?;
+                if let Some(p) = open_positions.iter_mut().find(|p| p.id == Some(held_id)) {
+                    p.size_usd += stake_usd;
+                    p.entry_price = level_basis;
+                    p.add_count += 1;
+                }
+                self.daily_risk.trades_today = self.daily_risk.trades_today.saturating_add(1);
+                continue;
+            }
+
             let _id = self.db.insert_position(&pos)?;
             self.balance -= stake_usd;
             self.db.record_balance(self.balance)?;
@@ -1540,104 +2453,1946 @@ impl BotEngine {
         Ok(())
     }
 
-    /// Sweep all open positions and close those that hit stop-loss or take-profit.
+    /// Whether `correction` reverts the score change recorded in `entry`.
     ///
-    /// Uses WS mid-prices first for minimal latency; falls back to concurrent
-    /// REST fetches when WS price is unavailable.
-    pub async fn manage_positions(&mut self) -> Result<()> {
-        let open = self.db.list_open_positions()?;
-        if open.is_empty() {
-            return Ok(());
+    /// A correction invalidates an entry when it concerns the same fixture and
+    /// takes the scoring side of the entry event (`*_home` / `*_away`) back to
+    /// its pre-entry score.  Corrections to the other side, such as an earlier
+    /// goal being disallowed, leave the entry standing.  Entries whose side is
+    /// unknown are invalidated by a drop on either side.
+    fn correction_invalidates_entry(entry: &ScoreEvent, correction: &ScoreEvent) -> bool {
+        if entry.event_id != correction.event_id {
+            return false;
         }
-        let now = Utc::now();
-        let open_len = open.len();
-
-        let mut resolved_prices: Vec<Option<f64>> = vec![None; open.len()];
-        let mut quote_sources: Vec<&str> = vec!["none"; open.len()];
-        let mut ws_age_millis: Vec<Option<i64>> = vec![None; open.len()];
-        let mut used_rest_fallback: Vec<bool> = vec![false; open.len()];
-        let mut rest_fallback_indices = Vec::new();
-        let now_ms = now.timestamp_millis().max(0) as u64;
+        let home_reverted =
+            correction.home_score <= entry.prev_home_score.unwrap_or(entry.home_score - 1);
+        let away_reverted =
+            correction.away_score <= entry.prev_away_score.unwrap_or(entry.away_score - 1);
+        if entry.event_type.ends_with("_home") {
+            home_reverted
+        } else if entry.event_type.ends_with("_away") {
+            away_reverted
+        } else {
+            correction.home_score < entry.home_score || correction.away_score < entry.away_score
+        }
+    }
 
-        for (idx, pos) in open.iter().enumerate() {
-            let ws_price = if let Some(asset_id) = self
-                .ensure_asset_subscription_with_hint(
-                    &pos.market_id,
-                    &pos.outcome,
-                    pos.asset_id.as_deref(),
-                )
-                .await
-            {
-                self.price_feed.get_price(&asset_id).await
-            } else {
-                None
-            };
+    /// Calibrated model probability that `pos` pays out, given the game state.
+    ///
+    /// Returns `None` for positions whose YES/home mapping is unknown.
+    fn position_fair_value(&self, pos: &Position, game: &LiveGame) -> Option<f64> {
+        let yes_is_home = pos.yes_is_home?;
+        let p_home_raw = super::win_probability::estimate_game_win_probability(game, true);
+        let p_home = self.calibrate_probability(game, p_home_raw);
+        let p_yes = if yes_is_home { p_home } else { 1.0 - p_home };
+        Some(if pos.outcome.eq_ignore_ascii_case("YES") {
+            p_yes
+        } else {
+            1.0 - p_yes
+        })
+    }
 
-            match ws_price {
-                Some(snapshot) => {
-                    let age_ms = now_ms.saturating_sub(snapshot.last_updated_ms);
-                    if snapshot.mid_price > 0.0
-                        && snapshot.mid_price < 1.0
-                        && age_ms <= self.config.ws_price_max_age_ms
-                    {
-                        resolved_prices[idx] = Some(snapshot.mid_price);
-                        quote_sources[idx] = "ws";
-                        ws_age_millis[idx] = Some(age_ms as i64);
-                    } else {
-                        used_rest_fallback[idx] = true;
-                        warn!(
-                            "WS quote unusable for market {} outcome {} (mid={:.5}, age={}ms)",
-                            pos.market_id, pos.outcome, snapshot.mid_price, age_ms
-                        );
-                        rest_fallback_indices.push(idx);
-                    }
-                }
-                None => {
-                    used_rest_fallback[idx] = true;
-                    rest_fallback_indices.push(idx);
-                }
-            }
+    /// Link an observation to one of the fixture's winner markets, preferring
+    /// the home side: only its resolution labels a draw.
+    fn link_observation_market(
+        &self,
+        observation_id: i64,
+        event: &ScoreEvent,
+        markets: &[Market],
+    ) -> Result<()> {
+        let linked = markets
+            .iter()
+            .filter_map(|m| {
+                Self::infer_yes_is_home(m, &event.home_team, &event.away_team).map(|y| (m, y))
+            })
+            .max_by_key(|(_, yes_is_home)| *yes_is_home);
+        if let Some((market, yes_is_home)) = linked {
+            self.db
+                .set_observation_market(observation_id, &market.id, yes_is_home)?;
         }
+        Ok(())
+    }
 
-        if !rest_fallback_indices.is_empty() {
-            let price_futures: Vec<_> = rest_fallback_indices
-                .iter()
-                .map(|&idx| {
-                    let pos = &open[idx];
-                    self.polymarket
-                        .get_token_price(&pos.market_id, &pos.outcome)
-                })
-                .collect();
-            let rest_results = futures_util::future::join_all(price_futures).await;
+    /// Model fair value of `pos` from its fixture's current state; `None`
+    /// when the fixture is unknown or its feed has gone stale.
+    fn fixture_fair_value(&self, pos: &Position, now: DateTime<Utc>) -> Option<f64> {
+        let game = self.current_fixture_game(pos.fixture_id.as_deref()?, now)?;
+        self.position_fair_value(pos, &game)
+    }
 
-            for (idx, price_result) in rest_fallback_indices
-                .into_iter()
-                .zip(rest_results.into_iter())
-            {
-                let pos = &open[idx];
-                match price_result {
-                    Ok(p) if p > 0.0 && p < 1.0 => {
-                        resolved_prices[idx] = Some(p);
-                        quote_sources[idx] = "rest";
-                    }
-                    Ok(p) => {
-                        warn!(
-                            "Ignoring out-of-range price for market {} outcome {}: {:.5}",
-                            pos.market_id, pos.outcome, p
-                        );
-                    }
-                    Err(e) => {
-                        warn!(
-                            "Failed to get fallback REST price for market {} outcome {}: {}",
-                            pos.market_id, pos.outcome, e
-                        );
-                    }
+    /// Current mark for a single position: fresh WS mid first, REST fallback.
+    async fn current_position_price(&mut self, pos: &Position) -> Option<f64> {
+        let now_ms = Utc::now().timestamp_millis().max(0) as u64;
+        if let Some(asset_id) = self
+            .ensure_asset_subscription_with_hint(
+                &pos.market_id,
+                &pos.outcome,
+                pos.asset_id.as_deref(),
+            )
+            .await
+        {
+            if let Some(snapshot) = self.price_feed.get_price(&asset_id).await {
+                let age_ms = now_ms.saturating_sub(snapshot.last_updated_ms);
+                if snapshot.mid_price > 0.0
+                    && snapshot.mid_price < 1.0
+                    && age_ms <= self.config.ws_price_max_age_ms
+                {
+                    return Some(snapshot.mid_price);
                 }
             }
         }
-
-        let rest_fallback_count = used_rest_fallback.iter().filter(|&&b| b).count();
-        let avg_ws_age_ms = {
+        match self
+            .polymarket
+            .get_token_price(&pos.market_id, &pos.outcome)
+            .await
+        {
+            Ok(p) if p > 0.0 && p < 1.0 => Some(p),
+            Ok(p) => {
+                warn!(
+                    "Ignoring out-of-range price for market {} outcome {}: {:.5}",
+                    pos.market_id, pos.outcome, p
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to get REST price for market {} outcome {}: {}",
+                    pos.market_id, pos.outcome, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Close `pos` at `exit_price` with the given status, updating the balance.
+    ///
+    /// Returns `false` if the live close order failed and the position is
+    /// still open.
+    async fn exit_position(
+        &mut self,
+        pos: &Position,
+        status: &str,
+        exit_price: f64,
+    ) -> Result<bool> {
+        let Some(pos_id) = pos.id else {
+            return Ok(false);
+        };
+        let pnl = Self::position_net_pnl(pos, exit_price);
+        if !self.config.dry_run {
+            if let Err(e) = self
+                .polymarket
+                .close_position(&pos.market_id, &pos.outcome, pos.size_usd)
+                .await
+            {
+                error!("Failed to close position {} ({}): {}", pos_id, status, e);
+                return Ok(false);
+            }
+        }
+        self.db.close_position(pos_id, status, exit_price, pnl)?;
+        self.balance += pos.size_usd + pnl;
+        self.db.record_balance(self.balance)?;
+        Ok(true)
+    }
+
+    /// Handle a score correction / VAR reversal.
+    ///
+    /// Open positions whose triggering event is reverted by the correction are
+    /// flagged as correction-induced, then either re-priced at the corrected
+    /// score (kept only if the edge still clears the entry threshold) or
+    /// exited immediately.
+    async fn on_score_correction(&mut self, event: &ScoreEvent, game: &LiveGame) -> Result<()> {
+        let open = self.db.list_open_positions()?;
+        let mut affected = Vec::new();
+        for pos in open {
+            if pos.fixture_id.as_deref() != Some(event.event_id.as_str())
+                || !Self::uses_exit_policies(&pos)
+            {
+                continue;
+            }
+            let Some(entry_event_id) = pos.entry_score_event_id else {
+                continue;
+            };
+            let Some(entry_event) = self.db.get_score_event(entry_event_id)? else {
+                continue;
+            };
+            if Self::correction_invalidates_entry(&entry_event, event) {
+                affected.push((pos, entry_event));
+            }
+        }
+
+        if affected.is_empty() {
+            info!(
+                "Score correction for {} ({}-{}) does not affect open positions",
+                event.event_id, event.home_score, event.away_score
+            );
+            return Ok(());
+        }
+
+        for (pos, entry_event) in affected {
+            let Some(pos_id) = pos.id else {
+                continue;
+            };
+            let sport = pos.sport.as_deref().unwrap_or(&game.sport);
+            let league = pos.league.as_deref().unwrap_or(&game.league);
+            let profile = self.profiles.resolve(sport, Some(league));
+            let threshold =
+                self.entry_min_edge(&profile.config, sport) + self.round_trip_cost_edge();
+            warn!(
+                "Score correction {}-{} reverts entry event {}-{} ({}) for position {}",
+                event.home_score,
+                event.away_score,
+                entry_event.home_score,
+                entry_event.away_score,
+                entry_event.event_type,
+                pos_id
+            );
+            self.db.mark_position_correction_induced(pos_id)?;
+
+            let Some(current_price) = self.current_position_price(&pos).await else {
+                warn!(
+                    "No usable price for correction-hit position {}; leaving it to the next sweep",
+                    pos_id
+                );
+                continue;
+            };
+
+            if self.config.score_correction_reevaluate {
+                if let Some(fair) = self.position_fair_value(&pos, game) {
+                    let corrected_edge = edge(fair, current_price);
+                    if corrected_edge >= threshold {
+                        info!(
+                            "Keeping position {} after correction: fair={:.3}, price={:.3}, edge={:.3} >= {:.3}",
+                            pos_id, fair, current_price, corrected_edge, threshold
+                        );
+                        continue;
+                    }
+                    info!(
+                        "Correction removes edge on position {}: fair={:.3}, price={:.3}, edge={:.3} < {:.3}",
+                        pos_id, fair, current_price, corrected_edge, threshold
+                    );
+                }
+            }
+
+            if self
+                .exit_position(&pos, "closed_score_correction", current_price)
+                .await?
+            {
+                warn!(
+                    "Closed position {} after score correction at {:.3}",
+                    pos_id, current_price
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Remember the latest state of a fixture.
+    fn track_fixture_game(&mut self, game: &LiveGame, now: DateTime<Utc>) {
+        let state = self
+            .fixture_states
+            .entry(game.event_id.clone())
+            .or_insert_with(|| FixtureState {
+                game: game.clone(),
+                clock: GameClock::new(&game.sport),
+                last_seen: now,
+            });
+        state.game = game.clone();
+        state.clock.observe(game, now);
+        state.last_seen = now;
+    }
+
+    /// Refresh fixtures the engine already follows from a monitor poll, so
+    /// their clocks keep running between score and state events.
+    pub fn on_fixture_snapshot(&mut self, games: &[LiveGame]) {
+        let now = Utc::now();
+        for game in games {
+            if self.fixture_states.contains_key(&game.event_id) {
+                self.track_fixture_game(game, now);
+            }
+        }
+    }
+
+    /// Latest state of a fixture with the game clock extrapolated to `now`.
+    ///
+    /// `None` once the fixture has gone unreported for longer than the clock
+    /// extrapolates: its minute would be stuck, so model fair values, game
+    /// progress and maker quotes derived from it would be wrong.
+    fn current_fixture_game(&self, fixture_id: &str, now: DateTime<Utc>) -> Option<LiveGame> {
+        let state = self.fixture_states.get(fixture_id)?;
+        if (now - state.last_seen).num_seconds() > MAX_EXTRAPOLATION_SECS {
+            return None;
+        }
+        let mut game = state.game.clone();
+        if let Some(minute) = state.clock.minute_at(now) {
+            game.minute = Some(minute);
+        }
+        Some(game)
+    }
+
+    /// Whether the exit policies manage `pos`.  Hedged pairs and arbitrage
+    /// baskets are held to resolution; maker inventory is worked by the quoter.
+    fn uses_exit_policies(pos: &Position) -> bool {
+        pos.group_id.is_none() && pos.strategy == "directional"
+    }
+
+    fn exit_policy_params(config: &Config) -> ExitPolicyParams {
+        ExitPolicyParams {
+            trailing_stop_fraction: config.trailing_stop_fraction,
+            time_decay_min_band_fraction: config.time_decay_min_band_fraction,
+            breakeven_trigger_fraction: config.breakeven_trigger_fraction,
+        }
+    }
+
+    /// Strategy profile of the sport and league `pos` was opened in.
+    fn position_profile(&self, pos: &Position) -> Arc<StrategyProfile> {
+        self.profiles
+            .resolve(pos.sport.as_deref().unwrap_or(""), pos.league.as_deref())
+    }
+
+    /// Fraction of a group to sell so it falls back under its cap, given
+    /// `(exposure_usd, cap_usd)` for each group a position belongs to.
+    /// Returns the largest overshoot; reductions under 5% are ignored to
+    /// avoid churning on small equity moves.
+    fn exposure_reduction_fraction(groups: &[(f64, f64)]) -> f64 {
+        let fraction = groups
+            .iter()
+            .filter(|(exposure, cap)| *exposure > *cap && *exposure > 0.0)
+            .map(|(exposure, cap)| 1.0 - cap / exposure)
+            .fold(0.0, f64::max);
+        if fraction < 0.05 {
+            0.0
+        } else {
+            fraction
+        }
+    }
+
+    /// Per-position sell fractions that bring every event / sport / league
+    /// group back under its exposure cap (pro rata within a group).
+    fn exposure_reduction_fractions(&self, open: &[Position]) -> Vec<f64> {
+        let total_open_notional: f64 = open.iter().map(|p| p.size_usd).sum();
+        let total_equity = (self.balance + total_open_notional).max(1.0);
+        let group_sum = |key: fn(&Position) -> Option<&str>, value: Option<&str>| -> f64 {
+            open.iter()
+                .filter(|p| value.is_some() && key(p) == value)
+                .map(|p| p.size_usd)
+                .sum()
+        };
+        open.iter()
+            .map(|pos| {
+                let groups = [
+                    (
+                        group_sum(|p| p.event_name.as_deref(), pos.event_name.as_deref()),
+                        self.config.max_event_exposure_fraction * total_equity,
+                    ),
+                    (
+                        group_sum(|p| p.sport.as_deref(), pos.sport.as_deref()),
+                        self.config.max_sport_exposure_fraction * total_equity,
+                    ),
+                    (
+                        group_sum(|p| p.league.as_deref(), pos.league.as_deref()),
+                        self.config.max_league_exposure_fraction * total_equity,
+                    ),
+                ];
+                Self::exposure_reduction_fraction(&groups)
+            })
+            .collect()
+    }
+
+    /// Sell `fraction` of an open position at `price`, booking the slice's P&L.
+    ///
+    /// Returns `false` if nothing was sold (slice below $1 or the live order failed).
+    async fn reduce_position(
+        &mut self,
+        pos: &Position,
+        fraction: f64,
+        price: f64,
+        reason: &str,
+    ) -> Result<bool> {
+        let Some(pos_id) = pos.id else {
+            return Ok(false);
+        };
+        let sell_size = pos.size_usd * fraction.clamp(0.0, 1.0);
+        if sell_size < 1.0 {
+            return Ok(false);
+        }
+        let slice = Position {
+            size_usd: sell_size,
+            ..pos.clone()
+        };
+        let pnl = Self::position_net_pnl(&slice, price);
+        if !self.config.dry_run {
+            if let Err(e) = self
+                .polymarket
+                .close_position(&pos.market_id, &pos.outcome, sell_size)
+                .await
+            {
+                error!("Failed to reduce position {} ({}): {}", pos_id, reason, e);
+                return Ok(false);
+            }
+        }
+        self.db
+            .partial_close_position(pos_id, sell_size, price, pnl, reason)?;
+        self.balance += sell_size + pnl;
+        self.db.record_balance(self.balance)?;
+        Ok(true)
+    }
+
+    /// Legs that pay out exactly when `pos` loses: the opposite token, and in
+    /// sports without draws the other team's winner market.
+    async fn hedge_candidates(&mut self, pos: &Position) -> Result<Vec<HedgeCandidate>> {
+        let mut candidates = Vec::new();
+        let opposite = if pos.outcome.eq_ignore_ascii_case("YES") {
+            "NO"
+        } else {
+            "YES"
+        };
+        let flipped = Position {
+            outcome: opposite.to_string(),
+            asset_id: None,
+            ..pos.clone()
+        };
+        if let Some(price) = self.current_position_price(&flipped).await {
+            candidates.push(HedgeCandidate {
+                market_id: pos.market_id.clone(),
+                outcome: opposite.to_string(),
+                price,
+                yes_is_home: pos.yes_is_home,
+            });
+        }
+
+        let (Some(sport), Some(yes_is_home), Some((home, away))) = (
+            pos.sport.as_deref(),
+            pos.yes_is_home,
+            pos.event_name.as_deref().and_then(|n| n.split_once(" vs ")),
+        ) else {
+            return Ok(candidates);
+        };
+        if sport_has_draws(sport) {
+            return Ok(candidates);
+        }
+        let backs_home = pos.outcome.eq_ignore_ascii_case("YES") == yes_is_home;
+        let league = pos.league.clone().unwrap_or_default();
+        for market in self.market_cache.search(home, away, &league).await {
+            if market.id == pos.market_id
+                || Self::infer_yes_is_home(&market, home, away) != Some(!backs_home)
+            {
+                continue;
+            }
+            let leg = Position {
+                market_id: market.id.clone(),
+                outcome: "YES".into(),
+                asset_id: None,
+                ..pos.clone()
+            };
+            if let Some(price) = self.current_position_price(&leg).await {
+                self.db.upsert_market(&market)?;
+                candidates.push(HedgeCandidate {
+                    market_id: market.id.clone(),
+                    outcome: "YES".into(),
+                    price,
+                    yes_is_home: Some(!backs_home),
+                });
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Hedge a stopped-out position instead of selling it when the locked-in
+    /// P&L beats `sell_pnl`.  Returns `true` if the hedge leg was bought, or
+    /// in live mode placed: it then rests as a pending entry and is grouped
+    /// with the position once it fills.
+    async fn hedge_position(&mut self, pos: &Position, pos_id: i64, sell_pnl: f64) -> Result<bool> {
+        let candidates = self.hedge_candidates(pos).await?;
+        let one_way_cost = self.round_trip_cost_edge() / 2.0;
+        let Some(plan) = best_hedge(pos, candidates, one_way_cost) else {
+            return Ok(false);
+        };
+        if plan.locked_pnl <= sell_pnl {
+            info!(
+                "Hedge for position {} locks ${:.2} vs ${:.2} selling; selling instead",
+                pos_id, plan.locked_pnl, sell_pnl
+            );
+            return Ok(false);
+        }
+        let available = self.balance - self.pending_stake_usd();
+        if plan.hedge_size_usd > available {
+            warn!(
+                "Hedge ${:.2} for position {} exceeds available balance ${:.2}",
+                plan.hedge_size_usd, pos_id, available
+            );
+            return Ok(false);
+        }
+
+        let leg = &plan.candidate;
+        info!(
+            "Hedging position {} with {} {} at {:.3}: size=${:.2} (ratio {:.2}), locked pnl=${:.2} vs ${:.2} selling",
+            pos_id,
+            leg.market_id,
+            leg.outcome,
+            leg.price,
+            plan.hedge_size_usd,
+            plan.hedge_ratio,
+            plan.locked_pnl,
+            sell_pnl
+        );
+        let (stop_loss, take_profit) = compute_levels(
+            leg.price,
+            self.config.stop_loss_fraction,
+            self.config.take_profit_fraction,
+        );
+        let hedge = Position {
+            estimated_round_trip_cost_bps: self.estimated_round_trip_cost_bps(),
+            stop_loss_price: stop_loss,
+            take_profit_price: take_profit,
+            sport: pos.sport.clone(),
+            league: pos.league.clone(),
+            event_name: pos.event_name.clone(),
+            fixture_id: pos.fixture_id.clone(),
+            yes_is_home: leg.yes_is_home,
+            model_version: pos.model_version.clone(),
+            ..Position::new_open(
+                leg.market_id.clone(),
+                leg.outcome.clone(),
+                "hedge",
+                plan.hedge_size_usd,
+                leg.price,
+                self.config.dry_run,
+            )
+        };
+
+        if !self.config.dry_run {
+            let order_id = match self
+                .polymarket
+                .place_order(&leg.market_id, &leg.outcome, plan.hedge_size_usd, leg.price)
+                .await
+            {
+                Ok(order_id) => order_id,
+                Err(e) => {
+                    error!("Failed to place hedge for position {}: {}", pos_id, e);
+                    return Ok(false);
+                }
+            };
+            // Paying more per hedge share gives up locked P&L one for one;
+            // beyond this price selling is better.
+            let shares = plan.hedge_size_usd / leg.price;
+            let max_price = leg.price + (plan.locked_pnl - sell_pnl) / shares;
+            info!(
+                "Hedge order {} resting at {:.3} (max {:.3})",
+                order_id, leg.price, max_price
+            );
+            self.pending_entries.push(PendingEntry {
+                order_id,
+                position: hedge,
+                add_to: None,
+                limit_price: leg.price,
+                order_matched: 0.0,
+                filled_shares: 0.0,
+                filled_usd: 0.0,
+                model_prob: 0.0,
+                threshold_edge: 0.0,
+                placed_at: Utc::now(),
+                reprices: 0,
+                kind: EntryKind::Hedge { hedged_id: pos_id },
+                max_price: Some(max_price),
+                finished: None,
+            });
+            self.persist_pending_entries()?;
+            return Ok(true);
+        }
+
+        let hedge = Position {
+            group_id: Some(pos_id),
+            ..hedge
+        };
+        self.db.insert_position(&hedge)?;
+        self.db.set_position_group(pos_id, pos_id)?;
+        self.balance -= plan.hedge_size_usd;
+        self.db.record_balance(self.balance)?;
+        Ok(true)
+    }
+
+    /// Scan cached markets for mutually exclusive baskets priced away from $1
+    /// (see [`super::arbitrage`]) and report or buy them per `arbitrage_mode`.
+    pub async fn scan_arbitrage(&mut self) -> Result<()> {
+        let mode = self
+            .config
+            .arbitrage_mode
+            .parse::<ArbitrageMode>()
+            .unwrap_or(ArbitrageMode::Off);
+        if mode == ArbitrageMode::Off {
+            return Ok(());
+        }
+        self.settle_resolved_arbitrage().await?;
+        if self.controls.entries_paused {
+            return Ok(());
+        }
+
+        let mut sets = group_exclusive_markets(&self.market_cache.active_markets().await);
+        let controls = &self.controls;
+        sets.retain(|(event_name, legs)| !Self::arbitrage_set_blocked(controls, event_name, legs));
+        // Watch the most liquid events only; every leg needs a WS subscription.
+        sets.sort_by(|a, b| {
+            let volume = |set: &[(Market, Leg)]| -> f64 {
+                set.iter().map(|(m, _)| m.volume.unwrap_or(0.0)).sum()
+            };
+            volume(&b.1).total_cmp(&volume(&a.1))
+        });
+        sets.truncate(MAX_ARBITRAGE_EVENTS);
+
+        let now_ms = Utc::now().timestamp_millis().max(0) as u64;
+        let one_way_fee = self.round_trip_cost_edge() / 2.0;
+        let mut watched = HashSet::new();
+        for (event_name, legs) in &sets {
+            let mut quotes = Vec::with_capacity(legs.len());
+            for (market, leg) in legs {
+                let Some(asset_id) = self.ensure_asset_subscription(&market.id, "YES").await else {
+                    break;
+                };
+                watched.insert(asset_id.clone());
+                let Some(snapshot) = self.price_feed.get_price(&asset_id).await else {
+                    continue;
+                };
+                if now_ms.saturating_sub(snapshot.last_updated_ms)
+                    <= self.config.ws_price_max_age_ms
+                {
+                    quotes.push(LegQuote {
+                        market_id: market.id.clone(),
+                        leg: *leg,
+                        best_bid: snapshot.best_bid,
+                        best_ask: snapshot.best_ask,
+                    });
+                }
+            }
+            if quotes.len() != legs.len() {
+                continue;
+            }
+            let Some(opp) = find_opportunity(
+                event_name,
+                &quotes,
+                one_way_fee,
+                self.config.arbitrage_min_edge,
+            ) else {
+                continue;
+            };
+            info!(
+                "Arbitrage on '{}': buy {} basket at {:.3} per set, edge {:.3} per set",
+                opp.event_name,
+                opp.side.outcome(),
+                opp.cost_per_set,
+                opp.edge_per_set
+            );
+            if mode == ArbitrageMode::Execute {
+                self.execute_arbitrage(&opp, legs).await?;
+            }
+        }
+        self.arbitrage_asset_ids = watched;
+        Ok(())
+    }
+
+    /// Whether manual controls block any leg of an arbitrage set: its market,
+    /// its sport or either team of its event.
+    fn arbitrage_set_blocked(
+        controls: &ManualControls,
+        event_name: &str,
+        legs: &[(Market, Leg)],
+    ) -> bool {
+        legs.iter().any(|(market, _)| {
+            let teams = market
+                .event_name
+                .as_deref()
+                .and_then(event_teams)
+                .or_else(|| event_teams(event_name));
+            controls.market_blocked(&market.id)
+                || market
+                    .sport
+                    .as_deref()
+                    .is_some_and(|sport| controls.sport_block(sport).is_some())
+                || teams.is_some_and(|(home, away)| {
+                    controls.team_blocked(home) || controls.team_blocked(away)
+                })
+        })
+    }
+
+    /// Buy every leg of an arbitrage basket in equal share counts.
+    ///
+    /// Live legs rest as pending entries and are booked together once all
+    /// of them finished (see `settle_arbitrage_baskets`); a leg that cannot
+    /// be placed cancels the ones already resting.
+    async fn execute_arbitrage(
+        &mut self,
+        opp: &ArbitrageOpportunity,
+        legs: &[(Market, Leg)],
+    ) -> Result<()> {
+        let open = self.db.list_open_positions()?;
+        if open.iter().any(|p| {
+            p.strategy == "arbitrage" && p.event_name.as_deref() == Some(opp.event_name.as_str())
+        }) || self.pending_entries.iter().any(
+            |e| matches!(&e.kind, EntryKind::ArbitrageLeg { basket } if *basket == opp.event_name),
+        ) {
+            return Ok(());
+        }
+        if self.feed_health_blocking_entries() {
+            warn!("Feed-health breaker active: not executing arbitrage");
+            return Ok(());
+        }
+        let budget = self
+            .config
+            .arbitrage_max_stake_usd
+            .min(self.balance - self.pending_stake_usd());
+        let shares = budget / opp.cost_per_set;
+        let outcome = opp.side.outcome();
+        if opp.legs.iter().any(|(_, price)| shares * price < 1.0) {
+            info!(
+                "Arbitrage basket on '{}' too small for the available budget",
+                opp.event_name
+            );
+            return Ok(());
+        }
+
+        // Each leg may chase the market by its share of the basket edge.
+        let chase = opp.edge_per_set / opp.legs.len() as f64;
+        let mut group_id = None;
+        for ((market_id, price), (market, leg)) in opp.legs.iter().zip(legs) {
+            let size_usd = shares * price;
+            self.db.upsert_market(market)?;
+            let pos = Position {
+                entry_price_source: Some("ws".into()),
+                estimated_round_trip_cost_bps: self.estimated_round_trip_cost_bps(),
+                sport: market.sport.clone(),
+                league: market.league.clone(),
+                event_name: Some(opp.event_name.clone()),
+                market_slug: market.slug.clone(),
+                yes_is_home: match leg {
+                    Leg::Home => Some(true),
+                    Leg::Away => Some(false),
+                    Leg::Draw => None,
+                },
+                group_id,
+                ..Position::new_open(
+                    market_id.clone(),
+                    outcome.to_string(),
+                    "arbitrage",
+                    size_usd,
+                    *price,
+                    self.config.dry_run,
+                )
+            };
+            if !self.config.dry_run {
+                let placed = self
+                    .polymarket
+                    .place_order(market_id, outcome, size_usd, *price)
+                    .await;
+                let failed = placed.is_err();
+                let (order_id, finished) = match placed {
+                    Ok(order_id) => (order_id, None),
+                    Err(e) => {
+                        error!(
+                            "Arbitrage leg {} {} failed on '{}': {}",
+                            market_id, outcome, opp.event_name, e
+                        );
+                        (String::new(), Some("order_failed".to_string()))
+                    }
+                };
+                // A failed leg stays in the basket unfilled, so the basket
+                // settles at zero sets and unwinds whatever the others got.
+                self.pending_entries.push(PendingEntry {
+                    order_id,
+                    position: pos,
+                    add_to: None,
+                    limit_price: *price,
+                    order_matched: 0.0,
+                    filled_shares: 0.0,
+                    filled_usd: 0.0,
+                    model_prob: 0.0,
+                    threshold_edge: 0.0,
+                    placed_at: Utc::now(),
+                    reprices: 0,
+                    kind: EntryKind::ArbitrageLeg {
+                        basket: opp.event_name.clone(),
+                    },
+                    max_price: Some(price + chase),
+                    finished,
+                });
+                if failed {
+                    self.cancel_arbitrage_basket(&opp.event_name, "basket_broken")
+                        .await;
+                    break;
+                }
+                continue;
+            }
+            let id = self.db.insert_position(&pos)?;
+            if group_id.is_none() {
+                self.db.set_position_group(id, id)?;
+                group_id = Some(id);
+            }
+            self.balance -= size_usd;
+            self.db.record_balance(self.balance)?;
+        }
+        if !self.config.dry_run {
+            self.settle_arbitrage_baskets().await?;
+            self.persist_pending_entries()?;
+        }
+        Ok(())
+    }
+
+    /// Cancel the resting legs of an arbitrage basket, recording what they
+    /// filled.  A leg whose cancel fails stays live and is retried on the
+    /// next sweep.
+    async fn cancel_arbitrage_basket(&mut self, basket: &str, reason: &str) {
+        for idx in 0..self.pending_entries.len() {
+            let entry = &self.pending_entries[idx];
+            let in_basket =
+                matches!(&entry.kind, EntryKind::ArbitrageLeg { basket: b } if b == basket);
+            if !in_basket || entry.finished.is_some() {
+                continue;
+            }
+            let order_id = entry.order_id.clone();
+            if let Err(e) = self.polymarket.cancel_order(&order_id).await {
+                error!(
+                    "Failed to cancel arbitrage leg {} on '{}': {}",
+                    order_id, basket, e
+                );
+                continue;
+            }
+            let matched = self
+                .polymarket
+                .get_order_status(&order_id)
+                .await
+                .ok()
+                .map(|status| status.size_matched);
+            let entry = &mut self.pending_entries[idx];
+            if let Some(matched) = matched {
+                entry.record_matched(matched);
+            }
+            entry.finished = Some(reason.to_string());
+        }
+    }
+
+    /// Cancel the rest of every basket with a leg that finished without
+    /// filling completely.
+    async fn break_failed_baskets(&mut self) {
+        let broken: HashSet<String> = self
+            .pending_entries
+            .iter()
+            .filter(|e| {
+                e.finished
+                    .as_deref()
+                    .is_some_and(|reason| reason != "filled")
+            })
+            .filter_map(|e| match &e.kind {
+                EntryKind::ArbitrageLeg { basket } => Some(basket.clone()),
+                _ => None,
+            })
+            .collect();
+        for basket in broken {
+            self.cancel_arbitrage_basket(&basket, "basket_broken").await;
+        }
+    }
+
+    /// Book every basket whose legs have all finished.
+    async fn settle_arbitrage_baskets(&mut self) -> Result<()> {
+        let baskets: HashSet<String> = self
+            .pending_entries
+            .iter()
+            .filter_map(|e| match &e.kind {
+                EntryKind::ArbitrageLeg { basket } => Some(basket.clone()),
+                _ => None,
+            })
+            .collect();
+        for basket in baskets {
+            let in_basket = |e: &PendingEntry| matches!(&e.kind, EntryKind::ArbitrageLeg { basket: b } if *b == basket);
+            if self
+                .pending_entries
+                .iter()
+                .any(|e| in_basket(e) && e.finished.is_none())
+            {
+                continue;
+            }
+            let (legs, rest) = std::mem::take(&mut self.pending_entries)
+                .into_iter()
+                .partition(in_basket);
+            self.pending_entries = rest;
+            self.book_arbitrage_basket(&basket, legs).await?;
+        }
+        Ok(())
+    }
+
+    /// Book a finished basket as complete sets only: every leg is sized to
+    /// the smallest fill and the excess shares are sold back.
+    async fn book_arbitrage_basket(&mut self, basket: &str, legs: Vec<PendingEntry>) -> Result<()> {
+        let sets = legs
+            .iter()
+            .map(|leg| leg.filled_shares)
+            .fold(f64::INFINITY, f64::min);
+        let mut group_id = None;
+        for mut leg in legs {
+            let reason = leg.finished.clone().unwrap_or_default();
+            leg.position.group_id = group_id;
+            let Some(id) = self.book_pending_entry(&leg, &reason)? else {
+                continue;
+            };
+            if group_id.is_none() {
+                self.db.set_position_group(id, id)?;
+                group_id = Some(id);
+            }
+            let excess = leg.filled_shares - sets;
+            if excess <= 1e-9 {
+                continue;
+            }
+            let Some(pos) = self
+                .db
+                .list_open_positions()?
+                .into_iter()
+                .find(|p| p.id == Some(id))
+            else {
+                continue;
+            };
+            let price = match self.current_position_price(&pos).await {
+                Some(price) => price,
+                None => pos.entry_price,
+            };
+            let unwound = if sets <= 1e-9 {
+                self.exit_position(&pos, "closed_unwound", price).await?
+            } else {
+                self.reduce_position(&pos, excess / leg.filled_shares, price, "arbitrage_unwind")
+                    .await?
+            };
+            if !unwound {
+                warn!(
+                    "Could not unwind {:.2} excess shares of arbitrage leg {} on '{}'",
+                    excess, id, basket
+                );
+            }
+        }
+        info!(
+            "Arbitrage basket on '{}' settled at {:.2} sets",
+            basket, sets
+        );
+        Ok(())
+    }
+
+    /// Close arbitrage legs whose market has resolved at their payout.
+    async fn settle_resolved_arbitrage(&mut self) -> Result<()> {
+        let legs: Vec<Position> = self
+            .db
+            .list_open_positions()?
+            .into_iter()
+            .filter(|p| p.strategy == "arbitrage")
+            .collect();
+        for pos in legs {
+            let Some(pos_id) = pos.id else {
+                continue;
+            };
+            let resolved = match self
+                .polymarket
+                .get_market_resolved_outcome(&pos.market_id)
+                .await
+            {
+                Ok(Some(outcome)) => outcome,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Failed to check resolution of {}: {}", pos.market_id, e);
+                    continue;
+                }
+            };
+            let payout = if resolved.eq_ignore_ascii_case(&pos.outcome) {
+                1.0
+            } else {
+                0.0
+            };
+            let pnl = Self::settlement_pnl(&pos, payout);
+            self.db
+                .close_position(pos_id, "closed_settled", payout, pnl)?;
+            self.balance += pos.size_usd + pnl;
+            self.db.record_balance(self.balance)?;
+            info!(
+                "Settled arbitrage leg {} ({} {}): payout={:.0}, pnl=${:.2}",
+                pos_id, pos.market_id, pos.outcome, payout, pnl
+            );
+        }
+        Ok(())
+    }
+
+    fn quote_params(&self) -> QuoteParams {
+        QuoteParams {
+            half_spread: self.config.mm_half_spread,
+            inventory_skew: self.config.mm_inventory_skew,
+            max_inventory_usd: self.config.mm_max_inventory_usd,
+            max_quote_usd: self.config.mm_max_quote_usd,
+            depth_fraction: self.config.mm_depth_fraction,
+        }
+    }
+
+    /// Open maker inventory in a market, if any.
+    fn maker_position(&self, market_id: &str) -> Result<Option<Position>> {
+        Ok(self
+            .db
+            .list_open_positions()?
+            .into_iter()
+            .find(|p| p.strategy == "market_making" && p.market_id == market_id))
+    }
+
+    async fn cancel_quote(&self, market_id: &str, quote: &RestingQuote) {
+        if !self.config.dry_run {
+            if let Err(e) = self.polymarket.cancel_order(&quote.order_id).await {
+                error!(
+                    "Failed to cancel maker {} {} in {}: {}",
+                    quote.side.as_str(),
+                    quote.order_id,
+                    market_id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Pull resting maker quotes on one fixture, or on every fixture.  Each
+    /// order is cancelled and then polled once more, so shares that filled
+    /// before the cancel landed are booked rather than lost with the quote.
+    /// A quote whose order cannot be polled stays in its book until the next
+    /// fill sync sees it dead.
+    async fn cancel_maker_quotes(&mut self, fixture_id: Option<&str>) -> Result<()> {
+        let market_ids: Vec<String> = self
+            .maker_books
+            .iter()
+            .filter(|(_, book)| fixture_id.is_none_or(|id| book.fixture_id == id))
+            .map(|(market_id, _)| market_id.clone())
+            .collect();
+        for market_id in market_ids {
+            let Some(book) = self.maker_books.get(&market_id).cloned() else {
+                continue;
+            };
+            for quote in book.bid.iter().chain(book.ask.iter()) {
+                self.cancel_quote(&market_id, quote).await;
+                if self
+                    .sync_quote_fill(&market_id, &book, quote)
+                    .await?
+                    .is_none()
+                {
+                    continue;
+                }
+                if let Some(book) = self.maker_books.get_mut(&market_id) {
+                    match quote.side {
+                        QuoteSide::Bid => book.bid = None,
+                        QuoteSide::Ask => book.ask = None,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Book shares newly filled on one resting quote into the market's maker
+    /// inventory position.  Returns the order's `(matched_shares, live)`, or
+    /// `None` when it could not be polled.
+    async fn sync_quote_fill(
+        &mut self,
+        market_id: &str,
+        book: &MakerBook,
+        quote: &RestingQuote,
+    ) -> Result<Option<(f64, bool)>> {
+        let (matched, live) = if self.config.dry_run {
+            let now_ms = Utc::now().timestamp_millis().max(0) as u64;
+            let snapshot = match book.asset_id.as_deref() {
+                Some(asset_id) => self.price_feed.get_price(asset_id).await,
+                None => None,
+            }
+            .filter(|s| {
+                now_ms.saturating_sub(s.last_updated_ms) <= self.config.ws_price_max_age_ms
+            });
+            match snapshot {
+                Some(s) if quote_crossed(quote, s.best_bid, s.best_ask) => (quote.shares, false),
+                _ => (quote.filled_shares, true),
+            }
+        } else {
+            match self.polymarket.get_order_status(&quote.order_id).await {
+                Ok(status) => (status.size_matched.min(quote.shares), status.live),
+                Err(e) => {
+                    warn!("Failed to poll maker order {}: {}", quote.order_id, e);
+                    return Ok(None);
+                }
+            }
+        };
+        let new_shares = matched - quote.filled_shares;
+        if new_shares > 1e-9 {
+            self.book_maker_fill(market_id, book, quote.side, quote.price, new_shares)
+                .await?;
+        }
+        Ok(Some((matched, live)))
+    }
+
+    /// Book newly filled shares on resting maker quotes into the market's
+    /// maker inventory position.
+    async fn sync_maker_fills(&mut self) -> Result<()> {
+        let market_ids: Vec<String> = self.maker_books.keys().cloned().collect();
+        for market_id in market_ids {
+            let Some(book) = self.maker_books.get(&market_id).cloned() else {
+                continue;
+            };
+            for quote in book.bid.iter().chain(book.ask.iter()) {
+                let Some((matched, live)) = self.sync_quote_fill(&market_id, &book, quote).await?
+                else {
+                    continue;
+                };
+                if let Some(book) = self.maker_books.get_mut(&market_id) {
+                    let slot = match quote.side {
+                        QuoteSide::Bid => &mut book.bid,
+                        QuoteSide::Ask => &mut book.ask,
+                    };
+                    if live {
+                        if let Some(resting) = slot.as_mut() {
+                            resting.filled_shares = matched;
+                        }
+                    } else {
+                        *slot = None;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Record `shares` filled at `price` on a maker quote.
+    async fn book_maker_fill(
+        &mut self,
+        market_id: &str,
+        book: &MakerBook,
+        side: QuoteSide,
+        price: f64,
+        shares: f64,
+    ) -> Result<()> {
+        let held = self.maker_position(market_id)?;
+        match side {
+            QuoteSide::Bid => {
+                let size_usd = shares * price;
+                match held {
+                    Some(pos) => {
+                        let pos_id = pos.id.expect("stored position has an id");
+                        let avg =
+                            average_entry_price(pos.size_usd, pos.entry_price, size_usd, price);
+                        self.db.add_to_position(
+                            pos_id,
+                            size_usd,
+                            price,
+                            avg,
+                            (pos.stop_loss_price, pos.take_profit_price),
+                            "maker_bid",
+                        )?;
+                    }
+                    None => {
+                        let fixture = self.current_fixture_game(&book.fixture_id, Utc::now());
+                        let pos = Position {
+                            asset_id: book.asset_id.clone(),
+                            entry_price_source: Some("maker".into()),
+                            estimated_round_trip_cost_bps: self.estimated_round_trip_cost_bps(),
+                            sport: fixture.as_ref().map(|g| g.sport.clone()),
+                            league: fixture.as_ref().map(|g| g.league.clone()),
+                            event_name: fixture
+                                .as_ref()
+                                .map(|g| format!("{} vs {}", g.home_team, g.away_team)),
+                            fixture_id: Some(book.fixture_id.clone()),
+                            yes_is_home: Some(book.yes_is_home),
+                            ..Position::new_open(
+                                market_id,
+                                "YES",
+                                "market_making",
+                                size_usd,
+                                price,
+                                self.config.dry_run,
+                            )
+                        };
+                        self.db.insert_position(&pos)?;
+                    }
+                }
+                self.balance -= size_usd;
+                info!(
+                    "Maker bid filled in {}: {:.2} shares at {:.3}",
+                    market_id, shares, price
+                );
+            }
+            QuoteSide::Ask => {
+                let Some(pos) = held else {
+                    warn!("Maker ask filled in {} without inventory", market_id);
+                    return Ok(());
+                };
+                let pos_id = pos.id.expect("stored position has an id");
+                let cost_released = (shares * pos.entry_price).min(pos.size_usd);
+                let slice = Position {
+                    size_usd: cost_released,
+                    ..pos.clone()
+                };
+                let pnl = Self::position_net_pnl(&slice, price);
+                if cost_released >= pos.size_usd - 1e-6 {
+                    self.db.close_position(pos_id, "closed_maker", price, pnl)?;
+                } else {
+                    self.db.partial_close_position(
+                        pos_id,
+                        cost_released,
+                        price,
+                        pnl,
+                        "maker_ask",
+                    )?;
+                }
+                self.balance += cost_released + pnl;
+                info!(
+                    "Maker ask filled in {}: {:.2} shares at {:.3}, pnl=${:.2}",
+                    market_id, shares, price, pnl
+                );
+            }
+        }
+        self.db.record_balance(self.balance)?;
+        Ok(())
+    }
+
+    /// Re-quote every live fixture's winner markets around model fair value
+    /// (market-making mode).  Fills are booked first; the feed-health breaker,
+    /// the daily circuit breaker and the exposure caps pull or block quotes.
+    pub async fn refresh_maker_quotes(&mut self) -> Result<()> {
+        if !self.config.market_making_enabled {
+            return Ok(());
+        }
+        self.sync_maker_fills().await?;
+
+        let open = self.db.list_open_positions()?;
+        let total_equity = (self.balance + open.iter().map(|p| p.size_usd).sum::<f64>()).max(1.0);
+        let breaker_active = self.feed_health_blocking_entries()
+            || self.daily_drawdown_fraction(total_equity)
+                >= self.config.max_daily_drawdown_fraction;
+        if breaker_active {
+            self.cancel_maker_quotes(None).await?;
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let games: Vec<LiveGame> = self
+            .fixture_states
+            .keys()
+            .filter_map(|id| self.current_fixture_game(id, now))
+            .filter(|g| g.status == GameStatus::InProgress)
+            .collect();
+        let live_fixtures: HashSet<&str> = games.iter().map(|g| g.event_id.as_str()).collect();
+        let stale: Vec<String> = self
+            .maker_books
+            .values()
+            .filter(|book| !live_fixtures.contains(book.fixture_id.as_str()))
+            .map(|book| book.fixture_id.clone())
+            .collect();
+        for fixture_id in stale {
+            self.cancel_maker_quotes(Some(&fixture_id)).await?;
+        }
+        // Keep books with inventory so their asset stays subscribed for marks.
+        self.maker_books.retain(|market_id, book| {
+            live_fixtures.contains(book.fixture_id.as_str())
+                || open
+                    .iter()
+                    .any(|p| p.strategy == "market_making" && &p.market_id == market_id)
+        });
+
+        let params = self.quote_params();
+        for game in &games {
+            let event_key = format!("{} vs {}", game.home_team, game.away_team);
+            let p_home_raw = super::win_probability::estimate_game_win_probability(game, true);
+            let p_home = self.calibrate_probability(game, p_home_raw);
+            let markets = self
+                .market_cache
+                .search(&game.home_team, &game.away_team, &game.league)
+                .await;
+            for market in markets {
+                let Some(yes_is_home) =
+                    Self::infer_yes_is_home(&market, &game.home_team, &game.away_team)
+                else {
+                    continue;
+                };
+                let fair = if yes_is_home { p_home } else { 1.0 - p_home };
+                let (inventory_usd, inventory_shares) = open
+                    .iter()
+                    .find(|p| p.strategy == "market_making" && p.market_id == market.id)
+                    .map(|p| (p.size_usd, p.size_usd / p.entry_price))
+                    .unwrap_or((0.0, 0.0));
+                let mut quotes = compute_quotes(
+                    fair,
+                    inventory_usd,
+                    inventory_shares,
+                    market.liquidity,
+                    &params,
+                );
+
+                // Manual controls and exposure caps act as kill switches on
+                // adding inventory.
+                if self.controls.sport_block(&game.sport).is_some()
+                    || self.controls.team_blocked(&game.home_team)
+                    || self.controls.team_blocked(&game.away_team)
+                    || self.controls.market_blocked(&market.id)
+                {
+                    quotes.bid = None;
+                }
+                if let Some((price, shares)) = quotes.bid {
+                    let bid_usd = price * shares;
+                    let event_exposure = open
+                        .iter()
+                        .filter(|p| p.event_name.as_deref() == Some(event_key.as_str()))
+                        .map(|p| p.size_usd)
+                        .sum::<f64>()
+                        + bid_usd;
+                    let effective_frac = self.effective_exposure_fraction_with_candidate(
+                        &open,
+                        &market.id,
+                        Some(game.sport.as_str()),
+                        Some(game.league.as_str()),
+                        Some(event_key.as_str()),
+                        bid_usd,
+                        total_equity,
+                    );
+                    if event_exposure / total_equity > self.config.max_event_exposure_fraction
+                        || effective_frac > self.config.max_effective_exposure_fraction
+                        || bid_usd > self.balance
+                    {
+                        quotes.bid = None;
+                    }
+                }
+
+                if !self.maker_books.contains_key(&market.id) {
+                    self.db.upsert_market(&market)?;
+                    let asset_id = self.ensure_asset_subscription(&market.id, "YES").await;
+                    self.maker_books.insert(
+                        market.id.clone(),
+                        MakerBook {
+                            fixture_id: game.event_id.clone(),
+                            yes_is_home,
+                            asset_id,
+                            bid: None,
+                            ask: None,
+                        },
+                    );
+                }
+                self.replace_quote(&market.id, QuoteSide::Bid, quotes.bid)
+                    .await;
+                self.replace_quote(&market.id, QuoteSide::Ask, quotes.ask)
+                    .await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Move one side of a maker book to `target` `(price, shares)`, keeping
+    /// the resting order when it already rests at the target price and size.
+    async fn replace_quote(
+        &mut self,
+        market_id: &str,
+        side: QuoteSide,
+        target: Option<(f64, f64)>,
+    ) {
+        let Some(book) = self.maker_books.get_mut(market_id) else {
+            return;
+        };
+        let slot = match side {
+            QuoteSide::Bid => &mut book.bid,
+            QuoteSide::Ask => &mut book.ask,
+        };
+        if let (Some(resting), Some(target)) = (slot.as_ref(), target) {
+            if resting.matches(target) {
+                return;
+            }
+        }
+        if let Some(resting) = slot.take() {
+            self.cancel_quote(market_id, &resting).await;
+        }
+        let Some((price, shares)) = target else {
+            return;
+        };
+
+        let order_id = if self.config.dry_run {
+            format!("dry-{}-{}", side.as_str(), Utc::now().timestamp_micros())
+        } else {
+            match self
+                .polymarket
+                .place_limit_order(market_id, "YES", side.as_str(), shares, price)
+                .await
+            {
+                Ok(id) => id,
+                Err(e) => {
+                    error!(
+                        "Failed to place maker {} in {}: {}",
+                        side.as_str(),
+                        market_id,
+                        e
+                    );
+                    return;
+                }
+            }
+        };
+        if let Some(book) = self.maker_books.get_mut(market_id) {
+            let slot = match side {
+                QuoteSide::Bid => &mut book.bid,
+                QuoteSide::Ask => &mut book.ask,
+            };
+            *slot = Some(RestingQuote {
+                order_id,
+                side,
+                price,
+                shares,
+                filled_shares: 0.0,
+                placed_at: Utc::now(),
+            });
+        }
+    }
+
+    /// Cash committed to resting entry, hedge and arbitrage orders.
+    fn pending_stake_usd(&self) -> f64 {
+        self.pending_entries
+            .iter()
+            .filter(|entry| entry.finished.is_none())
+            .map(PendingEntry::unfilled_usd)
+            .sum()
+    }
+
+    fn execution_params(&self) -> ExecutionParams {
+        ExecutionParams {
+            timeout_secs: self.config.order_timeout_secs,
+            reprice_enabled: self.config.order_reprice_enabled,
+            max_reprices: self.config.order_max_reprices,
+        }
+    }
+
+    /// Poll live entry orders: book finished ones, cancel those that timed
+    /// out or lost their edge, and cancel/replace those the market ran away
+    /// from while the edge persists.
+    async fn manage_pending_entries(&mut self) -> Result<()> {
+        if self.pending_entries.is_empty() {
+            return Ok(());
+        }
+        let params = self.execution_params();
+        let now = Utc::now();
+        for mut entry in std::mem::take(&mut self.pending_entries) {
+            if entry.finished.is_some() {
+                self.pending_entries.push(entry);
+                continue;
+            }
+            match self.polymarket.get_order_status(&entry.order_id).await {
+                Ok(status) => {
+                    entry.record_matched(status.size_matched);
+                    if !status.live {
+                        self.finish_pending_entry(entry, "filled")?;
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to poll entry order {}: {}", entry.order_id, e);
+                    self.pending_entries.push(entry);
+                    continue;
+                }
+            }
+
+            let market_price = self.current_position_price(&entry.position).await;
+            let action = next_action(&entry, market_price, now, &params);
+            let reason = match action {
+                EntryAction::Wait => {
+                    self.pending_entries.push(entry);
+                    continue;
+                }
+                EntryAction::Cancel(reason) => reason,
+                EntryAction::Replace(_) => "reprice",
+            };
+
+            if let Err(e) = self.polymarket.cancel_order(&entry.order_id).await {
+                error!(
+                    "Failed to cancel entry order {} ({}): {}",
+                    entry.order_id, reason, e
+                );
+                self.pending_entries.push(entry);
+                continue;
+            }
+            // Catch fills that raced the cancel.
+            if let Ok(status) = self.polymarket.get_order_status(&entry.order_id).await {
+                entry.record_matched(status.size_matched);
+            }
+
+            if let EntryAction::Replace(new_price) = action {
+                let remaining_usd = entry.remaining_usd_at(new_price);
+                if remaining_usd >= 1.0 {
+                    match self
+                        .polymarket
+                        .place_order(
+                            &entry.position.market_id,
+                            &entry.position.outcome,
+                            remaining_usd,
+                            new_price,
+                        )
+                        .await
+                    {
+                        Ok(order_id) => {
+                            info!(
+                                "Re-priced entry in {} {}: {:.3} -> {:.3}, ${:.2} remaining",
+                                entry.position.market_id,
+                                entry.position.outcome,
+                                entry.limit_price,
+                                new_price,
+                                remaining_usd
+                            );
+                            entry.order_id = order_id;
+                            entry.limit_price = new_price;
+                            entry.order_matched = 0.0;
+                            entry.placed_at = now;
+                            entry.reprices += 1;
+                            self.pending_entries.push(entry);
+                            continue;
+                        }
+                        Err(e) => error!("Failed to re-place entry order: {}", e),
+                    }
+                }
+            }
+            info!(
+                "Entry order {} in {} cancelled ({}), filled ${:.2} of ${:.2}",
+                entry.order_id,
+                entry.position.market_id,
+                reason,
+                entry.filled_usd,
+                entry.position.size_usd
+            );
+            self.finish_pending_entry(entry, reason)?;
+        }
+        self.break_failed_baskets().await;
+        self.settle_arbitrage_baskets().await?;
+        self.persist_pending_entries()
+    }
+
+    /// Settle entry orders restored from the previous run against the
+    /// exchange: book what filled while the bot was down and drop finished
+    /// orders.  Live ones stay pending and are managed by the sweep.
+    pub async fn reconcile_pending_entries(&mut self) -> Result<()> {
+        if self.pending_entries.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "Reconciling {} pending entry order(s) from the previous run",
+            self.pending_entries.len()
+        );
+        for mut entry in std::mem::take(&mut self.pending_entries) {
+            if entry.finished.is_some() {
+                self.pending_entries.push(entry);
+                continue;
+            }
+            match self.polymarket.get_order_status(&entry.order_id).await {
+                Ok(status) => {
+                    entry.record_matched(status.size_matched);
+                    if !status.live {
+                        self.finish_pending_entry(entry, "filled")?;
+                        continue;
+                    }
+                }
+                Err(e) => warn!("Failed to poll entry order {}: {}", entry.order_id, e),
+            }
+            self.pending_entries.push(entry);
+        }
+        self.break_failed_baskets().await;
+        self.settle_arbitrage_baskets().await?;
+        self.persist_pending_entries()
+    }
+
+    /// Write the live entry orders through to the database.
+    fn persist_pending_entries(&self) -> Result<()> {
+        let rows = self
+            .pending_entries
+            .iter()
+            .map(|entry| Ok((entry.order_id.clone(), serde_json::to_string(entry)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.db.replace_pending_entries(&rows)
+    }
+
+    /// Cancel every resting entry order, booking any partial fills.
+    async fn cancel_pending_entries(&mut self, reason: &str) -> Result<()> {
+        for mut entry in std::mem::take(&mut self.pending_entries) {
+            if entry.finished.is_some() {
+                self.pending_entries.push(entry);
+                continue;
+            }
+            if let Err(e) = self.polymarket.cancel_order(&entry.order_id).await {
+                error!(
+                    "Failed to cancel entry order {} ({}): {}",
+                    entry.order_id, reason, e
+                );
+                self.pending_entries.push(entry);
+                continue;
+            }
+            if let Ok(status) = self.polymarket.get_order_status(&entry.order_id).await {
+                entry.record_matched(status.size_matched);
+            }
+            self.finish_pending_entry(entry, reason)?;
+        }
+        self.settle_arbitrage_baskets().await?;
+        self.persist_pending_entries()
+    }
+
+    /// Book a finished order, or park an arbitrage leg until the rest of its
+    /// basket has finished too.
+    fn finish_pending_entry(&mut self, mut entry: PendingEntry, reason: &str) -> Result<()> {
+        if let EntryKind::ArbitrageLeg { .. } = entry.kind {
+            entry.finished = Some(reason.to_string());
+            self.pending_entries.push(entry);
+            return Ok(());
+        }
+        self.book_pending_entry(&entry, reason)?;
+        Ok(())
+    }
+
+    /// Book whatever a finished entry filled: a new position, or an add to
+    /// the position it was pyramiding into.  A hedge is grouped with the
+    /// position it hedges if that is still open.  Returns the position
+    /// booked into, if anything filled.
+    fn book_pending_entry(&mut self, entry: &PendingEntry, reason: &str) -> Result<Option<i64>> {
+        if entry.filled_shares <= 1e-9 {
+            return Ok(None);
+        }
+        let fill_price = entry.filled_usd / entry.filled_shares;
+        let open = self.db.list_open_positions()?;
+        let held = entry
+            .add_to
+            .and_then(|held_id| open.iter().find(|p| p.id == Some(held_id)));
+        let hedged = match entry.kind {
+            EntryKind::Hedge { hedged_id } => open
+                .iter()
+                .any(|p| p.id == Some(hedged_id))
+                .then_some(hedged_id),
+            EntryKind::Directional | EntryKind::ArbitrageLeg { .. } => None,
+        };
+        let booked = match held {
+            Some(held) => {
+                let held_id = held.id.expect("stored position has an id");
+                let avg = average_entry_price(
+                    held.size_usd,
+                    held.entry_price,
+                    entry.filled_usd,
+                    fill_price,
+                );
+                let levels = compute_levels(
+                    avg,
+                    self.config.stop_loss_fraction,
+                    self.config.take_profit_fraction,
+                );
+                self.db.add_to_position(
+                    held_id,
+                    entry.filled_usd,
+                    fill_price,
+                    avg,
+                    levels,
+                    "pyramid_add",
+                )?;
+                held_id
+            }
+            None => {
+                let (stop_loss, take_profit) = compute_levels(
+                    fill_price,
+                    self.config.stop_loss_fraction,
+                    self.config.take_profit_fraction,
+                );
+                let pos = Position {
+                    size_usd: entry.filled_usd,
+                    entry_price: fill_price,
+                    stop_loss_price: stop_loss,
+                    take_profit_price: take_profit,
+                    high_water_price: Some(fill_price),
+                    opened_at: Utc::now(),
+                    group_id: hedged.or(entry.position.group_id),
+                    ..entry.position.clone()
+                };
+                let id = self.db.insert_position(&pos)?;
+                if let Some(hedged_id) = hedged {
+                    self.db.set_position_group(hedged_id, hedged_id)?;
+                }
+                id
+            }
+        };
+        self.balance -= entry.filled_usd;
+        self.db.record_balance(self.balance)?;
+        info!(
+            "Booked entry fill in {} {} ({}): ${:.2} at {:.3}",
+            entry.position.market_id, entry.position.outcome, reason, entry.filled_usd, fill_price
+        );
+        Ok(Some(booked))
+    }
+
+    /// Open positions entered on the given canonical fixture.
+    fn fixture_positions(&self, fixture_id: &str) -> Result<Vec<Position>> {
+        Ok(self
+            .db
+            .list_open_positions()?
+            .into_iter()
+            .filter(|p| p.fixture_id.as_deref() == Some(fixture_id))
+            .collect())
+    }
+
+    /// Net P&L of holding `pos` to resolution at `payout` (1.0 or 0.0).
+    /// Only the entry leg pays trading costs.
+    fn settlement_pnl(pos: &Position, payout: f64) -> f64 {
+        let shares = pos.size_usd / pos.entry_price;
+        let entry_cost = pos.size_usd * (pos.estimated_round_trip_cost_bps / 2.0 / 10_000.0);
+        shares * payout - pos.size_usd - entry_cost
+    }
+
+    /// Handle a non-score game-state transition (see [`GameStateChange`]).
+    ///
+    /// The final whistle settles open positions on the fixture; a red card
+    /// re-prices open soccer positions on it.  Other transitions are logged.
+    pub async fn on_game_state_event(
+        &mut self,
+        event: &GameStateEvent,
+        game: &LiveGame,
+    ) -> Result<()> {
+        self.cancel_maker_quotes(Some(&game.event_id)).await?;
+        if event.change == GameStateChange::MatchFinished {
+            self.fixture_states.remove(&game.event_id);
+        } else {
+            self.track_fixture_game(game, Utc::now());
+        }
+        match &event.change {
+            GameStateChange::MatchFinished => {
+                let timeline_len = self.db.archive_fixture(game)?;
+                info!(
+                    "Archived final result {} {}-{} {} ({} score events)",
+                    game.home_team, game.home_score, game.away_score, game.away_team, timeline_len
+                );
+                let labelled = self.db.label_fixture_observations(
+                    &game.event_id,
+                    game.home_score > game.away_score,
+                )?;
+                if labelled > 0 {
+                    info!(
+                        "Labelled {} model observations for {} from the final score",
+                        labelled, game.event_id
+                    );
+                }
+                self.db.label_fixture_shadow_evaluations(
+                    &game.event_id,
+                    game.home_score,
+                    game.away_score,
+                )?;
+                self.settle_fixture_positions(game).await
+            }
+            GameStateChange::RedCard { .. }
+                if Self::normalize_sport_key(&game.sport) == "soccer" =>
+            {
+                self.reprice_fixture_positions(
+                    game,
+                    &event.change.to_string(),
+                    "closed_state_change",
+                )
+                .await
+            }
+            change => {
+                info!(
+                    "Game state {} for {} ({} vs {}) at minute {:?} via {}",
+                    change,
+                    event.event_id,
+                    event.home_team,
+                    event.away_team,
+                    event.minute,
+                    event.source_provider.as_deref().unwrap_or("unknown")
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Settle open positions on a finished fixture at their resolved payout.
+    ///
+    /// No close order is sent in live mode: the market resolves on-chain and
+    /// the position is booked at its payout.
+    async fn settle_fixture_positions(&mut self, game: &LiveGame) -> Result<()> {
+        if !self.config.settle_on_final_whistle {
+            return Ok(());
+        }
+        let home_won = game.home_score > game.away_score;
+        let away_won = game.away_score > game.home_score;
+        for pos in self.fixture_positions(&game.event_id)? {
+            let (Some(pos_id), Some(yes_is_home)) = (pos.id, pos.yes_is_home) else {
+                continue;
+            };
+            let yes_wins = if yes_is_home { home_won } else { away_won };
+            let wins = if pos.outcome.eq_ignore_ascii_case("YES") {
+                yes_wins
+            } else {
+                !yes_wins
+            };
+            let payout = if wins { 1.0 } else { 0.0 };
+            let pnl = Self::settlement_pnl(&pos, payout);
+            self.db
+                .close_position(pos_id, "closed_settled", payout, pnl)?;
+            self.balance += pos.size_usd + pnl;
+            self.db.record_balance(self.balance)?;
+            info!(
+                "Settled position {} on final whistle {} {}-{} {}: payout={:.0}, pnl=${:.2}",
+                pos_id,
+                game.home_team,
+                game.home_score,
+                game.away_score,
+                game.away_team,
+                payout,
+                pnl
+            );
+        }
+        Ok(())
+    }
+
+    /// Re-price open positions on a fixture with the model and exit those
+    /// whose remaining edge is gone (see [`evaluate_model_exit`]).
+    async fn reprice_fixture_positions(
+        &mut self,
+        game: &LiveGame,
+        trigger: &str,
+        status: &str,
+    ) -> Result<()> {
+        for pos in self.fixture_positions(&game.event_id)? {
+            let Some(pos_id) = pos.id.filter(|_| Self::uses_exit_policies(&pos)) else {
+                continue;
+            };
+            let Some(fair) = self.position_fair_value(&pos, game) else {
+                continue;
+            };
+            let Some(price) = self.current_position_price(&pos).await else {
+                continue;
+            };
+            self.apply_model_exit(&pos, pos_id, fair, price, trigger, status)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Run the model exit check for one position and close it if it fires.
+    async fn apply_model_exit(
+        &mut self,
+        pos: &Position,
+        pos_id: i64,
+        fair: f64,
+        price: f64,
+        trigger: &str,
+        status: &str,
+    ) -> Result<bool> {
+        let action = evaluate_model_exit(
+            pos,
+            price,
+            fair,
+            self.round_trip_cost_edge(),
+            self.position_profile(pos).config.model_exit_edge_buffer,
+        );
+        let PositionAction::ModelExit {
+            exit_price,
+            pnl,
+            reason,
+        } = action
+        else {
+            return Ok(false);
+        };
+        info!(
+            "Model exit on position {} after {} ({}): fair={:.3}, price={:.3}, pnl=${:.2}",
+            pos_id,
+            trigger,
+            reason.as_str(),
+            fair,
+            exit_price,
+            pnl
+        );
+        self.exit_position(pos, status, exit_price).await
+    }
+
+    /// Sweep all open positions and close those that hit stop-loss or take-profit.
+    ///
+    /// Uses WS mid-prices first for minimal latency; falls back to concurrent
+    /// REST fetches when WS price is unavailable.
+    pub async fn manage_positions(&mut self) -> Result<()> {
+        self.manage_pending_entries().await?;
+        let open = self.db.list_open_positions()?;
+        if open.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        let open_len = open.len();
+
+        let mut resolved_prices: Vec<Option<f64>> = vec![None; open.len()];
+        let mut quote_sources: Vec<&str> = vec!["none"; open.len()];
+        let mut ws_age_millis: Vec<Option<i64>> = vec![None; open.len()];
+        let mut used_rest_fallback: Vec<bool> = vec![false; open.len()];
+        let mut rest_fallback_indices = Vec::new();
+        let now_ms = now.timestamp_millis().max(0) as u64;
+
+        for (idx, pos) in open.iter().enumerate() {
+            let ws_price = if let Some(asset_id) = self
+                .ensure_asset_subscription_with_hint(
+                    &pos.market_id,
+                    &pos.outcome,
+                    pos.asset_id.as_deref(),
+                )
+                .await
+            {
+                self.price_feed.get_price(&asset_id).await
+            } else {
+                None
+            };
+
+            match ws_price {
+                Some(snapshot) => {
+                    let age_ms = now_ms.saturating_sub(snapshot.last_updated_ms);
+                    if snapshot.mid_price > 0.0
+                        && snapshot.mid_price < 1.0
+                        && age_ms <= self.config.ws_price_max_age_ms
+                    {
+                        resolved_prices[idx] = Some(snapshot.mid_price);
+                        quote_sources[idx] = "ws";
+                        ws_age_millis[idx] = Some(age_ms as i64);
+                    } else {
+                        used_rest_fallback[idx] = true;
+                        warn!(
+                            "WS quote unusable for market {} outcome {} (mid={:.5}, age={}ms)",
+                            pos.market_id, pos.outcome, snapshot.mid_price, age_ms
+                        );
+                        rest_fallback_indices.push(idx);
+                    }
+                }
+                None => {
+                    used_rest_fallback[idx] = true;
+                    rest_fallback_indices.push(idx);
+                }
+            }
+        }
+
+        if !rest_fallback_indices.is_empty() {
+            let price_futures: Vec<_> = rest_fallback_indices
+                .iter()
+                .map(|&idx| {
+                    let pos = &open[idx];
+                    self.polymarket
+                        .get_token_price(&pos.market_id, &pos.outcome)
+                })
+                .collect();
+            let rest_results = futures_util::future::join_all(price_futures).await;
+
+            for (idx, price_result) in rest_fallback_indices
+                .into_iter()
+                .zip(rest_results.into_iter())
+            {
+                let pos = &open[idx];
+                match price_result {
+                    Ok(p) if p > 0.0 && p < 1.0 => {
+                        resolved_prices[idx] = Some(p);
+                        quote_sources[idx] = "rest";
+                    }
+                    Ok(p) => {
+                        warn!(
+                            "Ignoring out-of-range price for market {} outcome {}: {:.5}",
+                            pos.market_id, pos.outcome, p
+                        );
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to get fallback REST price for market {} outcome {}: {}",
+                            pos.market_id, pos.outcome, e
+                        );
+                    }
+                }
+            }
+        }
+
+        let rest_fallback_count = used_rest_fallback.iter().filter(|&&b| b).count();
+        let avg_ws_age_ms = {
             let mut sum = 0.0;
             let mut n = 0.0;
             for age in ws_age_millis.iter().flatten() {
@@ -1652,6 +4407,11 @@ impl BotEngine {
         };
         self.update_feed_health(open_len, rest_fallback_count, avg_ws_age_ms);
         let force_flatten = self.should_force_flatten_positions();
+        let reduction_fractions = if self.config.reduce_on_exposure_cap {
+            self.exposure_reduction_fractions(&open)
+        } else {
+            vec![0.0; open_len]
+        };
 
         for (idx, pos) in open.into_iter().enumerate() {
             let pos_id = match pos.id {
@@ -1682,6 +4442,17 @@ impl BotEngine {
                 }
             };
 
+            // Hedged pairs, arbitrage baskets and maker inventory are held to
+            // settlement as a unit: flattening one leg would unhedge the rest.
+            // A position whose hedge order is still resting waits for it.
+            if !Self::uses_exit_policies(&pos)
+                || self
+                    .pending_entries
+                    .iter()
+                    .any(|e| e.kind == EntryKind::Hedge { hedged_id: pos_id })
+            {
+                continue;
+            }
             if force_flatten {
                 let pnl = Self::position_net_pnl(&pos, current_price);
                 warn!(
@@ -1730,51 +4501,134 @@ impl BotEngine {
                 continue;
             }
 
-            match evaluate_position(&pos, current_price) {
+            if self.config.model_exit_enabled {
+                if let Some(fair) = self.fixture_fair_value(&pos, now) {
+                    if self
+                        .apply_model_exit(
+                            &pos,
+                            pos_id,
+                            fair,
+                            current_price,
+                            "sweep",
+                            "closed_model_exit",
+                        )
+                        .await?
+                    {
+                        continue;
+                    }
+                }
+            }
+
+            if reduction_fractions[idx] > 0.0 {
+                warn!(
+                    "Exposure cap tightened: reducing position {} by {:.0}%",
+                    pos_id,
+                    reduction_fractions[idx] * 100.0
+                );
+                // A slice that could not be sold falls through to the
+                // normal exit checks so the position keeps its stops.
+                if self
+                    .reduce_position(
+                        &pos,
+                        reduction_fractions[idx],
+                        current_price,
+                        "exposure_reduction",
+                    )
+                    .await?
+                {
+                    continue;
+                }
+            }
+
+            let profile = self.position_profile(&pos);
+            if profile.config.partial_take_profit_fraction > 0.0 && pos.partial_exit_count == 0 {
+                let trigger = pos.entry_price
+                    + (pos.take_profit_price - pos.entry_price)
+                        * profile.config.partial_take_profit_trigger;
+                if current_price >= trigger && current_price < pos.take_profit_price {
+                    info!(
+                        "Partial take-profit on position {}: selling {:.0}% at {:.3}",
+                        pos_id,
+                        profile.config.partial_take_profit_fraction * 100.0,
+                        current_price
+                    );
+                    if self
+                        .reduce_position(
+                            &pos,
+                            profile.config.partial_take_profit_fraction,
+                            current_price,
+                            "partial_take_profit",
+                        )
+                        .await?
+                    {
+                        continue;
+                    }
+                }
+            }
+
+            let mut pos = pos;
+            if current_price > pos.high_water_price.unwrap_or(pos.entry_price) {
+                self.db.update_position_high_water(pos_id, current_price)?;
+                pos.high_water_price = Some(current_price);
+            }
+            let progress = pos
+                .fixture_id
+                .as_deref()
+                .and_then(|id| self.current_fixture_game(id, now))
+                .and_then(|game| game_progress(&game.sport, game.minute?));
+
+            match evaluate_exit_policy(
+                &pos,
+                current_price,
+                &Self::exit_policy_params(&profile.config),
+                progress,
+            ) {
                 PositionAction::TakeProfit { exit_price, pnl } => {
                     info!(
                         "Taking profit on position {}: exit={:.3}, pnl=+${:.2}",
                         pos_id, exit_price, pnl
                     );
-                    if !self.config.dry_run {
-                        if let Err(e) = self
-                            .polymarket
-                            .close_position(&pos.market_id, &pos.outcome, pos.size_usd)
-                            .await
-                        {
-                            error!("Failed to close position {}: {}", pos_id, e);
-                            continue;
-                        }
-                    }
-                    self.db
-                        .close_position(pos_id, "closed_profit", exit_price, pnl)?;
-                    self.balance += pos.size_usd + pnl;
-                    self.db.record_balance(self.balance)?;
+                    self.exit_position(&pos, "closed_profit", exit_price)
+                        .await?;
                 }
                 PositionAction::StopLoss { exit_price, pnl } => {
                     warn!(
                         "Stop-loss triggered on position {}: exit={:.3}, pnl=${:.2}",
                         pos_id, exit_price, pnl
                     );
-                    if !self.config.dry_run {
-                        if let Err(e) = self
-                            .polymarket
-                            .close_position(&pos.market_id, &pos.outcome, pos.size_usd)
-                            .await
-                        {
-                            error!("Failed to close position {}: {}", pos_id, e);
-                            continue;
-                        }
+                    if self.config.hedging_enabled && self.hedge_position(&pos, pos_id, pnl).await?
+                    {
+                        continue;
                     }
-                    self.db
-                        .close_position(pos_id, "closed_stop_loss", exit_price, pnl)?;
-                    self.balance += pos.size_usd + pnl; // pnl is negative
-                    self.db.record_balance(self.balance)?;
+                    self.exit_position(&pos, "closed_stop_loss", exit_price)
+                        .await?;
                 }
-                PositionAction::Hold => {}
+                PositionAction::TrailingStop { exit_price, pnl } => {
+                    info!(
+                        "Trailing stop on position {}: exit={:.3} (high-water {:.3}), pnl=${:.2}",
+                        pos_id,
+                        exit_price,
+                        pos.high_water_price.unwrap_or(pos.entry_price),
+                        pnl
+                    );
+                    self.exit_position(&pos, "closed_trailing_stop", exit_price)
+                        .await?;
+                }
+                PositionAction::BreakevenStop { exit_price, pnl } => {
+                    info!(
+                        "Break-even stop on position {}: exit={:.3}, pnl=${:.2}",
+                        pos_id, exit_price, pnl
+                    );
+                    self.exit_position(&pos, "closed_breakeven", exit_price)
+                        .await?;
+                }
+                PositionAction::Hold | PositionAction::ModelExit { .. } => {}
             }
         }
 
+        let stale_before = now - chrono::Duration::hours(6);
+        self.fixture_states
+            .retain(|_, state| state.last_seen >= stale_before);
         self.cleanup_price_feed_subscriptions().await?;
 
         Ok(())
@@ -1790,8 +4644,93 @@ impl BotEngine {
 mod tests {
     use chrono::Utc;
 
-    use super::BotEngine;
-    use crate::db::models::ScoreEvent;
+    use super::{
+        resolution_home_won, BotEngine, ControlCommand, EntryKind, MakerBook, PendingEntry,
+        QuoteSide, RestingQuote,
+    };
+    use crate::config::Config;
+    use crate::db::models::{GameStatus, LiveGame, Market, Position, ScoreEvent};
+    use crate::db::Database;
+    use crate::polymarket::price_ws::PriceSnapshot;
+    use crate::polymarket::{MarketCache, PolymarketClient};
+
+    /// Dry-run engine over an in-memory database; the price feed points at a
+    /// closed port so nothing leaves the machine.
+    fn engine() -> BotEngine {
+        engine_with_db(Database::open(":memory:").unwrap())
+    }
+
+    fn engine_with_db(db: Database) -> BotEngine {
+        use clap::Parser;
+        let config = Config::parse_from([
+            "livesports-bot",
+            "--dry-run",
+            "--polymarket-ws-url",
+            "ws://127.0.0.1:9",
+        ]);
+        let polymarket = PolymarketClient::new(
+            &config.polymarket_api_url,
+            &config.polymarket_clob_url,
+            None,
+        )
+        .unwrap();
+        BotEngine::new(config, db, polymarket, MarketCache::new()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn manual_controls_survive_a_restart_and_are_audited() {
+        let db = Database::open(":memory:").unwrap();
+        let mut first = engine_with_db(db.clone());
+        first
+            .handle_control(
+                "ops",
+                ControlCommand::Pause {
+                    sport: Some("NBA".into()),
+                },
+            )
+            .await
+            .unwrap();
+        let empty = ControlCommand::Blacklist {
+            market_id: None,
+            team: None,
+        };
+        assert!(first.handle_control("ops", empty).await.is_err());
+        first
+            .handle_control("ops", ControlCommand::Status)
+            .await
+            .unwrap();
+
+        let restarted = engine_with_db(db.clone());
+        assert_eq!(
+            restarted.controls.sport_block("basketball").as_deref(),
+            Some("basketball paused")
+        );
+        let actions = db.list_control_actions(10).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert!(actions[0].outcome.starts_with("rejected"));
+        assert_eq!(actions[1].actor, "ops");
+        assert_eq!(actions[1].outcome, "applied");
+    }
+
+    fn soccer_game(minute: i32, home_score: i32) -> LiveGame {
+        LiveGame {
+            event_id: "fx1".into(),
+            sport: "soccer".into(),
+            league: "premier league".into(),
+            home_team: "Arsenal".into(),
+            away_team: "Chelsea".into(),
+            home_score,
+            away_score: 0,
+            minute: Some(minute),
+            status: GameStatus::InProgress,
+            start_time: None,
+            period: Some(2),
+            added_time: None,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            power_play: None,
+        }
+    }
 
     #[test]
     fn adaptive_edge_addon_increases_with_worse_signals() {
@@ -1853,6 +4792,105 @@ mod tests {
         assert!(high_addon < low_addon);
     }
 
+    #[test]
+    fn correction_invalidates_entry_only_when_score_reverted() {
+        let entry = ScoreEvent {
+            id: Some(7),
+            event_id: "ev1".into(),
+            source_provider: Some("TheSportsDB".into()),
+            provider_consensus_count: Some(1),
+            sport: "soccer".into(),
+            league: "EPL".into(),
+            home_team: "A".into(),
+            away_team: "B".into(),
+            prev_home_score: Some(0),
+            prev_away_score: Some(0),
+            home_score: 1,
+            away_score: 0,
+            minute: Some(30),
+            event_type: "goal_home".into(),
+            detected_at: Utc::now(),
+        };
+        let var_reversal = ScoreEvent {
+            id: None,
+            prev_home_score: Some(1),
+            prev_away_score: Some(0),
+            home_score: 0,
+            away_score: 0,
+            minute: Some(32),
+            event_type: "score_correction".into(),
+            ..entry.clone()
+        };
+        assert!(BotEngine::correction_invalidates_entry(
+            &entry,
+            &var_reversal
+        ));
+
+        let other_fixture = ScoreEvent {
+            event_id: "ev2".into(),
+            ..var_reversal.clone()
+        };
+        assert!(!BotEngine::correction_invalidates_entry(
+            &entry,
+            &other_fixture
+        ));
+
+        // A later away goal being disallowed leaves the home entry goal
+        // standing and does not invalidate the position.
+        let unrelated = ScoreEvent {
+            prev_home_score: Some(2),
+            prev_away_score: Some(1),
+            home_score: 2,
+            away_score: 0,
+            ..var_reversal.clone()
+        };
+        assert!(!BotEngine::correction_invalidates_entry(&entry, &unrelated));
+
+        // Entry on a home goal to 1-1; an earlier away goal is then
+        // disallowed (1-0).  The away side drops, but the entry goal stands.
+        let equaliser = ScoreEvent {
+            prev_home_score: Some(0),
+            prev_away_score: Some(1),
+            home_score: 1,
+            away_score: 1,
+            ..entry.clone()
+        };
+        let earlier_goal_disallowed = ScoreEvent {
+            prev_home_score: Some(1),
+            prev_away_score: Some(1),
+            home_score: 1,
+            away_score: 0,
+            ..var_reversal
+        };
+        assert!(!BotEngine::correction_invalidates_entry(
+            &equaliser,
+            &earlier_goal_disallowed
+        ));
+        let equaliser_disallowed = ScoreEvent {
+            home_score: 0,
+            away_score: 1,
+            ..earlier_goal_disallowed
+        };
+        assert!(BotEngine::correction_invalidates_entry(
+            &equaliser,
+            &equaliser_disallowed
+        ));
+    }
+
+    #[test]
+    fn exposure_reduction_brings_group_back_under_cap() {
+        // Within caps: nothing to do.
+        assert_eq!(
+            BotEngine::exposure_reduction_fraction(&[(20.0, 25.0), (40.0, 50.0)]),
+            0.0
+        );
+        // Event group at $40 vs a $30 cap → sell 25% of each position in it.
+        let f = BotEngine::exposure_reduction_fraction(&[(40.0, 30.0), (40.0, 50.0)]);
+        assert!((f - 0.25).abs() < 1e-9);
+        // Tiny overshoots are ignored.
+        assert_eq!(BotEngine::exposure_reduction_fraction(&[(30.5, 30.0)]), 0.0);
+    }
+
     #[test]
     fn should_time_exit_after_max_age() {
         let now = Utc::now();
@@ -1860,4 +4898,459 @@ mod tests {
         assert!(BotEngine::should_time_exit(opened, now, 3600));
         assert!(!BotEngine::should_time_exit(opened, now, 7200));
     }
+
+    #[test]
+    fn draws_are_only_labelled_from_home_side_markets() {
+        // "Will Arsenal (home) win?"
+        assert_eq!(resolution_home_won(true, true, "soccer"), Some(true));
+        assert_eq!(resolution_home_won(false, true, "soccer"), Some(false));
+        // "Will Chelsea (away) win?" resolving NO may be a draw.
+        assert_eq!(resolution_home_won(true, false, "soccer"), Some(false));
+        assert_eq!(resolution_home_won(false, false, "soccer"), None);
+        assert_eq!(resolution_home_won(false, false, "basketball"), Some(true));
+    }
+
+    #[tokio::test]
+    async fn quiet_fixture_is_not_repriced_on_a_stuck_clock() {
+        let mut engine = engine();
+        let pos = Position {
+            fixture_id: Some("fx1".into()),
+            yes_is_home: Some(true),
+            ..Position::new_open("mkt1", "YES", "directional", 10.0, 0.8, true)
+        };
+        // 1-0 at 55', then nothing scored for half an hour: the clock stops
+        // extrapolating at 65' and would price the lead far too low.
+        let scored_at = Utc::now() - chrono::Duration::minutes(30);
+        engine.track_fixture_game(&soccer_game(55, 1), scored_at);
+        assert!(engine.fixture_fair_value(&pos, Utc::now()).is_none());
+        let stuck = engine
+            .position_fair_value(&pos, &soccer_game(65, 1))
+            .unwrap();
+
+        // Monitor polls keep the fixture current; unknown fixtures are ignored.
+        let other = LiveGame {
+            event_id: "fx2".into(),
+            ..soccer_game(10, 0)
+        };
+        engine.on_fixture_snapshot(&[soccer_game(85, 1), other]);
+        assert!(engine.current_fixture_game("fx2", Utc::now()).is_none());
+        let current = engine.current_fixture_game("fx1", Utc::now()).unwrap();
+        assert_eq!(current.minute, Some(85));
+        let fair = engine.fixture_fair_value(&pos, Utc::now()).unwrap();
+        assert!(fair > stuck);
+    }
+
+    #[tokio::test]
+    async fn pulled_maker_quotes_book_their_fills_first() {
+        let mut engine = engine();
+        engine
+            .db
+            .upsert_market(&Market {
+                id: "mkt1".into(),
+                question: "Will Arsenal win?".into(),
+                sport: Some("soccer".into()),
+                league: None,
+                event_name: None,
+                yes_price: None,
+                no_price: None,
+                volume: None,
+                status: "active".into(),
+                fetched_at: Utc::now(),
+                slug: None,
+                end_date: None,
+                liquidity: None,
+            })
+            .unwrap();
+        let quote = |side: QuoteSide, price| RestingQuote {
+            order_id: format!("dry-{}", side.as_str()),
+            side,
+            price,
+            shares: 10.0,
+            filled_shares: 0.0,
+            placed_at: Utc::now(),
+        };
+        engine.maker_books.insert(
+            "mkt1".into(),
+            MakerBook {
+                fixture_id: "fx1".into(),
+                yes_is_home: true,
+                asset_id: Some("yes1".into()),
+                bid: Some(quote(QuoteSide::Bid, 0.5)),
+                ask: Some(quote(QuoteSide::Ask, 0.6)),
+            },
+        );
+        // The ask traded through the bid just before the pull.
+        let now_ms = Utc::now().timestamp_millis() as u64;
+        engine
+            .price_feed
+            .set_price(
+                "yes1",
+                PriceSnapshot {
+                    best_bid: 0.47,
+                    best_ask: 0.49,
+                    mid_price: 0.48,
+                    last_updated_ms: now_ms,
+                },
+            )
+            .await;
+
+        engine.cancel_maker_quotes(Some("fx1")).await.unwrap();
+        let book = &engine.maker_books["mkt1"];
+        assert!(book.bid.is_none() && book.ask.is_none());
+        let open = engine.db.list_open_positions().unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].strategy, "market_making");
+        assert!((open[0].size_usd - 5.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn pending_entries_are_restored_after_a_restart() {
+        let db = Database::open(":memory:").unwrap();
+        let mut engine = engine_with_db(db.clone());
+        let entry = |order_id: &str| PendingEntry {
+            order_id: order_id.into(),
+            position: Position::new_open("mkt1", "YES", "directional", 10.0, 0.5, false),
+            add_to: None,
+            limit_price: 0.5,
+            order_matched: 4.0,
+            filled_shares: 4.0,
+            filled_usd: 2.0,
+            model_prob: 0.6,
+            threshold_edge: 0.05,
+            placed_at: Utc::now(),
+            reprices: 1,
+            kind: EntryKind::Directional,
+            max_price: None,
+            finished: None,
+        };
+        let hedge = PendingEntry {
+            kind: EntryKind::Hedge { hedged_id: 4 },
+            max_price: Some(0.55),
+            ..entry("order-3")
+        };
+        let leg = PendingEntry {
+            kind: EntryKind::ArbitrageLeg {
+                basket: "A vs B".into(),
+            },
+            finished: Some("timeout".into()),
+            ..entry("order-4")
+        };
+        engine.pending_entries = vec![entry("order-1"), entry("order-2"), hedge, leg];
+        engine.persist_pending_entries().unwrap();
+        engine.pending_entries.remove(0);
+        engine.persist_pending_entries().unwrap();
+
+        let restarted = engine_with_db(db);
+        assert_eq!(restarted.pending_entries.len(), 3);
+        let restored = &restarted.pending_entries[0];
+        assert_eq!(restored.order_id, "order-2");
+        assert_eq!(restored.reprices, 1);
+        assert!((restored.unfilled_usd() - 8.0).abs() < 1e-9);
+        assert_eq!(restored.position.market_id, "mkt1");
+        assert_eq!(restored.kind, EntryKind::Directional);
+
+        let hedge = &restarted.pending_entries[1];
+        assert_eq!(hedge.kind, EntryKind::Hedge { hedged_id: 4 });
+        assert_eq!(hedge.max_price, Some(0.55));
+        let leg = &restarted.pending_entries[2];
+        assert_eq!(leg.finished.as_deref(), Some("timeout"));
+        // Only orders still resting hold cash back.
+        assert!((restarted.pending_stake_usd() - 16.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn model_version_names_the_calibration_set() {
+        use super::{CalibrationModel, LevelCalibration, PlattCalibration};
+
+        let mut engine = engine();
+        let params = crate::bot::win_probability::active_params().version.clone();
+        assert_eq!(engine.model_version(), params);
+
+        engine.probability_calibrations.insert(
+            "soccer".into(),
+            LevelCalibration {
+                model: CalibrationModel::Platt(PlattCalibration { a: 1.1, b: 0.0 }),
+                samples: 200,
+            },
+        );
+        engine.calibration_version =
+            BotEngine::archive_calibrations(&engine.db, &engine.probability_calibrations).unwrap();
+        let version = engine.model_version();
+        let (prefix, calibrations) = version.split_once('+').unwrap();
+        assert_eq!(prefix, params);
+        assert!(calibrations.starts_with("cal-"));
+    }
+
+    #[test]
+    fn arbitrage_sets_respect_team_blacklists() {
+        use super::{group_exclusive_markets, ManualControls};
+
+        let market = |id: &str, question: &str| Market {
+            id: id.into(),
+            question: question.into(),
+            sport: Some("soccer".into()),
+            league: None,
+            event_name: Some("Arsenal vs. Chelsea".into()),
+            yes_price: None,
+            no_price: None,
+            volume: None,
+            status: "active".into(),
+            fetched_at: Utc::now(),
+            slug: None,
+            end_date: None,
+            liquidity: None,
+        };
+        let sets = group_exclusive_markets(&[
+            market("h", "Will Arsenal win?"),
+            market("a", "Will Chelsea win?"),
+            market("d", "Will Arsenal vs. Chelsea end in a draw?"),
+        ]);
+        assert_eq!(sets.len(), 1);
+        let (event_name, legs) = &sets[0];
+
+        let mut controls = ManualControls::default();
+        assert!(!BotEngine::arbitrage_set_blocked(
+            &controls, event_name, legs
+        ));
+        controls
+            .apply(&ControlCommand::Blacklist {
+                market_id: None,
+                team: Some("chelsea".into()),
+            })
+            .unwrap();
+        assert!(BotEngine::arbitrage_set_blocked(
+            &controls, event_name, legs
+        ));
+    }
+
+    #[tokio::test]
+    async fn flattens_leave_hedged_pairs_to_settle_together() {
+        let mut engine = engine();
+        let now = Utc::now();
+        let open = |market_id: &str, strategy: &str| {
+            engine
+                .db
+                .upsert_market(&Market {
+                    id: market_id.into(),
+                    question: format!("Will {} win?", market_id),
+                    sport: Some("soccer".into()),
+                    league: None,
+                    event_name: None,
+                    yes_price: None,
+                    no_price: None,
+                    volume: None,
+                    status: "active".into(),
+                    fetched_at: now,
+                    slug: None,
+                    end_date: None,
+                    liquidity: None,
+                })
+                .unwrap();
+            let pos = Position {
+                asset_id: Some(format!("{}-yes", market_id)),
+                opened_at: now - chrono::Duration::days(2),
+                ..Position::new_open(market_id, "YES", strategy, 10.0, 0.5, true)
+            };
+            engine.db.insert_position(&pos).unwrap()
+        };
+        let stopped = open("home", "directional");
+        let hedge = open("away", "hedge");
+        let solo = open("other", "directional");
+        engine.db.set_position_group(stopped, stopped).unwrap();
+        engine.db.set_position_group(hedge, stopped).unwrap();
+
+        for market_id in ["home", "away", "other"] {
+            engine
+                .price_feed
+                .set_price(
+                    &format!("{}-yes", market_id),
+                    PriceSnapshot {
+                        best_bid: 0.49,
+                        best_ask: 0.51,
+                        mid_price: 0.5,
+                        last_updated_ms: now.timestamp_millis() as u64,
+                    },
+                )
+                .await;
+        }
+        // Feed degraded long enough to flatten, and every position is past
+        // its maximum age.
+        engine.feed_health.samples = 1_000;
+        engine.feed_health.ewma_ws_age_ms = 1e9;
+        engine.feed_health.degraded_since = Some(now - chrono::Duration::days(1));
+
+        engine.manage_positions().await.unwrap();
+        let mut still_open: Vec<i64> = engine
+            .db
+            .list_open_positions()
+            .unwrap()
+            .iter()
+            .filter_map(|p| p.id)
+            .collect();
+        still_open.sort();
+        assert_eq!(still_open, vec![stopped, hedge]);
+        assert!(!still_open.contains(&solo));
+    }
+
+    #[tokio::test]
+    async fn hedges_are_grouped_only_once_filled() {
+        let mut engine = engine();
+        for market_id in ["home", "away"] {
+            engine
+                .db
+                .upsert_market(&Market {
+                    id: market_id.into(),
+                    question: format!("Will {} win?", market_id),
+                    sport: Some("soccer".into()),
+                    league: None,
+                    event_name: None,
+                    yes_price: None,
+                    no_price: None,
+                    volume: None,
+                    status: "active".into(),
+                    fetched_at: Utc::now(),
+                    slug: None,
+                    end_date: None,
+                    liquidity: None,
+                })
+                .unwrap();
+        }
+        let stopped = engine
+            .db
+            .insert_position(&Position::new_open(
+                "home",
+                "YES",
+                "directional",
+                10.0,
+                0.5,
+                false,
+            ))
+            .unwrap();
+        let mut hedge = PendingEntry {
+            order_id: "hedge-1".into(),
+            position: Position::new_open("away", "YES", "hedge", 6.0, 0.3, false),
+            add_to: None,
+            limit_price: 0.3,
+            order_matched: 0.0,
+            filled_shares: 0.0,
+            filled_usd: 0.0,
+            model_prob: 0.0,
+            threshold_edge: 0.0,
+            placed_at: Utc::now(),
+            reprices: 0,
+            kind: EntryKind::Hedge { hedged_id: stopped },
+            max_price: Some(0.35),
+            finished: None,
+        };
+        let group_of = |engine: &BotEngine, market_id: &str| {
+            engine
+                .db
+                .list_open_positions()
+                .unwrap()
+                .into_iter()
+                .find(|p| p.market_id == market_id)
+                .map(|p| p.group_id)
+        };
+
+        // Cancelled unfilled: nothing booked, the position stays ungrouped.
+        engine.book_pending_entry(&hedge, "timeout").unwrap();
+        assert_eq!(group_of(&engine, "home"), Some(None));
+        assert_eq!(group_of(&engine, "away"), None);
+
+        hedge.record_matched(15.0);
+        engine.book_pending_entry(&hedge, "timeout").unwrap();
+        assert_eq!(group_of(&engine, "home"), Some(Some(stopped)));
+        assert_eq!(group_of(&engine, "away"), Some(Some(stopped)));
+    }
+
+    #[tokio::test]
+    async fn arbitrage_baskets_book_complete_sets_only() {
+        let mut engine = engine();
+        let now = Utc::now();
+        let leg = |basket: &str, market_id: &str, price: f64, filled: f64, reason: &str| {
+            let mut entry = PendingEntry {
+                order_id: format!("{}-order", market_id),
+                position: Position {
+                    asset_id: Some(format!("{}-yes", market_id)),
+                    event_name: Some(basket.into()),
+                    ..Position::new_open(market_id, "YES", "arbitrage", 10.0 * price, price, false)
+                },
+                add_to: None,
+                limit_price: price,
+                order_matched: 0.0,
+                filled_shares: 0.0,
+                filled_usd: 0.0,
+                model_prob: 0.0,
+                threshold_edge: 0.0,
+                placed_at: now,
+                reprices: 0,
+                kind: EntryKind::ArbitrageLeg {
+                    basket: basket.into(),
+                },
+                max_price: Some(price + 0.01),
+                finished: Some(reason.into()),
+            };
+            entry.record_matched(filled);
+            entry
+        };
+        for market_id in ["h1", "a1", "d1", "h2", "a2"] {
+            engine
+                .db
+                .upsert_market(&Market {
+                    id: market_id.into(),
+                    question: format!("Will {} happen?", market_id),
+                    sport: Some("soccer".into()),
+                    league: None,
+                    event_name: None,
+                    yes_price: None,
+                    no_price: None,
+                    volume: None,
+                    status: "active".into(),
+                    fetched_at: now,
+                    slug: None,
+                    end_date: None,
+                    liquidity: None,
+                })
+                .unwrap();
+            engine
+                .price_feed
+                .set_price(
+                    &format!("{}-yes", market_id),
+                    PriceSnapshot {
+                        best_bid: 0.29,
+                        best_ask: 0.31,
+                        mid_price: 0.3,
+                        last_updated_ms: now.timestamp_millis() as u64,
+                    },
+                )
+                .await;
+        }
+        engine.pending_entries = vec![
+            // The draw leg timed out at 6 of 10 shares.
+            leg("A vs B", "h1", 0.3, 10.0, "filled"),
+            leg("A vs B", "a1", 0.3, 10.0, "filled"),
+            leg("A vs B", "d1", 0.35, 6.0, "timeout"),
+            // The away leg could not be placed at all.
+            leg("C vs D", "h2", 0.3, 4.0, "basket_broken"),
+            leg("C vs D", "a2", 0.3, 0.0, "order_failed"),
+        ];
+        // A basket with a leg still resting waits.
+        let mut resting = leg("E vs F", "h1", 0.3, 0.0, "");
+        resting.finished = None;
+        engine.pending_entries.push(resting);
+
+        engine.settle_arbitrage_baskets().await.unwrap();
+        assert_eq!(engine.pending_entries.len(), 1);
+        assert_eq!(engine.pending_entries[0].order_id, "h1-order");
+
+        let open = engine.db.list_open_positions().unwrap();
+        assert_eq!(open.len(), 3);
+        let group = open[0].group_id;
+        assert!(group.is_some());
+        for pos in &open {
+            assert_eq!(pos.group_id, group);
+            assert_eq!(pos.event_name.as_deref(), Some("A vs B"));
+            assert!((pos.size_usd / pos.entry_price - 6.0).abs() < 1e-6);
+        }
+    }
 }
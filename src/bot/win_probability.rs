@@ -12,12 +12,80 @@
 //! - **NFL**: Logistic on point_diff / √(possessions_remaining)
 //! - **Baseball**: Run-differential × innings-remaining table
 //! - **Ice Hockey**: Logistic on goal_diff with time-decay, empty-net aware
+//!
+//! The soccer table and logistic coefficients below are the compiled-in
+//! defaults.  `fit-models` refits them from archived fixtures into a
+//! versioned [`WinProbParams`] file in the model store (see `model_store`),
+//! and the promoted version is installed at startup and on reload.
+
+use std::sync::{Arc, LazyLock, RwLock};
+
+use serde::{Deserialize, Serialize};
 
 use crate::db::models::{LiveGame, ScoreEvent};
 
 /// Home-ice/court/field advantage in win probability points (added to home team).
 const HOME_ADVANTAGE: f64 = 0.035;
 
+// ── Parameters ───────────────────────────────────────────────────────────────
+
+/// Fitted model coefficients, loadable in place of the compiled-in constants.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WinProbParams {
+    /// "builtin" for the compiled-in defaults, else the fit version
+    pub version: String,
+    /// P(home wins) by goal difference (-3..+3) and `SOCCER_MINUTES` column
+    pub soccer_table: [[f64; 8]; 7],
+    pub nba_k: f64,
+    pub nfl_k: f64,
+    pub mlb_k: f64,
+    pub nhl_k: f64,
+}
+
+impl Default for WinProbParams {
+    fn default() -> Self {
+        WinProbParams {
+            version: "builtin".into(),
+            soccer_table: SOCCER_TABLE,
+            nba_k: NBA_K,
+            nfl_k: NFL_K,
+            mlb_k: MLB_K,
+            nhl_k: NHL_K,
+        }
+    }
+}
+
+impl WinProbParams {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let coefficients = [self.nba_k, self.nfl_k, self.mlb_k, self.nhl_k];
+        if coefficients.iter().any(|k| !(k.is_finite() && *k > 0.0)) {
+            anyhow::bail!("logistic coefficients must be positive and finite");
+        }
+        if self
+            .soccer_table
+            .iter()
+            .flatten()
+            .any(|p| !(0.0..=1.0).contains(p))
+        {
+            anyhow::bail!("soccer table entries must be probabilities");
+        }
+        Ok(())
+    }
+}
+
+static ACTIVE_PARAMS: LazyLock<RwLock<Arc<WinProbParams>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(WinProbParams::default())));
+
+/// Replace the parameters used by every subsequent estimate.
+pub fn install_params(params: WinProbParams) {
+    *ACTIVE_PARAMS.write().unwrap() = Arc::new(params);
+}
+
+/// Parameters currently in use.
+pub fn active_params() -> Arc<WinProbParams> {
+    ACTIVE_PARAMS.read().unwrap().clone()
+}
+
 // ── Public API ───────────────────────────────────────────────────────────────
 
 /// Estimate the true win probability for the specified team given the current
@@ -25,12 +93,45 @@ const HOME_ADVANTAGE: f64 = 0.035;
 ///
 /// This replaces the old naive linear formula with sport-specific models.
 pub fn estimate_win_probability(event: &ScoreEvent, game: &LiveGame, for_home: bool) -> f64 {
-    let raw = match event.sport.as_str() {
-        "soccer" | "football" | "football_eu" => soccer_win_prob(game),
-        "basketball" | "nba" => basketball_win_prob(game),
-        "american_football" | "nfl" => nfl_win_prob(game),
-        "baseball" | "mlb" => baseball_win_prob(game),
-        "ice_hockey" | "nhl" => hockey_win_prob(game),
+    sport_win_probability(&event.sport, game, for_home)
+}
+
+/// Same as [`estimate_win_probability`], keyed on the game's own sport — for
+/// re-pricing on game-state changes where there is no triggering score event.
+pub fn estimate_game_win_probability(game: &LiveGame, for_home: bool) -> f64 {
+    sport_win_probability(&game.sport, game, for_home)
+}
+
+fn sport_win_probability(sport: &str, game: &LiveGame, for_home: bool) -> f64 {
+    sport_win_probability_with(&active_params(), sport, game, for_home)
+}
+
+/// Model family a sport key is priced with.
+pub fn model_family(sport: &str) -> &'static str {
+    match sport {
+        "soccer" | "football" | "football_eu" => "soccer",
+        "basketball" | "nba" => "basketball",
+        "american_football" | "nfl" => "nfl",
+        "baseball" | "mlb" => "baseball",
+        "ice_hockey" | "nhl" => "hockey",
+        "tennis" => "tennis",
+        _ => "fallback",
+    }
+}
+
+/// [`estimate_game_win_probability`] under explicit parameters (model fitting).
+pub fn sport_win_probability_with(
+    params: &WinProbParams,
+    sport: &str,
+    game: &LiveGame,
+    for_home: bool,
+) -> f64 {
+    let raw = match model_family(sport) {
+        "soccer" => soccer_win_prob(game, &params.soccer_table),
+        "basketball" => basketball_win_prob(game, params.nba_k),
+        "nfl" => nfl_win_prob(game, params.nfl_k),
+        "baseball" => baseball_win_prob(game, params.mlb_k),
+        "hockey" => hockey_win_prob(game, params.nhl_k),
         "tennis" => tennis_win_prob(game),
         _ => fallback_win_prob(game),
     };
@@ -78,11 +179,18 @@ const SOCCER_TABLE: [[f64; 8]; 7] = [
 ];
 
 /// Minute breakpoints for the table columns.
-const SOCCER_MINUTES: [f64; 8] = [0.0, 15.0, 30.0, 45.0, 60.0, 75.0, 85.0, 90.0];
+pub const SOCCER_MINUTES: [f64; 8] = [0.0, 15.0, 30.0, 45.0, 60.0, 75.0, 85.0, 90.0];
 
-fn soccer_win_prob(game: &LiveGame) -> f64 {
-    let diff = (game.home_score - game.away_score) as f64;
+/// Goal-difference equivalent of a one-player advantage over a full match.
+/// Scaled by the fraction of regulation time remaining.
+const RED_CARD_GOAL_EQUIV: f64 = 0.7;
+
+fn soccer_win_prob(game: &LiveGame, table: &[[f64; 8]; 7]) -> f64 {
     let minute = game.minute.unwrap_or(45) as f64;
+    let card_adv = (game.away_red_cards - game.home_red_cards) as f64;
+    let remaining = ((90.0 - minute) / 90.0).clamp(0.0, 1.0);
+    let diff =
+        (game.home_score - game.away_score) as f64 + RED_CARD_GOAL_EQUIV * card_adv * remaining;
 
     // Clamp goal diff to table range [-3, +3]
     let clamped_diff = diff.clamp(-3.0, 3.0);
@@ -91,17 +199,17 @@ fn soccer_win_prob(game: &LiveGame) -> f64 {
     let row_f = clamped_diff + 3.0; // 0.0 to 6.0
 
     // Bilinear interpolation
-    let p = bilinear_interp(&SOCCER_TABLE, &SOCCER_MINUTES, row_f, minute);
+    let p = bilinear_interp(table, &SOCCER_MINUTES, row_f, minute);
 
     // For extreme diffs beyond ±3, extrapolate conservatively
     if diff > 3.0 {
         // Each extra goal pushes closer to 1.0
         let extra = (diff - 3.0).min(3.0);
-        let base = bilinear_interp(&SOCCER_TABLE, &SOCCER_MINUTES, 6.0, minute);
+        let base = bilinear_interp(table, &SOCCER_MINUTES, 6.0, minute);
         base + (1.0 - base) * (1.0 - (-extra * 0.7).exp())
     } else if diff < -3.0 {
         let extra = (-3.0 - diff).min(3.0);
-        let base = bilinear_interp(&SOCCER_TABLE, &SOCCER_MINUTES, 0.0, minute);
+        let base = bilinear_interp(table, &SOCCER_MINUTES, 0.0, minute);
         base * (-extra * 0.7).exp()
     } else {
         p
@@ -127,13 +235,13 @@ const NBA_K: f64 = 0.50;
 /// Total game duration in minutes.
 const NBA_MINUTES: f64 = 48.0;
 
-fn basketball_win_prob(game: &LiveGame) -> f64 {
+fn basketball_win_prob(game: &LiveGame, k: f64) -> f64 {
     let margin = (game.home_score - game.away_score) as f64;
     let elapsed = game.minute.unwrap_or(24) as f64;
     let remaining = (NBA_MINUTES - elapsed).max(0.1); // avoid division by zero
 
     // Standard NBA win probability logistic model
-    let z = NBA_K * margin / remaining.sqrt();
+    let z = k * margin / remaining.sqrt();
     let p = sigmoid(z);
 
     // Apply home court advantage
@@ -159,7 +267,7 @@ const NFL_MINUTES_PER_POSSESSION: f64 = 5.0;
 /// Total game minutes.
 const NFL_MINUTES: f64 = 60.0;
 
-fn nfl_win_prob(game: &LiveGame) -> f64 {
+fn nfl_win_prob(game: &LiveGame, k: f64) -> f64 {
     let diff = (game.home_score - game.away_score) as f64;
     let elapsed = game.minute.unwrap_or(30) as f64;
     let remaining = (NFL_MINUTES - elapsed).max(0.5);
@@ -169,7 +277,7 @@ fn nfl_win_prob(game: &LiveGame) -> f64 {
 
     // Points per possession ≈ 2.0 in NFL
     // Normalize margin by what's achievable in remaining possessions
-    let z = NFL_K * diff / possessions_remaining.sqrt();
+    let z = k * diff / possessions_remaining.sqrt();
     let p = sigmoid(z);
 
     blend_home_advantage(p, HOME_ADVANTAGE)
@@ -192,14 +300,14 @@ const MLB_K: f64 = 1.20;
 /// Total innings.
 const MLB_INNINGS: f64 = 9.0;
 
-fn baseball_win_prob(game: &LiveGame) -> f64 {
+fn baseball_win_prob(game: &LiveGame, k: f64) -> f64 {
     let diff = (game.home_score - game.away_score) as f64;
     // In baseball, "minute" field stores the inning (1-9)
     let inning = (game.minute.unwrap_or(5) as f64).clamp(1.0, 12.0);
     let innings_remaining = (MLB_INNINGS - inning).max(0.3);
 
     // Runs are rare (~0.5/inning) so each run matters more than basketball pts
-    let z = MLB_K * diff / innings_remaining.sqrt();
+    let z = k * diff / innings_remaining.sqrt();
     let p = sigmoid(z);
 
     // Home advantage is ~54% in MLB (slightly less than other sports)
@@ -224,7 +332,7 @@ const NHL_K: f64 = 0.50;
 /// Total regulation minutes.
 const NHL_MINUTES: f64 = 60.0;
 
-fn hockey_win_prob(game: &LiveGame) -> f64 {
+fn hockey_win_prob(game: &LiveGame, k: f64) -> f64 {
     let diff = (game.home_score - game.away_score) as f64;
     let elapsed = game.minute.unwrap_or(30) as f64;
     let remaining = (NHL_MINUTES - elapsed).max(0.5);
@@ -233,7 +341,7 @@ fn hockey_win_prob(game: &LiveGame) -> f64 {
     // Scale by remaining time: a 1-goal lead with 5 min left is worth more
     // than with 40 min left
     let time_factor = (NHL_MINUTES / remaining).sqrt();
-    let z = NHL_K * diff * time_factor;
+    let z = k * diff * time_factor;
 
     // For very late game (last 2 min), if trailing, empty-net dynamics give
     // the trailing team a small boost
@@ -387,11 +495,34 @@ mod tests {
             away_score: away,
             minute: Some(minute),
             status: GameStatus::InProgress,
+            start_time: None,
+            period: None,
+            added_time: None,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            power_play: None,
         }
     }
 
     // ── Soccer Tests ─────────────────────────────────────────────────────────
 
+    #[test]
+    fn soccer_red_card_shifts_probability() {
+        let ev = make_event("soccer", 0, 0, 30);
+        let level = make_game("soccer", 0, 0, 30);
+        let mut home_down_a_man = level.clone();
+        home_down_a_man.home_red_cards = 1;
+        let p_level = estimate_win_probability(&ev, &level, true);
+        let p_red = estimate_win_probability(&ev, &home_down_a_man, true);
+        assert!(
+            p_red < p_level - 0.05,
+            "level={:.3}, red={:.3}",
+            p_level,
+            p_red
+        );
+        assert_eq!(estimate_game_win_probability(&level, true), p_level);
+    }
+
     #[test]
     fn soccer_tied_at_halftime() {
         let ev = make_event("soccer", 0, 0, 45);
@@ -1,9 +1,28 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
 
 /// Polymarket live-sports betting bot
-#[derive(Parser, Debug, Clone)]
+#[derive(Parser, Debug, Clone, Serialize, Deserialize)]
 #[command(name = "livesports-bot", version, about)]
 pub struct Config {
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+
+    /// TOML or YAML config file.  Its values sit below environment
+    /// variables and CLI flags in precedence.
+    #[arg(long, env = "CONFIG_FILE")]
+    pub config_file: Option<String>,
+
+    /// Named profile from the config file's `profiles` table (e.g. `paper`,
+    /// `live-small`), layered over the file's top-level values.
+    #[arg(long, env = "CONFIG_PROFILE")]
+    pub config_profile: Option<String>,
+
+    /// Print the effective configuration with secrets redacted and exit.
+    #[arg(long)]
+    pub print_config: bool,
+
     /// Run in dry-run mode (no real trades placed)
     #[arg(long, env = "DRY_RUN", default_value = "false")]
     pub dry_run: bool,
@@ -16,6 +35,39 @@ pub struct Config {
     #[arg(long, env = "DASHBOARD_ADDR", default_value = "0.0.0.0:8080")]
     pub dashboard_addr: String,
 
+    /// Admin API credentials as comma-separated `name:token` pairs; the name
+    /// is recorded as the author of audited changes.  The admin endpoints
+    /// are disabled when empty.
+    #[arg(long, env = "ADMIN_TOKENS", value_delimiter = ',')]
+    pub admin_tokens: Vec<String>,
+
+    /// Dashboard bearer tokens, comma-separated.  With these or
+    /// `DASHBOARD_USERS` set, every dashboard route requires credentials
+    /// (admin tokens are accepted too); the dashboard is open when both are
+    /// empty.
+    #[arg(long, env = "DASHBOARD_TOKENS", value_delimiter = ',')]
+    pub dashboard_tokens: Vec<String>,
+
+    /// Dashboard basic-auth users as comma-separated `user:hash` pairs, with
+    /// hashes from `livesports-bot hash-password`.
+    #[arg(long, env = "DASHBOARD_USERS", value_delimiter = ',')]
+    pub dashboard_users: Vec<String>,
+
+    /// Origins allowed to call the dashboard API from other sites, e.g.
+    /// `https://ops.example.com`; `*` allows any.  Cross-origin requests
+    /// are refused when empty.
+    #[arg(long, env = "CORS_ALLOWED_ORIGINS", value_delimiter = ',')]
+    pub cors_allowed_origins: Vec<String>,
+
+    /// PEM certificate chain; with `--tls-key-path` the dashboard serves
+    /// HTTPS.
+    #[arg(long, env = "TLS_CERT_PATH")]
+    pub tls_cert_path: Option<String>,
+
+    /// PEM private key for `--tls-cert-path`.
+    #[arg(long, env = "TLS_KEY_PATH")]
+    pub tls_key_path: Option<String>,
+
     /// SQLite database path
     #[arg(long, env = "DATABASE_PATH", default_value = "livesports.db")]
     pub database_path: String,
@@ -76,6 +128,11 @@ pub struct Config {
     )]
     pub polymarket_sports_ws_url: String,
 
+    /// TOML or JSON file overriding strategy parameters per sport and
+    /// per league; unset parameters inherit from the flags below.
+    #[arg(long, env = "STRATEGY_PROFILES_PATH")]
+    pub strategy_profiles_path: Option<String>,
+
     /// Maximum fraction of bankroll to bet (Kelly multiplier, 0.0–1.0)
     #[arg(long, env = "KELLY_FRACTION", default_value = "0.25")]
     pub kelly_fraction: f64,
@@ -88,6 +145,35 @@ pub struct Config {
     #[arg(long, env = "TAKE_PROFIT_FRACTION", default_value = "0.3")]
     pub take_profit_fraction: f64,
 
+    /// Exit policy for new positions: fixed, trailing, time_decay, breakeven or composite
+    #[arg(long, env = "EXIT_POLICY", default_value = "fixed")]
+    pub exit_policy: String,
+
+    /// Trailing stop distance below the high-water price (e.g. 0.25 = 25%)
+    #[arg(long, env = "TRAILING_STOP_FRACTION", default_value = "0.25")]
+    pub trailing_stop_fraction: f64,
+
+    /// Exit band width left at full time under time decay (e.g. 0.25 = 25% of entry width)
+    #[arg(long, env = "TIME_DECAY_MIN_BAND_FRACTION", default_value = "0.25")]
+    pub time_decay_min_band_fraction: f64,
+
+    /// Move the stop to break-even once this fraction of the take-profit distance is reached
+    #[arg(long, env = "BREAKEVEN_TRIGGER_FRACTION", default_value = "0.5")]
+    pub breakeven_trigger_fraction: f64,
+
+    /// Fraction of a position to sell at the first target (0 disables partial take-profit)
+    #[arg(long, env = "PARTIAL_TAKE_PROFIT_FRACTION", default_value = "0.0")]
+    pub partial_take_profit_fraction: f64,
+
+    /// First target as a fraction of the entry → take-profit distance
+    #[arg(long, env = "PARTIAL_TAKE_PROFIT_TRIGGER", default_value = "0.5")]
+    pub partial_take_profit_trigger: f64,
+
+    /// Scale positions down when event/sport/league exposure caps are exceeded
+    /// (e.g. after equity falls) instead of only blocking new entries.
+    #[arg(long, env = "REDUCE_ON_EXPOSURE_CAP", default_value = "false")]
+    pub reduce_on_exposure_cap: bool,
+
     /// Minimum edge required to place a bet (e.g. 0.05 = 5%)
     #[arg(long, env = "MIN_EDGE", default_value = "0.05")]
     pub min_edge: f64,
@@ -152,6 +238,14 @@ pub struct Config {
     #[arg(long, env = "MAX_POSITIONS_PER_EVENT", default_value = "2")]
     pub max_positions_per_event: u32,
 
+    /// Add to an open position when a later score event confirms it (e.g. a lead is extended)
+    #[arg(long, env = "PYRAMIDING_ENABLED", default_value = "false")]
+    pub pyramiding_enabled: bool,
+
+    /// Maximum number of adds to a single position when pyramiding is enabled
+    #[arg(long, env = "MAX_ADDS_PER_POSITION", default_value = "1")]
+    pub max_adds_per_position: u32,
+
     /// Maximum covariance-adjusted effective exposure fraction of equity.
     #[arg(long, env = "MAX_EFFECTIVE_EXPOSURE_FRACTION", default_value = "0.30")]
     pub max_effective_exposure_fraction: f64,
@@ -208,6 +302,25 @@ pub struct Config {
     #[arg(long, env = "MAX_POSITION_AGE_SECS", default_value = "14400")]
     pub max_position_age_secs: u64,
 
+    /// Model artifact store: versioned win-probability parameter files and
+    /// the `PROMOTED` pointer the engine loads (and reloads on change or
+    /// SIGHUP).  The compiled-in coefficients are used until a version is
+    /// promoted.
+    #[arg(long, env = "MODEL_DIR", default_value = "models")]
+    pub model_dir: String,
+
+    /// Pin a single JSON/TOML parameter file instead of the store's
+    /// promoted version.
+    #[arg(long, env = "WIN_PROB_PARAMS_PATH")]
+    pub win_prob_params_path: Option<String>,
+
+    /// Challenger models priced on every score event alongside the live
+    /// model without trading, comma-separated: `builtin`, a store version or
+    /// a JSON/TOML file path, optionally suffixed `:uncalibrated`
+    /// (`live:uncalibrated` is the live model without calibration).
+    #[arg(long, env = "SHADOW_MODELS", value_delimiter = ',')]
+    pub shadow_models: Vec<String>,
+
     /// Enable periodic outcome-based model calibration.
     #[arg(long, env = "CALIBRATION_ENABLED", default_value = "true")]
     pub calibration_enabled: bool,
@@ -216,9 +329,74 @@ pub struct Config {
     #[arg(long, env = "CALIBRATION_INTERVAL_SECS", default_value = "3600")]
     pub calibration_interval_secs: u64,
 
-    /// Minimum closed trades per sport required to fit calibration.
-    #[arg(long, env = "CALIBRATION_MIN_SAMPLES_PER_SPORT", default_value = "50")]
-    pub calibration_min_samples_per_sport: usize,
+    /// Calibration training data: "observations" (every scored event,
+    /// labelled by final result) or "trades" (closed positions only).
+    #[arg(
+        long,
+        env = "CALIBRATION_TRAINING_SOURCE",
+        default_value = "observations"
+    )]
+    pub calibration_training_source: String,
+
+    /// How often the drift monitors (calibration, edge decay, feed quality)
+    /// run.
+    #[arg(long, env = "DRIFT_CHECK_INTERVAL_SECS", default_value = "300")]
+    pub drift_check_interval_secs: u64,
+
+    /// Window of labelled observations used for rolling calibration log loss.
+    #[arg(long, env = "DRIFT_WINDOW_HOURS", default_value = "48")]
+    pub drift_window_hours: i64,
+
+    /// Minimum labelled observations in the window before a sport's
+    /// calibration drift is judged.
+    #[arg(long, env = "DRIFT_MIN_SAMPLES", default_value = "50")]
+    pub drift_min_samples: usize,
+
+    /// Relative log-loss increase over the last fit that counts as drift.
+    #[arg(long, env = "DRIFT_LOGLOSS_TOLERANCE", default_value = "0.15")]
+    pub drift_logloss_tolerance: f64,
+
+    /// Closed trades per sport in the edge-decay CLV window.
+    #[arg(long, env = "DRIFT_CLV_WINDOW_TRADES", default_value = "20")]
+    pub drift_clv_window_trades: usize,
+
+    /// Mean CLV (bps) over the window below which a sport's edge has decayed.
+    #[arg(long, env = "DRIFT_MIN_CLV_BPS", default_value = "0.0")]
+    pub drift_min_clv_bps: f64,
+
+    /// Rise in the REST-fallback rate over its long-run baseline that counts
+    /// as feed-quality drift.
+    #[arg(long, env = "DRIFT_FEED_FALLBACK_INCREASE", default_value = "0.20")]
+    pub drift_feed_fallback_increase: f64,
+
+    /// Multiple of the long-run WS quote age that counts as feed-quality drift.
+    #[arg(long, env = "DRIFT_FEED_WS_AGE_RATIO", default_value = "2.0")]
+    pub drift_feed_ws_age_ratio: f64,
+
+    /// Response to a drift breach: "alert" (log and record only), "tighten"
+    /// (raise the sport's min edge) or "pause" (no new entries in the sport).
+    #[arg(long, env = "DRIFT_ACTION", default_value = "alert")]
+    pub drift_action: String,
+
+    /// Min-edge add-on applied to a drifting sport when `drift_action` is
+    /// "tighten".
+    #[arg(long, env = "DRIFT_MIN_EDGE_ADDON", default_value = "0.02")]
+    pub drift_min_edge_addon: f64,
+
+    /// Minimum samples required to fit calibration at each level
+    /// (sport, sport/league, sport/league/phase).
+    #[arg(
+        long,
+        env = "CALIBRATION_MIN_SAMPLES_PER_LEVEL",
+        alias = "calibration-min-samples-per-sport",
+        default_value = "50"
+    )]
+    pub calibration_min_samples_per_level: usize,
+
+    /// Pseudo-sample count shrinking league and phase calibrations towards
+    /// their parent level.
+    #[arg(long, env = "CALIBRATION_SHRINKAGE_SAMPLES", default_value = "100")]
+    pub calibration_shrinkage_samples: f64,
 
     /// Relative improvement required (logloss or brier) to promote a new calibration.
     #[arg(
@@ -240,15 +418,110 @@ pub struct Config {
     #[arg(long, env = "CALIBRATION_L2", default_value = "0.001")]
     pub calibration_l2: f64,
 
+    /// Cross-validation folds used to choose between calibration models.
+    #[arg(long, env = "CALIBRATION_CV_FOLDS", default_value = "5")]
+    pub calibration_cv_folds: usize,
+
+    /// Bins for histogram calibration and reliability diagrams.
+    #[arg(long, env = "CALIBRATION_HISTOGRAM_BINS", default_value = "10")]
+    pub calibration_histogram_bins: usize,
+
     /// Score-event dedup window for cross-provider duplicate suppression.
     #[arg(long, env = "SCORE_EVENT_DEDUP_WINDOW_SECS", default_value = "20")]
     pub score_event_dedup_window_secs: u64,
 
+    /// Re-price positions hit by a score correction and keep them if edge survives
+    /// (otherwise they are exited immediately).
+    #[arg(long, env = "SCORE_CORRECTION_REEVALUATE", default_value = "true")]
+    pub score_correction_reevaluate: bool,
+
+    /// Book open positions at their expected payout when the score feed
+    /// reports the final whistle, before the market resolves.  Off by
+    /// default: a late correction or an abandoned match would leave the
+    /// booked PnL wrong.
+    #[arg(long, env = "SETTLE_ON_FINAL_WHISTLE", default_value = "false")]
+    pub settle_on_final_whistle: bool,
+
+    /// On a stop-loss, buy the complementary leg (opposite token, or the other
+    /// team's winner market in sports without draws) when locking the outcome
+    /// beats selling into the book.
+    #[arg(long, env = "HEDGING_ENABLED", default_value = "false")]
+    pub hedging_enabled: bool,
+
+    /// Cross-market arbitrage over mutually exclusive winner markets: off, report or execute
+    #[arg(long, env = "ARBITRAGE_MODE", default_value = "off")]
+    pub arbitrage_mode: String,
+
+    /// Minimum guaranteed profit per basket share set (after fees) to act on
+    #[arg(long, env = "ARBITRAGE_MIN_EDGE", default_value = "0.01")]
+    pub arbitrage_min_edge: f64,
+
+    /// Maximum USD committed to one arbitrage basket
+    #[arg(long, env = "ARBITRAGE_MAX_STAKE_USD", default_value = "20.0")]
+    pub arbitrage_max_stake_usd: f64,
+
+    /// Seconds between arbitrage scans
+    #[arg(long, env = "ARBITRAGE_SCAN_INTERVAL_SECS", default_value = "15")]
+    pub arbitrage_scan_interval_secs: u64,
+
+    /// Rest maker bids/asks around model fair value on live fixtures' winner markets
+    #[arg(long, env = "MARKET_MAKING_ENABLED", default_value = "false")]
+    pub market_making_enabled: bool,
+
+    /// Distance of each maker quote from the (inventory-skewed) fair value
+    #[arg(long, env = "MM_HALF_SPREAD", default_value = "0.02")]
+    pub mm_half_spread: f64,
+
+    /// Shift of the quote centre at full inventory, away from the side held
+    #[arg(long, env = "MM_INVENTORY_SKEW", default_value = "0.02")]
+    pub mm_inventory_skew: f64,
+
+    /// Maximum maker inventory per market (USD cost basis)
+    #[arg(long, env = "MM_MAX_INVENTORY_USD", default_value = "25.0")]
+    pub mm_max_inventory_usd: f64,
+
+    /// Maximum size of a single maker quote (USD)
+    #[arg(long, env = "MM_MAX_QUOTE_USD", default_value = "5.0")]
+    pub mm_max_quote_usd: f64,
+
+    /// Fraction of market liquidity a single quote may show
+    #[arg(long, env = "MM_DEPTH_FRACTION", default_value = "0.01")]
+    pub mm_depth_fraction: f64,
+
+    /// Cancel a live entry, hedge or arbitrage order still unfilled after
+    /// this many seconds
+    #[arg(long, env = "ORDER_TIMEOUT_SECS", default_value = "10")]
+    pub order_timeout_secs: u64,
+
+    /// Chase unfilled orders with cancel/replace while the edge (or, for
+    /// hedges and arbitrage legs, the price cap) allows
+    #[arg(long, env = "ORDER_REPRICE_ENABLED", default_value = "false")]
+    pub order_reprice_enabled: bool,
+
+    /// Maximum cancel/replace cycles per entry
+    #[arg(long, env = "ORDER_MAX_REPRICES", default_value = "2")]
+    pub order_max_reprices: u32,
+
+    /// Exit positions when the model says the remaining edge is gone
+    /// (checked on every score event and position sweep).
+    #[arg(long, env = "MODEL_EXIT_ENABLED", default_value = "true")]
+    pub model_exit_enabled: bool,
+
+    /// Extra margin below model fair value at which a model exit fires.
+    #[arg(long, env = "MODEL_EXIT_EDGE_BUFFER", default_value = "0.0")]
+    pub model_exit_edge_buffer: f64,
+
+    /// Maximum kick-off time difference (minutes) for two providers' events
+    /// to be linked as the same fixture.
+    #[arg(long, env = "FIXTURE_LINK_START_TOLERANCE_MINS", default_value = "90")]
+    pub fixture_link_start_tolerance_mins: i64,
+
     /// Live scores polling interval in seconds
     #[arg(long, env = "POLL_INTERVAL_SECS", default_value = "5")]
     pub poll_interval_secs: u64,
 
-    /// Retain score events for at most this many days.
+    /// Retain score events for at most this many days (archived fixture
+    /// timelines are kept indefinitely).
     #[arg(long, env = "SCORE_EVENTS_RETENTION_DAYS", default_value = "14")]
     pub score_events_retention_days: i64,
 
@@ -257,6 +530,39 @@ pub struct Config {
     pub balance_history_retention_days: i64,
 }
 
+/// Offline commands run instead of the bot.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Fit win-probability model parameters from historical fixtures.
+    FitModels(FitModelsArgs),
+    /// Point the model store's `PROMOTED` pointer at a saved version.
+    PromoteModel(PromoteModelArgs),
+    /// List the versions in the model store.
+    ListModels,
+    /// Read a password from stdin and print its hash for `DASHBOARD_USERS`.
+    HashPassword,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FitModelsArgs {
+    /// SQLite database or `.csv` file with historical game states
+    /// (defaults to the bot database).
+    #[arg(long)]
+    pub source: Option<String>,
+
+    /// Model store the new version is saved to (defaults to `--model-dir`).
+    #[arg(long)]
+    pub output_dir: Option<String>,
+
+    /// Promote the new version straight away.
+    #[arg(long)]
+    pub promote: bool,
+
+    /// Minimum rows required to refit a sport family.
+    #[arg(long, default_value = "200")]
+    pub min_samples: usize,
+}
+
 impl Config {
     pub fn validate(&self) -> anyhow::Result<()> {
         if !self.dry_run {
@@ -280,6 +586,23 @@ impl Config {
         if !(0.0..=10.0).contains(&self.take_profit_fraction) {
             anyhow::bail!("take_profit_fraction must be between 0.0 and 10.0");
         }
+        self.exit_policy
+            .parse::<crate::bot::position::ExitPolicy>()?;
+        if !(0.01..=0.9).contains(&self.trailing_stop_fraction) {
+            anyhow::bail!("trailing_stop_fraction must be between 0.01 and 0.9");
+        }
+        if !(0.0..=1.0).contains(&self.time_decay_min_band_fraction) {
+            anyhow::bail!("time_decay_min_band_fraction must be between 0.0 and 1.0");
+        }
+        if !(0.0..=1.0).contains(&self.breakeven_trigger_fraction) {
+            anyhow::bail!("breakeven_trigger_fraction must be between 0.0 and 1.0");
+        }
+        if !(0.0..1.0).contains(&self.partial_take_profit_fraction) {
+            anyhow::bail!("partial_take_profit_fraction must be in [0.0, 1.0)");
+        }
+        if !(0.0..=1.0).contains(&self.partial_take_profit_trigger) {
+            anyhow::bail!("partial_take_profit_trigger must be between 0.0 and 1.0");
+        }
         if self.initial_balance <= 0.0 {
             anyhow::bail!("initial_balance must be positive");
         }
@@ -325,6 +648,38 @@ impl Config {
         if self.max_positions_per_event == 0 {
             anyhow::bail!("max_positions_per_event must be positive");
         }
+        self.arbitrage_mode
+            .parse::<crate::bot::arbitrage::ArbitrageMode>()?;
+        if !(0.0..=0.5).contains(&self.arbitrage_min_edge) {
+            anyhow::bail!("arbitrage_min_edge must be between 0.0 and 0.5");
+        }
+        if self.arbitrage_max_stake_usd <= 0.0 {
+            anyhow::bail!("arbitrage_max_stake_usd must be positive");
+        }
+        if self.arbitrage_scan_interval_secs == 0 {
+            anyhow::bail!("arbitrage_scan_interval_secs must be positive");
+        }
+        if !(0.005..=0.25).contains(&self.mm_half_spread) {
+            anyhow::bail!("mm_half_spread must be between 0.005 and 0.25");
+        }
+        if !(0.0..=0.25).contains(&self.mm_inventory_skew) {
+            anyhow::bail!("mm_inventory_skew must be between 0.0 and 0.25");
+        }
+        if self.mm_max_inventory_usd <= 0.0 || self.mm_max_quote_usd <= 0.0 {
+            anyhow::bail!("mm_max_inventory_usd and mm_max_quote_usd must be positive");
+        }
+        if !(0.0..=1.0).contains(&self.mm_depth_fraction) {
+            anyhow::bail!("mm_depth_fraction must be between 0.0 and 1.0");
+        }
+        if self.order_timeout_secs == 0 {
+            anyhow::bail!("order_timeout_secs must be positive");
+        }
+        if self.order_max_reprices > 10 {
+            anyhow::bail!("order_max_reprices must be at most 10");
+        }
+        if self.max_adds_per_position > 10 {
+            anyhow::bail!("max_adds_per_position must be at most 10");
+        }
         if !(0.0..=1.0).contains(&self.max_effective_exposure_fraction) {
             anyhow::bail!("max_effective_exposure_fraction must be between 0.0 and 1.0");
         }
@@ -366,10 +721,13 @@ impl Config {
         {
             anyhow::bail!("calibration_interval_secs must be between 1 and 604800");
         }
-        if self.calibration_min_samples_per_sport < 10
-            || self.calibration_min_samples_per_sport > 1_000_000
+        if self.calibration_min_samples_per_level < 10
+            || self.calibration_min_samples_per_level > 1_000_000
         {
-            anyhow::bail!("calibration_min_samples_per_sport must be between 10 and 1000000");
+            anyhow::bail!("calibration_min_samples_per_level must be between 10 and 1000000");
+        }
+        if !(0.0..=100_000.0).contains(&self.calibration_shrinkage_samples) {
+            anyhow::bail!("calibration_shrinkage_samples must be between 0 and 100000");
         }
         if !(0.0..=0.5).contains(&self.calibration_min_relative_improvement) {
             anyhow::bail!("calibration_min_relative_improvement must be between 0.0 and 0.5");
@@ -383,15 +741,95 @@ impl Config {
         if !(0.0..=1.0).contains(&self.calibration_l2) {
             anyhow::bail!("calibration_l2 must be between 0.0 and 1.0");
         }
+        if !matches!(
+            self.calibration_training_source.as_str(),
+            "observations" | "trades"
+        ) {
+            anyhow::bail!("calibration_training_source must be 'observations' or 'trades'");
+        }
+        if self.drift_check_interval_secs == 0 {
+            anyhow::bail!("drift_check_interval_secs must be positive");
+        }
+        if self.drift_window_hours <= 0 {
+            anyhow::bail!("drift_window_hours must be positive");
+        }
+        if self.drift_min_samples == 0 || self.drift_clv_window_trades == 0 {
+            anyhow::bail!("drift_min_samples and drift_clv_window_trades must be positive");
+        }
+        if !(0.0..=5.0).contains(&self.drift_logloss_tolerance) {
+            anyhow::bail!("drift_logloss_tolerance must be between 0.0 and 5.0");
+        }
+        if !(0.0..=1.0).contains(&self.drift_feed_fallback_increase) {
+            anyhow::bail!("drift_feed_fallback_increase must be between 0.0 and 1.0");
+        }
+        if self.drift_feed_ws_age_ratio <= 1.0 {
+            anyhow::bail!("drift_feed_ws_age_ratio must be greater than 1.0");
+        }
+        self.drift_action
+            .parse::<crate::bot::drift::DriftAction>()?;
+        if !(0.0..=0.5).contains(&self.drift_min_edge_addon) {
+            anyhow::bail!("drift_min_edge_addon must be between 0.0 and 0.5");
+        }
+        if !(2..=20).contains(&self.calibration_cv_folds) {
+            anyhow::bail!("calibration_cv_folds must be between 2 and 20");
+        }
+        if !(2..=100).contains(&self.calibration_histogram_bins) {
+            anyhow::bail!("calibration_histogram_bins must be between 2 and 100");
+        }
         if self.score_event_dedup_window_secs == 0 || self.score_event_dedup_window_secs > 600 {
             anyhow::bail!("score_event_dedup_window_secs must be between 1 and 600");
         }
+        if !(0.0..=0.2).contains(&self.model_exit_edge_buffer) {
+            anyhow::bail!("model_exit_edge_buffer must be between 0.0 and 0.2");
+        }
+        if !(1..=24 * 60).contains(&self.fixture_link_start_tolerance_mins) {
+            anyhow::bail!("fixture_link_start_tolerance_mins must be between 1 and 1440");
+        }
         if self.score_events_retention_days <= 0 {
             anyhow::bail!("score_events_retention_days must be positive");
         }
         if self.balance_history_retention_days <= 0 {
             anyhow::bail!("balance_history_retention_days must be positive");
         }
+        for entry in &self.admin_tokens {
+            match entry.split_once(':') {
+                Some((name, token)) if !name.trim().is_empty() && token.trim().len() >= 16 => {}
+                _ => anyhow::bail!(
+                    "admin_tokens entries must be 'name:token' with a token of at least 16 characters"
+                ),
+            }
+        }
+        if self.dashboard_tokens.iter().any(|t| t.trim().len() < 16) {
+            anyhow::bail!("dashboard_tokens must be at least 16 characters");
+        }
+        crate::dashboard::DashboardAuth::new(&self.dashboard_tokens, &self.dashboard_users)?;
+        for origin in &self.cors_allowed_origins {
+            let origin = origin.trim();
+            if origin == "*" {
+                continue;
+            }
+            let valid = url::Url::parse(origin).is_ok_and(|u| {
+                matches!(u.scheme(), "http" | "https")
+                    && u.host().is_some()
+                    && u.path() == "/"
+                    && u.query().is_none()
+            });
+            if !valid {
+                anyhow::bail!(
+                    "cors_allowed_origins entries must be '*' or an origin like 'https://host:port', got '{}'",
+                    origin
+                );
+            }
+        }
+        if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
+            anyhow::bail!("tls_cert_path and tls_key_path must be set together");
+        }
         Ok(())
     }
 }
+
+#[derive(Args, Debug, Clone)]
+pub struct PromoteModelArgs {
+    /// Version to promote, e.g. `20261018T120000Z`
+    pub version: String,
+}
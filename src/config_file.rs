@@ -0,0 +1,337 @@
+//! Layered configuration: config file < environment < CLI flags.
+//!
+//! The file (`--config-file`, TOML or YAML) uses the flag names in snake or
+//! kebab case.  Top-level values apply to every run; a named profile under
+//! `profiles` is layered on top, so one file can describe several
+//! deployments:
+//!
+//! ```toml
+//! min_edge = 0.05
+//!
+//! [profiles.paper]
+//! dry_run = true
+//! initial_balance = 1000.0
+//!
+//! [profiles.live-small]
+//! kelly_fraction = 0.1
+//! max_trades_per_day = 20
+//! ```
+//!
+//! File values become the flags' defaults, so clap still parses them and
+//! environment variables and CLI flags override them as usual.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::Context;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::config::Config;
+
+/// Flags that select the file itself and cannot be set from it.
+const FILE_ONLY_FLAGS: &[&str] = &["config_file", "config_profile", "print_config"];
+
+/// Renamed environment variables still read when the new name is unset:
+/// `(flag id, old variable)`.
+const DEPRECATED_ENV: &[(&str, &str)] = &[(
+    "calibration_min_samples_per_level",
+    "CALIBRATION_MIN_SAMPLES_PER_SPORT",
+)];
+
+const REDACTED: &str = "<redacted>";
+
+/// Where an effective value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+impl Source {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Source::Default => "default",
+            Source::File => "file",
+            Source::Env => "env",
+            Source::Cli => "cli",
+        }
+    }
+}
+
+/// One effective top-level setting.
+#[derive(Debug, Clone)]
+pub struct Setting {
+    pub key: String,
+    /// Raw values; empty when unset
+    pub values: Vec<String>,
+    pub source: Source,
+    pub secret: bool,
+}
+
+/// Parse the process arguments and environment over the config file.
+pub fn load() -> anyhow::Result<(Config, Vec<Setting>)> {
+    load_from(std::env::args_os())
+}
+
+pub fn load_from<I, T>(args: I) -> anyhow::Result<(Config, Vec<Setting>)>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    // First pass only locates the file and profile.
+    let first = Config::command().get_matches_from(args.clone());
+    let file = first.get_one::<String>("config_file").cloned();
+    let profile = first.get_one::<String>("config_profile").cloned();
+
+    let mut command = Config::command();
+    let mut file_keys = BTreeSet::new();
+    match (&file, &profile) {
+        (Some(path), profile) => {
+            for (key, value) in read_file(Path::new(path), profile.as_deref())? {
+                let id = key.replace('-', "_");
+                if FILE_ONLY_FLAGS.contains(&id.as_str())
+                    || !command.get_arguments().any(|a| a.get_id() == id.as_str())
+                {
+                    anyhow::bail!("unknown setting '{}' in {}", key, path);
+                }
+                let values = arg_values(&value).with_context(|| format!("setting '{}'", key))?;
+                command = command.mut_arg(id.as_str(), |arg| arg.default_values(values));
+                file_keys.insert(id);
+            }
+        }
+        (None, Some(_)) => anyhow::bail!("--config-profile requires --config-file"),
+        (None, None) => {}
+    }
+    for &(id, old) in DEPRECATED_ENV {
+        let Some(current) = command
+            .get_arguments()
+            .find(|a| a.get_id() == id)
+            .and_then(|a| a.get_env())
+            .map(|env| env.to_owned())
+        else {
+            continue;
+        };
+        if std::env::var_os(&current).is_none() && std::env::var_os(old).is_some() {
+            warn!(
+                "{} is deprecated; set {} instead",
+                old,
+                current.to_string_lossy()
+            );
+            command = command.mut_arg(id, |arg| arg.env(old));
+        }
+    }
+
+    let matches = command.try_get_matches_from_mut(args)?;
+    let config = Config::from_arg_matches(&matches)?;
+    let settings = command
+        .get_arguments()
+        .map(|arg| arg.get_id().as_str())
+        .filter(|id| !matches!(*id, "help" | "version") && !FILE_ONLY_FLAGS.contains(id))
+        .map(|id| setting(&matches, id, &file_keys))
+        .collect();
+    Ok((config, settings))
+}
+
+/// The file's top-level values with the selected profile layered on top.
+fn read_file(path: &Path, profile: Option<&str>) -> anyhow::Result<BTreeMap<String, Value>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let root: Value = match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml" | "yml") => serde_norway::from_str(&text)
+            .with_context(|| format!("Invalid YAML in {}", path.display()))?,
+        _ => {
+            toml::from_str(&text).with_context(|| format!("Invalid TOML in {}", path.display()))?
+        }
+    };
+    let Value::Object(mut root) = root else {
+        anyhow::bail!("{} must contain a table of settings", path.display());
+    };
+    let profiles = match root.remove("profiles") {
+        Some(Value::Object(profiles)) => profiles,
+        Some(_) => anyhow::bail!("'profiles' in {} must be a table", path.display()),
+        None => Default::default(),
+    };
+    let mut values: BTreeMap<String, Value> = root.into_iter().collect();
+    if let Some(name) = profile {
+        let Some(Value::Object(overrides)) = profiles.get(name) else {
+            anyhow::bail!(
+                "profile '{}' not found in {} (available: {})",
+                name,
+                path.display(),
+                profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            );
+        };
+        values.extend(overrides.clone());
+    }
+    Ok(values)
+}
+
+fn arg_values(value: &Value) -> anyhow::Result<Vec<String>> {
+    match value {
+        Value::Array(items) => items.iter().map(scalar).collect(),
+        other => Ok(vec![scalar(other)?]),
+    }
+}
+
+fn scalar(value: &Value) -> anyhow::Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => anyhow::bail!("expected a string, number or boolean, got {}", other),
+    }
+}
+
+fn setting(matches: &ArgMatches, id: &str, file_keys: &BTreeSet<String>) -> Setting {
+    let values = matches
+        .get_raw(id)
+        .map(|raw| raw.map(|v| v.to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    let source = match matches.value_source(id) {
+        Some(ValueSource::CommandLine) => Source::Cli,
+        Some(ValueSource::EnvVariable) => Source::Env,
+        _ if file_keys.contains(id) => Source::File,
+        _ => Source::Default,
+    };
+    Setting {
+        key: id.to_string(),
+        values,
+        source,
+        secret: is_secret(id),
+    }
+}
+
+/// Credentials (and password hashes) are never printed.
+fn is_secret(key: &str) -> bool {
+    key.ends_with("_key")
+        || key.ends_with("_users")
+        || key.contains("secret")
+        || key.contains("token")
+        || key.contains("password")
+}
+
+/// Render settings as a TOML config file annotated with each value's source.
+/// Every value is quoted; clap parses them the same way as file values.
+pub fn render(settings: &[Setting]) -> String {
+    let mut out = String::new();
+    for s in settings {
+        let value = if s.values.is_empty() {
+            out.push_str(&format!("# {} is unset\n", s.key));
+            continue;
+        } else if s.secret {
+            toml::Value::String(REDACTED.to_string()).to_string()
+        } else if s.values.len() == 1 {
+            toml::Value::String(s.values[0].clone()).to_string()
+        } else {
+            toml::Value::Array(s.values.iter().cloned().map(toml::Value::String).collect())
+                .to_string()
+        };
+        out.push_str(&format!("{} = {}  # {}\n", s.key, value, s.source.as_str()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("livesports-config-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    const FILE: &str = r#"
+min_edge = 0.07
+kelly_fraction = 0.2
+polymarket_api_key = "from-file"
+
+[profiles.paper]
+dry_run = true
+initial_balance = 1000
+
+[profiles.live-small]
+kelly_fraction = 0.1
+shadow_models = ["builtin", "live:uncalibrated"]
+"#;
+
+    #[test]
+    fn layers_profile_over_file_and_cli_over_both() {
+        let path = temp_file("layers.toml", FILE);
+        let (config, settings) = load_from([
+            "livesports-bot",
+            "--config-file",
+            &path,
+            "--config-profile",
+            "live-small",
+            "--min-edge",
+            "0.03",
+        ])
+        .unwrap();
+        assert_eq!(config.min_edge, 0.03);
+        assert_eq!(config.kelly_fraction, 0.1);
+        assert_eq!(config.shadow_models, vec!["builtin", "live:uncalibrated"]);
+        assert!(!config.dry_run);
+
+        let source = |key: &str| settings.iter().find(|s| s.key == key).unwrap().source;
+        assert_eq!(source("min_edge"), Source::Cli);
+        assert_eq!(source("kelly_fraction"), Source::File);
+        assert_eq!(source("take_profit_fraction"), Source::Default);
+
+        let rendered = render(&settings);
+        assert!(rendered.contains("polymarket_api_key = \"<redacted>\"  # file"));
+        assert!(!rendered.contains("from-file"));
+        assert!(rendered.contains("shadow_models = [\"builtin\", \"live:uncalibrated\"]"));
+
+        let (paper, _) = load_from([
+            "livesports-bot",
+            "--config-file",
+            &path,
+            "--config-profile",
+            "paper",
+        ])
+        .unwrap();
+        assert!(paper.dry_run);
+        assert_eq!(paper.initial_balance, 1000.0);
+        assert_eq!(paper.kelly_fraction, 0.2);
+    }
+
+    #[test]
+    fn reads_renamed_env_vars_as_a_fallback() {
+        // No other test reads this variable.
+        std::env::set_var("CALIBRATION_MIN_SAMPLES_PER_SPORT", "75");
+        let (config, settings) = load_from(["livesports-bot"]).unwrap();
+        std::env::remove_var("CALIBRATION_MIN_SAMPLES_PER_SPORT");
+        assert_eq!(config.calibration_min_samples_per_level, 75);
+        let setting = settings
+            .iter()
+            .find(|s| s.key == "calibration_min_samples_per_level")
+            .unwrap();
+        assert_eq!(setting.source, Source::Env);
+    }
+
+    #[test]
+    fn rejects_unknown_settings_and_profiles() {
+        let path = temp_file("unknown.yaml", "min_edge: 0.05\nmin_edg: 0.05\n");
+        assert!(load_from(["livesports-bot", "--config-file", &path]).is_err());
+
+        let path = temp_file("profiles.yaml", "profiles:\n  paper:\n    dry_run: true\n");
+        assert!(load_from(["livesports-bot", "--config-file", &path]).is_ok());
+        let missing = load_from([
+            "livesports-bot",
+            "--config-file",
+            &path,
+            "--config-profile",
+            "live-full",
+        ]);
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,389 @@
+//! Authenticated admin API: runtime strategy parameters and manual trading
+//! controls (see `bot::control`).
+//!
+//! Callers present `Authorization: Bearer <token>` with one of the
+//! `admin_tokens`; the token's name is recorded as the author of each change.
+//! A patch is applied to a copy of the live config and checked with
+//! `Config::validate` before it is audited to `config_changes` and handed to
+//! the engine, which swaps it in without losing its in-memory state.
+//! Control commands are forwarded to the engine's main loop and answered
+//! once it has run them; the engine audits them to `control_actions`.
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use tokio::sync::oneshot;
+use tracing::info;
+
+use crate::bot::control::{ControlCommand, ControlOutcome, ControlRequest};
+use crate::bot::profiles::StrategyProfiles;
+use crate::config::Config;
+use crate::db::ConfigChange;
+
+use super::auth::constant_time_eq;
+use super::AppState;
+
+/// Parameters the engine reads on every use, so changing them takes effect
+/// without a restart.  Intervals, paths, credentials and trading mode are
+/// fixed at startup.
+pub const RUNTIME_PARAMS: &[&str] = &[
+    "min_edge",
+    "kelly_fraction",
+    "stop_loss_fraction",
+    "take_profit_fraction",
+    "exit_policy",
+    "trailing_stop_fraction",
+    "time_decay_min_band_fraction",
+    "breakeven_trigger_fraction",
+    "partial_take_profit_fraction",
+    "partial_take_profit_trigger",
+    "reduce_on_exposure_cap",
+    "expected_fee_bps",
+    "expected_slippage_bps",
+    "latency_max_score_age_ms",
+    "latency_min_expected_move",
+    "latency_min_residual_move",
+    "latency_max_priced_in_ratio",
+    "ws_price_max_age_ms",
+    "max_entry_quote_divergence",
+    "adaptive_min_edge_max_addon",
+    "adaptive_divergence_tightening",
+    "max_event_exposure_fraction",
+    "max_sport_exposure_fraction",
+    "max_league_exposure_fraction",
+    "max_team_exposure_fraction",
+    "max_positions_per_event",
+    "pyramiding_enabled",
+    "max_adds_per_position",
+    "max_effective_exposure_fraction",
+    "correlation_same_event",
+    "correlation_same_team",
+    "correlation_same_league",
+    "correlation_same_sport",
+    "max_daily_drawdown_fraction",
+    "max_trades_per_day",
+    "feed_health_max_rest_fallback_rate",
+    "feed_health_max_ws_age_ms",
+    "feed_health_cooldown_secs",
+    "feed_health_flatten_after_secs",
+    "max_position_age_secs",
+    "drift_action",
+    "drift_min_edge_addon",
+    "hedging_enabled",
+    "arbitrage_min_edge",
+    "arbitrage_max_stake_usd",
+    "mm_half_spread",
+    "mm_max_quote_usd",
+    "order_timeout_secs",
+    "order_max_reprices",
+    "model_exit_enabled",
+    "model_exit_edge_buffer",
+];
+
+/// `name:token` admin credentials.
+#[derive(Debug, Clone, Default)]
+pub struct AdminTokens(Vec<(String, String)>);
+
+impl AdminTokens {
+    /// Parse `name:token` entries; malformed ones are rejected by
+    /// `Config::validate`.
+    pub fn parse(entries: &[String]) -> Self {
+        AdminTokens(
+            entries
+                .iter()
+                .filter_map(|entry| entry.split_once(':'))
+                .map(|(name, token)| (name.trim().to_string(), token.trim().to_string()))
+                .collect(),
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    /// Name of the token presented as a bearer token.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Option<&str> {
+        let presented = headers
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")?
+            .trim();
+        self.0
+            .iter()
+            .find(|(_, token)| constant_time_eq(token.as_bytes(), presented.as_bytes()))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Current values of the runtime-adjustable parameters.
+pub fn runtime_params(config: &Config) -> Map<String, Value> {
+    let Ok(Value::Object(all)) = serde_json::to_value(config) else {
+        return Map::new();
+    };
+    all.into_iter()
+        .filter(|(key, _)| RUNTIME_PARAMS.contains(&key.as_str()))
+        .collect()
+}
+
+/// `(key, old, new)` of a changed parameter.
+pub type ParamChange = (String, Value, Value);
+
+/// Apply `patch` to a copy of `config` and validate it, together with the
+/// strategy profiles re-derived over it.  Returns the new config and the
+/// parameters that actually changed.
+pub fn apply_patch(
+    config: &Config,
+    profiles: &StrategyProfiles,
+    patch: &Map<String, Value>,
+) -> anyhow::Result<(Config, Vec<ParamChange>)> {
+    let mut value = serde_json::to_value(config)?;
+    let fields = value
+        .as_object_mut()
+        .context("config does not serialize to an object")?;
+    for (key, new) in patch {
+        if !RUNTIME_PARAMS.contains(&key.as_str()) {
+            anyhow::bail!("'{}' cannot be changed at runtime", key);
+        }
+        fields.insert(key.clone(), new.clone());
+    }
+    let mut patched: Config = serde_json::from_value(value).context("invalid parameter value")?;
+    patched.command = config.command.clone();
+    patched.validate()?;
+    profiles.rebase(&patched)?;
+
+    let before = runtime_params(config);
+    let after = runtime_params(&patched);
+    let changes = after
+        .into_iter()
+        .filter_map(|(key, new)| {
+            let old = before.get(&key).cloned().unwrap_or(Value::Null);
+            (old != new).then_some((key, old, new))
+        })
+        .collect();
+    Ok((patched, changes))
+}
+
+type ApiError = (StatusCode, String);
+
+fn authorize<'a>(state: &'a AppState, headers: &HeaderMap) -> Result<&'a str, ApiError> {
+    if !state.admin_tokens.is_enabled() {
+        return Err((StatusCode::NOT_FOUND, "admin API is disabled".into()));
+    }
+    state.admin_tokens.authenticate(headers).ok_or((
+        StatusCode::UNAUTHORIZED,
+        "invalid or missing admin token".into(),
+    ))
+}
+
+/// GET /api/admin/config
+pub async fn get_config_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    authorize(&state, &headers)?;
+    Ok(Json(runtime_params(&state.config.borrow())))
+}
+
+/// PATCH /api/admin/config with a JSON object of parameter values.
+pub async fn patch_config_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(patch): Json<Map<String, Value>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor = authorize(&state, &headers)?;
+    let mut result: Result<Vec<ConfigChange>, ApiError> = Ok(Vec::new());
+    // Validate, audit and swap under the channel's write lock so concurrent
+    // patches cannot interleave.
+    state.config.send_if_modified(|config| {
+        let (patched, changes) = match apply_patch(config, &state.profiles, &patch) {
+            Ok(applied) => applied,
+            Err(e) => {
+                result = Err((StatusCode::BAD_REQUEST, format!("{:#}", e)));
+                return false;
+            }
+        };
+        if changes.is_empty() {
+            return false;
+        }
+        let now = Utc::now();
+        let rows: Vec<ConfigChange> = changes
+            .into_iter()
+            .map(|(key, old, new)| ConfigChange {
+                id: None,
+                actor: actor.to_string(),
+                key,
+                old_value: old.to_string(),
+                new_value: new.to_string(),
+                changed_at: now,
+            })
+            .collect();
+        if let Err(e) = state.db.insert_config_changes(&rows) {
+            result = Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
+            return false;
+        }
+        for row in &rows {
+            info!(
+                "Admin {} changed {}: {} -> {}",
+                row.actor, row.key, row.old_value, row.new_value
+            );
+        }
+        *config = patched;
+        result = Ok(rows);
+        true
+    });
+    let changes = result?;
+    Ok(Json(json!({
+        "changes": changes,
+        "params": runtime_params(&state.config.borrow()),
+    })))
+}
+
+/// GET /api/admin/config/audit
+pub async fn config_audit_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    authorize(&state, &headers)?;
+    state
+        .db
+        .list_config_changes(200)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// GET /api/admin/controls/audit
+pub async fn controls_audit_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    authorize(&state, &headers)?;
+    state
+        .db
+        .list_control_actions(200)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Hand a control command to the engine and wait for its outcome.
+async fn send_control(
+    state: &AppState,
+    actor: &str,
+    command: ControlCommand,
+) -> Result<Json<ControlOutcome>, ApiError> {
+    let unavailable = || {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "bot engine is not running".to_string(),
+        )
+    };
+    let (reply, outcome) = oneshot::channel();
+    state
+        .control
+        .send(ControlRequest {
+            actor: actor.to_string(),
+            command,
+            reply,
+        })
+        .await
+        .map_err(|_| unavailable())?;
+    match outcome.await.map_err(|_| unavailable())? {
+        Ok(outcome) => Ok(Json(outcome)),
+        Err(e) => Err((StatusCode::BAD_REQUEST, e)),
+    }
+}
+
+/// GET /api/admin/controls
+pub async fn controls_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor = authorize(&state, &headers)?;
+    send_control(&state, actor, ControlCommand::Status).await
+}
+
+/// POST /api/admin/controls with a command such as
+/// `{"action":"pause","sport":"nba"}` (see `ControlCommand`).
+pub async fn control_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(command): Json<ControlCommand>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor = authorize(&state, &headers)?;
+    send_control(&state, actor, command).await
+}
+
+/// POST /api/admin/positions/:id/close
+pub async fn close_position_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(position_id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor = authorize(&state, &headers)?;
+    send_control(&state, actor, ControlCommand::ClosePosition { position_id }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn config() -> Config {
+        Config::parse_from(["livesports-bot", "--dry-run"])
+    }
+
+    fn patch(config: &Config, patch: Value) -> anyhow::Result<(Config, Vec<ParamChange>)> {
+        apply_patch(
+            config,
+            &StrategyProfiles::global(config),
+            patch.as_object().unwrap(),
+        )
+    }
+
+    #[test]
+    fn patches_runtime_params_with_validation() {
+        let config = config();
+        let (patched, changes) = patch(
+            &config,
+            json!({"min_edge": 0.03, "max_event_exposure_fraction": 0.1, "kelly_fraction": config.kelly_fraction}),
+        )
+        .unwrap();
+        assert_eq!(patched.min_edge, 0.03);
+        assert_eq!(patched.max_event_exposure_fraction, 0.1);
+        assert_eq!(patched.database_path, config.database_path);
+        // Unchanged values are not reported.
+        let keys: Vec<&str> = changes.iter().map(|(k, _, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["max_event_exposure_fraction", "min_edge"]);
+        assert_eq!(changes[1].2, json!(0.03));
+
+        assert!(patch(&config, json!({"kelly_fraction": 1.5})).is_err());
+        assert!(patch(&config, json!({"max_trades_per_day": "many"})).is_err());
+        assert!(patch(&config, json!({"dry_run": false})).is_err());
+    }
+
+    #[test]
+    fn authenticates_bearer_tokens_by_name() {
+        let tokens = AdminTokens::parse(&["ops:0123456789abcdef".into()]);
+        let mut headers = HeaderMap::new();
+        assert_eq!(tokens.authenticate(&headers), None);
+        headers.insert(
+            header::AUTHORIZATION,
+            "Bearer 0123456789abcdeX".parse().unwrap(),
+        );
+        assert_eq!(tokens.authenticate(&headers), None);
+        headers.insert(
+            header::AUTHORIZATION,
+            "Bearer 0123456789abcdef".parse().unwrap(),
+        );
+        assert_eq!(tokens.authenticate(&headers), Some("ops"));
+        assert!(!AdminTokens::parse(&[]).is_enabled());
+    }
+}
@@ -0,0 +1,438 @@
+//! Dashboard authentication.
+//!
+//! With `DASHBOARD_TOKENS` or `DASHBOARD_USERS` set, every dashboard API
+//! route requires either a bearer token or HTTP basic credentials; the page
+//! itself carries no data and is served openly so its script can send them.
+//! Passwords are stored as PBKDF2-SHA256 hashes (`livesports-bot
+//! hash-password`); admin tokens are accepted as well so the admin API keeps
+//! working behind it.  Each wrong password costs a full PBKDF2 run, so failed
+//! basic logins are limited per client address.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use super::AppState;
+
+/// Scheme tag of stored password hashes.
+pub const HASH_SCHEME: &str = "pbkdf2-sha256";
+
+const DEFAULT_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const REALM: &str = "livesports-bot";
+/// Failed basic logins allowed per client address within `FAILURE_WINDOW`.
+const MAX_FAILURES: u32 = 10;
+const FAILURE_WINDOW: Duration = Duration::from_secs(60);
+/// Tracked addresses before expired windows are swept.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+/// How long verified basic credentials skip PBKDF2, and how many are kept.
+const VERIFIED_TTL: Duration = Duration::from_secs(15 * 60);
+const MAX_VERIFIED: usize = 1_000;
+
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// A salted password hash, written `pbkdf2-sha256$<iterations>$<salt>$<hash>`
+/// with base64 salt and hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordHash {
+    iterations: u32,
+    salt: Vec<u8>,
+    hash: [u8; 32],
+}
+
+impl PasswordHash {
+    /// Hash `password` with a fresh random salt.
+    pub fn generate(password: &str) -> Self {
+        let mut salt = vec![0u8; SALT_LEN];
+        rand::thread_rng().fill(&mut salt[..]);
+        let hash = derive(password, &salt, DEFAULT_ITERATIONS);
+        PasswordHash {
+            iterations: DEFAULT_ITERATIONS,
+            salt,
+            hash,
+        }
+    }
+
+    pub fn parse(encoded: &str) -> anyhow::Result<Self> {
+        let parts: Vec<&str> = encoded.trim().split('$').collect();
+        let [scheme, iterations, salt, hash] = parts[..] else {
+            anyhow::bail!(
+                "password hash must be '{}$<iterations>$<salt>$<hash>'",
+                HASH_SCHEME
+            );
+        };
+        if scheme != HASH_SCHEME {
+            anyhow::bail!("unsupported password hash scheme '{}'", scheme);
+        }
+        let iterations: u32 = iterations
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .context("password hash iterations must be a positive integer")?;
+        let salt = STANDARD
+            .decode(salt)
+            .context("invalid password hash salt")?;
+        let hash = STANDARD
+            .decode(hash)
+            .ok()
+            .and_then(|h| <[u8; 32]>::try_from(h).ok())
+            .context("password hash must be 32 base64-encoded bytes")?;
+        Ok(PasswordHash {
+            iterations,
+            salt,
+            hash,
+        })
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        constant_time_eq(&derive(password, &self.salt, self.iterations), &self.hash)
+    }
+}
+
+impl fmt::Display for PasswordHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}${}${}${}",
+            HASH_SCHEME,
+            self.iterations,
+            STANDARD.encode(&self.salt),
+            STANDARD.encode(self.hash)
+        )
+    }
+}
+
+fn derive(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut out);
+    out
+}
+
+/// Failed basic logins per client address in the current window.
+#[derive(Default)]
+struct FailureLimiter {
+    clients: Mutex<HashMap<IpAddr, (u32, Instant)>>,
+}
+
+impl FailureLimiter {
+    fn allows(&self, client: IpAddr, now: Instant) -> bool {
+        match self.clients.lock().unwrap().get(&client) {
+            Some(&(failures, since)) => {
+                failures < MAX_FAILURES || now.duration_since(since) >= FAILURE_WINDOW
+            }
+            None => true,
+        }
+    }
+
+    fn record_failure(&self, client: IpAddr, now: Instant) {
+        let mut clients = self.clients.lock().unwrap();
+        if clients.len() >= MAX_TRACKED_CLIENTS {
+            clients.retain(|_, (_, since)| now.duration_since(*since) < FAILURE_WINDOW);
+        }
+        let entry = clients.entry(client).or_insert((0, now));
+        if now.duration_since(entry.1) >= FAILURE_WINDOW {
+            *entry = (0, now);
+        }
+        entry.0 += 1;
+    }
+}
+
+/// Digests of basic credentials that verified recently, so each password
+/// is only run through PBKDF2 once per `VERIFIED_TTL` rather than on every
+/// poll.
+#[derive(Default)]
+struct VerifiedCache {
+    digests: Mutex<HashMap<[u8; 32], Instant>>,
+}
+
+impl VerifiedCache {
+    fn contains(&self, digest: &[u8; 32], now: Instant) -> bool {
+        self.digests
+            .lock()
+            .unwrap()
+            .get(digest)
+            .is_some_and(|&at| now.duration_since(at) < VERIFIED_TTL)
+    }
+
+    fn insert(&self, digest: [u8; 32], now: Instant) {
+        let mut digests = self.digests.lock().unwrap();
+        if digests.len() >= MAX_VERIFIED {
+            digests.retain(|_, at| now.duration_since(*at) < VERIFIED_TTL);
+            if digests.len() >= MAX_VERIFIED {
+                digests.clear();
+            }
+        }
+        digests.insert(digest, now);
+    }
+}
+
+/// Result of checking a request's dashboard credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthCheck {
+    Granted,
+    Denied,
+    /// Too many failed basic logins from this client; not verified
+    Throttled,
+}
+
+/// Credentials accepted by the dashboard; open when none are configured.
+#[derive(Clone, Default)]
+pub struct DashboardAuth {
+    tokens: Vec<String>,
+    users: Vec<(String, PasswordHash)>,
+    verified: Arc<VerifiedCache>,
+    failures: Arc<FailureLimiter>,
+}
+
+impl DashboardAuth {
+    /// Build from bearer tokens and `user:hash` entries.
+    pub fn new(tokens: &[String], users: &[String]) -> anyhow::Result<Self> {
+        let users = users
+            .iter()
+            .map(|entry| {
+                let (user, hash) = entry
+                    .split_once(':')
+                    .filter(|(user, _)| !user.trim().is_empty())
+                    .context("dashboard_users entries must be 'user:hash'")?;
+                let hash = PasswordHash::parse(hash)
+                    .with_context(|| format!("dashboard user '{}'", user.trim()))?;
+                Ok((user.trim().to_string(), hash))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(DashboardAuth {
+            tokens: tokens.iter().map(|t| t.trim().to_string()).collect(),
+            users,
+            verified: Default::default(),
+            failures: Default::default(),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.tokens.is_empty() || !self.users.is_empty()
+    }
+
+    /// Whether the `Authorization` header carries a dashboard token or the
+    /// basic credentials of a dashboard user.  Basic credentials that have
+    /// not verified before are refused unchecked once `client` has used up
+    /// its failed attempts.
+    pub fn authenticate(&self, headers: &HeaderMap, client: IpAddr) -> AuthCheck {
+        let granted = |ok: bool| {
+            if ok {
+                AuthCheck::Granted
+            } else {
+                AuthCheck::Denied
+            }
+        };
+        let Some(value) = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return AuthCheck::Denied;
+        };
+        if let Some(presented) = value.strip_prefix("Bearer ") {
+            let presented = presented.trim().as_bytes();
+            return granted(
+                self.tokens
+                    .iter()
+                    .any(|token| constant_time_eq(token.as_bytes(), presented)),
+            );
+        }
+        let Some(encoded) = value.strip_prefix("Basic ") else {
+            return AuthCheck::Denied;
+        };
+        let digest: [u8; 32] = Sha256::digest(encoded.trim().as_bytes()).into();
+        let now = Instant::now();
+        if self.verified.contains(&digest, now) {
+            return AuthCheck::Granted;
+        }
+        if !self.failures.allows(client, now) {
+            return AuthCheck::Throttled;
+        }
+        let ok = STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|raw| String::from_utf8(raw).ok())
+            .and_then(|credentials| {
+                let (user, password) = credentials.split_once(':')?;
+                Some(
+                    self.users
+                        .iter()
+                        .any(|(name, hash)| name == user && hash.verify(password)),
+                )
+            })
+            .unwrap_or(false);
+        if ok {
+            self.verified.insert(digest, now);
+        } else {
+            self.failures.record_failure(client, now);
+        }
+        granted(ok)
+    }
+
+    /// 401 asking for the configured kind of credentials.
+    fn challenge(&self) -> Response {
+        let scheme = if self.users.is_empty() {
+            format!("Bearer realm=\"{}\"", REALM)
+        } else {
+            format!("Basic realm=\"{}\"", REALM)
+        };
+        let mut response = (StatusCode::UNAUTHORIZED, "authentication required").into_response();
+        if let Ok(value) = HeaderValue::from_str(&scheme) {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, value);
+        }
+        response
+    }
+}
+
+/// Middleware guarding every dashboard API route.
+pub async fn require_auth(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+    if !state.auth.is_enabled() || state.admin_tokens.authenticate(headers).is_some() {
+        return next.run(request).await;
+    }
+    // Without connection info (e.g. behind a custom server) every client
+    // shares one failure budget.
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |info| info.0.ip());
+    match state.auth.authenticate(headers, client) {
+        AuthCheck::Granted => next.run(request).await,
+        AuthCheck::Denied => state.auth.challenge(),
+        AuthCheck::Throttled => {
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                "too many failed login attempts",
+            )
+                .into_response();
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                HeaderValue::from(FAILURE_WINDOW.as_secs()),
+            );
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_hashes_round_trip_and_verify() {
+        let hash = PasswordHash::generate("correct horse");
+        let encoded = hash.to_string();
+        assert!(encoded.starts_with("pbkdf2-sha256$100000$"));
+        let parsed = PasswordHash::parse(&encoded).unwrap();
+        assert_eq!(parsed, hash);
+        assert!(parsed.verify("correct horse"));
+        assert!(!parsed.verify("correct horse "));
+
+        assert!(PasswordHash::parse("sha1$1$AAAA$AAAA").is_err());
+        assert!(PasswordHash::parse("pbkdf2-sha256$0$AAAA$AAAA").is_err());
+        assert!(PasswordHash::parse("pbkdf2-sha256$1000$AAAA$AAAA").is_err());
+    }
+
+    #[test]
+    fn accepts_bearer_tokens_and_basic_users() {
+        let hash = PasswordHash::generate("hunter2");
+        let auth =
+            DashboardAuth::new(&["viewer-token".into()], &[format!("alice:{}", hash)]).unwrap();
+        assert!(auth.is_enabled());
+
+        let client = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let check = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::AUTHORIZATION, value.parse().unwrap());
+            auth.authenticate(&headers, client) == AuthCheck::Granted
+        };
+        assert_eq!(
+            auth.authenticate(&HeaderMap::new(), client),
+            AuthCheck::Denied
+        );
+        assert!(check("Bearer viewer-token"));
+        assert!(!check("Bearer viewer-tokem"));
+        let basic = |credentials: &str| format!("Basic {}", STANDARD.encode(credentials));
+        assert!(check(&basic("alice:hunter2")));
+        // Second time is served from the verified cache.
+        assert!(check(&basic("alice:hunter2")));
+        assert!(!check(&basic("alice:hunter3")));
+        assert!(!check(&basic("bob:hunter2")));
+
+        assert!(DashboardAuth::new(&[], &["alice".into()]).is_err());
+        assert!(!DashboardAuth::default().is_enabled());
+    }
+
+    #[test]
+    fn failed_basic_logins_are_throttled_per_client() {
+        // Cheap hash: the limiter, not PBKDF2, is under test.
+        let salt = vec![7u8; SALT_LEN];
+        let hash = PasswordHash {
+            iterations: 1,
+            hash: derive("hunter2", &salt, 1),
+            salt,
+        };
+        let auth = DashboardAuth::new(&[], &[format!("alice:{}", hash)]).unwrap();
+        let attempt = |password: &str, client: [u8; 4]| {
+            let mut headers = HeaderMap::new();
+            let value = format!("Basic {}", STANDARD.encode(format!("alice:{}", password)));
+            headers.insert(header::AUTHORIZATION, value.parse().unwrap());
+            auth.authenticate(&headers, IpAddr::from(client))
+        };
+        let attacker = [10, 0, 0, 1];
+        for _ in 0..MAX_FAILURES {
+            assert_eq!(attempt("guess", attacker), AuthCheck::Denied);
+        }
+        assert_eq!(attempt("guess", attacker), AuthCheck::Throttled);
+        assert_eq!(attempt("hunter2", attacker), AuthCheck::Throttled);
+        // Other clients, and credentials that already verified, still work.
+        assert_eq!(attempt("hunter2", [10, 0, 0, 2]), AuthCheck::Granted);
+        assert_eq!(attempt("hunter2", attacker), AuthCheck::Granted);
+
+        let limiter = FailureLimiter::default();
+        let ip = IpAddr::from(attacker);
+        let start = Instant::now();
+        for _ in 0..MAX_FAILURES {
+            limiter.record_failure(ip, start);
+        }
+        assert!(!limiter.allows(ip, start + Duration::from_secs(59)));
+        assert!(limiter.allows(ip, start + FAILURE_WINDOW));
+    }
+
+    #[test]
+    fn verified_credentials_expire_and_are_capped() {
+        let cache = VerifiedCache::default();
+        let start = Instant::now();
+        cache.insert([1; 32], start);
+        assert!(cache.contains(&[1; 32], start + Duration::from_secs(60)));
+        assert!(!cache.contains(&[1; 32], start + VERIFIED_TTL));
+
+        for i in 0..MAX_VERIFIED {
+            let mut digest = [0u8; 32];
+            digest[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            cache.insert(digest, start);
+        }
+        assert!(cache.digests.lock().unwrap().len() <= MAX_VERIFIED);
+    }
+}
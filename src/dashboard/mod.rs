@@ -1,15 +1,27 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Path, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware,
     response::{Html, IntoResponse},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+use tokio::sync::{mpsc, watch};
+use tower_http::cors::{AllowOrigin, CorsLayer};
 
+use crate::bot::control::ControlRequest;
+use crate::bot::profiles::StrategyProfiles;
+use crate::config::Config;
 use crate::db::Database;
 
+pub mod admin;
+pub mod auth;
+pub mod tls;
+
+pub use admin::AdminTokens;
+pub use auth::DashboardAuth;
+
 #[derive(Clone)]
 pub struct AppState {
     pub db: Database,
@@ -17,19 +29,83 @@ pub struct AppState {
     /// Initial balance is surfaced to the dashboard UI and future `/api/config` endpoints.
     #[allow(dead_code)]
     pub initial_balance: f64,
+    /// Live config; admin patches are published to the engine through it.
+    pub config: watch::Sender<Config>,
+    pub admin_tokens: AdminTokens,
+    /// Strategy profiles as loaded at startup; admin patches must re-derive
+    /// them cleanly before they are applied.
+    pub profiles: StrategyProfiles,
+    /// Credentials required for every route; open when unset.
+    pub auth: DashboardAuth,
+    /// Manual control commands for the engine's main loop.
+    pub control: mpsc::Sender<ControlRequest>,
 }
 
 /// Build the Axum router for the dashboard.
 pub fn router(state: AppState) -> Router {
-    Router::new()
-        .route("/", get(index_handler))
+    let cors = cors_layer(&state.config.borrow().cors_allowed_origins);
+    let state = Arc::new(state);
+    let router = Router::new()
         .route("/api/stats", get(stats_handler))
         .route("/api/positions", get(positions_handler))
+        .route("/api/positions/:id/fills", get(position_fills_handler))
         .route("/api/markets", get(markets_handler))
         .route("/api/score-events", get(score_events_handler))
         .route("/api/balance-history", get(balance_history_handler))
-        .layer(CorsLayer::permissive())
-        .with_state(Arc::new(state))
+        .route("/api/calibrations", get(calibrations_handler))
+        .route("/api/shadow-models", get(shadow_models_handler))
+        .route("/api/drift-alerts", get(drift_alerts_handler))
+        .route(
+            "/api/admin/config",
+            get(admin::get_config_handler).patch(admin::patch_config_handler),
+        )
+        .route("/api/admin/config/audit", get(admin::config_audit_handler))
+        .route(
+            "/api/admin/controls",
+            get(admin::controls_handler).post(admin::control_handler),
+        )
+        .route(
+            "/api/admin/controls/audit",
+            get(admin::controls_audit_handler),
+        )
+        .route(
+            "/api/admin/positions/:id/close",
+            post(admin::close_position_handler),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_auth,
+        ))
+        // The page holds no data; its script authenticates the API calls.
+        .route("/", get(index_handler))
+        .with_state(state);
+    // Added last so preflight requests are answered before authentication.
+    match cors {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
+}
+
+/// CORS for the configured origins; `None` leaves cross-origin requests
+/// to the browser's same-origin policy.
+fn cors_layer(origins: &[String]) -> Option<CorsLayer> {
+    if origins.is_empty() {
+        return None;
+    }
+    if origins.iter().any(|o| o.trim() == "*") {
+        return Some(CorsLayer::permissive());
+    }
+    let origins: Vec<HeaderValue> = origins
+        .iter()
+        .filter_map(|o| HeaderValue::from_str(o.trim().trim_end_matches('/')).ok())
+        .collect();
+    Some(
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods([Method::GET, Method::POST, Method::PATCH])
+            .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+            .allow_credentials(true),
+    )
 }
 
 /// Serve the dashboard HTML page, injecting the dry_run flag.
@@ -63,6 +139,18 @@ async fn positions_handler(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
 }
 
+/// GET /api/positions/:id/fills
+async fn position_fills_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    state
+        .db
+        .list_position_fills(id)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
 /// GET /api/markets
 async fn markets_handler(
     State(state): State<Arc<AppState>>,
@@ -85,6 +173,62 @@ async fn score_events_handler(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
 }
 
+/// GET /api/calibrations — active calibration model and reliability diagram
+/// per level; `sport` is the first segment of the level `key`
+async fn calibrations_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let rows = state
+        .db
+        .load_model_calibrations()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let parse = |json: Option<&str>| {
+        json.and_then(|j| serde_json::from_str::<serde_json::Value>(j).ok())
+            .unwrap_or(serde_json::Value::Null)
+    };
+    let body: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "sport": c.key.split('/').next().unwrap_or(&c.key),
+                "key": c.key,
+                "model_kind": c.model_kind,
+                "samples": c.samples,
+                "logloss_before": c.logloss_before,
+                "logloss_after": c.logloss_after,
+                "brier_before": c.brier_before,
+                "brier_after": c.brier_after,
+                "fitted_at": c.fitted_at,
+                "model": parse(c.params_json.as_deref()),
+                "reliability": parse(c.reliability_json.as_deref()),
+            })
+        })
+        .collect();
+    Ok(Json(body))
+}
+
+/// GET /api/shadow-models — champion vs challenger log loss, Brier and simulated PnL
+async fn shadow_models_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    state
+        .db
+        .list_shadow_evaluations()
+        .map(|rows| Json(crate::bot::shadow::summarize(&rows)))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// GET /api/drift-alerts — open drift alerts first, then recent resolved ones
+async fn drift_alerts_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    state
+        .db
+        .list_drift_alerts(100)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
 /// GET /api/balance-history
 async fn balance_history_handler(
     State(state): State<Arc<AppState>>,
@@ -150,6 +294,9 @@ const DASHBOARD_HTML: &str = r#"<!DOCTYPE html>
   .empty { color: var(--muted); text-align: center; padding: 2rem; font-size: .9rem; }
   .refresh-btn { background: none; border: 1px solid var(--border); color: var(--muted); padding: .3rem .8rem; border-radius: 6px; cursor: pointer; font-size: .8rem; }
   .refresh-btn:hover { border-color: var(--accent); color: var(--accent); }
+  .refresh-btn.danger:hover { border-color: var(--red); color: var(--red); }
+  .controls { padding: .9rem 1.2rem; display: flex; flex-wrap: wrap; gap: .6rem; align-items: center; }
+  .controls input, .controls select { background: var(--bg); border: 1px solid var(--border); color: inherit; padding: .3rem .5rem; border-radius: 6px; font-size: .8rem; }
 </style>
 </head>
 <body>
@@ -173,6 +320,7 @@ const DASHBOARD_HTML: &str = r#"<!DOCTYPE html>
     <div class="stat-card"><div class="label">Avg Last WS Age</div><div class="value" id="s-avg-ws-age">–</div></div>
     <div class="stat-card"><div class="label">Avg Entry WS Age</div><div class="value" id="s-avg-entry-ws-age">–</div></div>
     <div class="stat-card"><div class="label">Avg Closed CLV</div><div class="value" id="s-avg-clv-bps">–</div></div>
+    <div class="stat-card"><div class="label">Correction Trades</div><div class="value" id="s-correction">–</div></div>
     <div class="stat-card"><div class="label">Calib Models</div><div class="value" id="s-cal-models">–</div></div>
     <div class="stat-card"><div class="label">Last Calibration</div><div class="value" id="s-cal-last">–</div></div>
   </div>
@@ -193,6 +341,23 @@ const DASHBOARD_HTML: &str = r#"<!DOCTYPE html>
     </table>
   </div>
 
+  <!-- Manual controls (admin token required) -->
+  <div class="panel">
+    <div class="panel-header">Manual Controls <span style="color:var(--muted);font-size:.8rem;font-weight:400;" id="controls-state">Admin token required</span></div>
+    <div class="controls">
+      <button class="refresh-btn" onclick="control({action:'pause'})">Pause entries</button>
+      <button class="refresh-btn" onclick="control({action:'resume'})">Resume entries</button>
+      <input id="ctl-sport" placeholder="sport, e.g. nba" size="12">
+      <button class="refresh-btn" onclick="sportControl('pause')">Pause sport</button>
+      <button class="refresh-btn" onclick="sportControl('resume')">Resume sport</button>
+      <select id="ctl-kind"><option value="market_id">Market ID</option><option value="team">Team</option></select>
+      <input id="ctl-target" placeholder="market id or team" size="18">
+      <button class="refresh-btn" onclick="blacklistControl('blacklist')">Blacklist</button>
+      <button class="refresh-btn" onclick="blacklistControl('unblacklist')">Unblacklist</button>
+      <button class="refresh-btn danger" style="margin-left:auto" onclick="control({action:'flatten'})">Flatten all</button>
+    </div>
+  </div>
+
   <!-- Balance chart -->
   <div class="panel">
     <div class="panel-header">Balance History <button class="refresh-btn" onclick="loadAll()">↻ Refresh</button></div>
@@ -243,8 +408,32 @@ const timeAgo = ts => {
   return new Date(ts).toLocaleTimeString();
 };
 
+// With DASHBOARD_TOKENS the API answers 401 with a Bearer challenge and the
+// page asks for a token; basic-auth users are prompted by the browser.
+function dashboardToken(force) {
+  let token = sessionStorage.getItem('dashboardToken');
+  if (!token || force) {
+    token = prompt('Dashboard token');
+    if (token) sessionStorage.setItem('dashboardToken', token);
+  }
+  return token;
+}
+
+async function apiFetch(path) {
+  const request = token => fetch(path, token ? { headers: { 'Authorization': 'Bearer ' + token } } : {});
+  let token = sessionStorage.getItem('dashboardToken');
+  let r = await request(token);
+  if (r.status === 401 && (r.headers.get('WWW-Authenticate') || '').startsWith('Bearer')) {
+    // Panels load together; only the first to fail asks for a token.
+    const stored = sessionStorage.getItem('dashboardToken');
+    token = stored && stored !== token ? stored : dashboardToken(true);
+    if (token) r = await request(token);
+  }
+  return r;
+}
+
 async function loadStats() {
-  const r = await fetch('/api/stats');
+  const r = await apiFetch('/api/stats');
   if (!r.ok) return;
   const s = await r.json();
   document.getElementById('s-balance').textContent = fmt.format(s.current_balance);
@@ -263,6 +452,10 @@ async function loadStats() {
   const clvEl = document.getElementById('s-avg-clv-bps');
   clvEl.textContent = bps(s.avg_closed_clv_bps || 0);
   clvEl.className = 'value ' + ((s.avg_closed_clv_bps || 0) >= 0 ? 'pos' : 'neg');
+  const corrEl = document.getElementById('s-correction');
+  const corrPnl = s.correction_induced_pnl || 0;
+  corrEl.textContent = (s.correction_induced_trades || 0) + (s.correction_induced_trades ? ' / ' + (corrPnl >= 0 ? '+' : '') + fmt.format(corrPnl) : '');
+  corrEl.className = 'value ' + (corrPnl >= 0 ? '' : 'neg');
   document.getElementById('s-cal-models').textContent = s.calibration_models_active ?? 0;
   document.getElementById('s-cal-last').textContent = s.calibration_last_fit_at ? timeAgo(s.calibration_last_fit_at) : '–';
 
@@ -295,7 +488,7 @@ async function loadStats() {
 }
 
 async function loadPositions() {
-  const r = await fetch('/api/positions');
+  const r = await apiFetch('/api/positions');
   if (!r.ok) return;
   const positions = await r.json();
   const tbody = document.getElementById('positions-tbody');
@@ -303,8 +496,8 @@ async function loadPositions() {
   tbody.innerHTML = positions.slice(0,20).map(p => {
     const pnl = p.pnl != null ? (p.pnl >= 0 ? '+' : '') + fmt.format(p.pnl) : '–';
     const pnlClass = p.pnl != null ? (p.pnl >= 0 ? 'pos' : 'neg') : '';
-    const statusClass = { open:'open', closed_profit:'profit', closed_stop_loss:'stoploss', closed_loss:'loss', closed_feed_health:'stoploss', closed_time_exit:'stoploss' }[p.status] || 'open';
-    const statusLabel = { open:'Open', closed_profit:'Profit', closed_stop_loss:'Stop Loss', closed_loss:'Loss', closed_feed_health:'Feed Flatten', closed_time_exit:'Time Exit' }[p.status] || p.status;
+    const statusClass = { open:'open', closed_profit:'profit', closed_stop_loss:'stoploss', closed_loss:'loss', closed_feed_health:'stoploss', closed_time_exit:'stoploss', closed_score_correction:'stoploss', closed_settled:'profit', closed_state_change:'stoploss', closed_model_exit:'profit', closed_trailing_stop:'profit', closed_breakeven:'stoploss', closed_maker:'profit', closed_manual:'stoploss' }[p.status] || 'open';
+    const statusLabel = { open:'Open', closed_profit:'Profit', closed_stop_loss:'Stop Loss', closed_loss:'Loss', closed_feed_health:'Feed Flatten', closed_time_exit:'Time Exit', closed_score_correction:'Score Correction', closed_settled:'Settled', closed_state_change:'State Change', closed_model_exit:'Model Exit', closed_trailing_stop:'Trailing Stop', closed_breakeven:'Break-even', closed_maker:'Maker Exit', closed_manual:'Manual Close' }[p.status] || p.status;
     const label = p.event_name || p.market_id.slice(0,12)+'…';
     const marketCell = p.market_slug
       ? `<a href="https://polymarket.com/event/${p.market_slug}" target="_blank" rel="noopener" style="color:var(--accent);text-decoration:none;" title="${p.market_id}">${label}</a>`
@@ -315,13 +508,13 @@ async function loadPositions() {
       <td>${fmt.format(p.size_usd)}</td>
       <td>${(p.entry_price*100).toFixed(1)}¢</td>
       <td class="${pnlClass}">${pnl}</td>
-      <td><span class="pill ${statusClass}">${statusLabel}</span></td>
+      <td><span class="pill ${statusClass}">${statusLabel}</span>${p.status === 'open' && p.id != null ? ` <button class="refresh-btn danger" onclick="closePosition(${p.id})">Close</button>` : ''}</td>
     </tr>`;
   }).join('');
 }
 
 async function loadScoreEvents() {
-  const r = await fetch('/api/score-events');
+  const r = await apiFetch('/api/score-events');
   if (!r.ok) return;
   const events = await r.json();
   const tbody = document.getElementById('events-tbody');
@@ -335,7 +528,7 @@ async function loadScoreEvents() {
 }
 
 async function loadMarkets() {
-  const r = await fetch('/api/markets');
+  const r = await apiFetch('/api/markets');
   if (!r.ok) return;
   const markets = await r.json();
   const tbody = document.getElementById('markets-tbody');
@@ -372,7 +565,7 @@ async function loadMarkets() {
 
 let chartCtx, chartData = { labels: [], datasets: [] };
 async function loadBalanceHistory() {
-  const r = await fetch('/api/balance-history');
+  const r = await apiFetch('/api/balance-history');
   if (!r.ok) return;
   const history = await r.json();
   if (!history.length) return;
@@ -432,7 +625,7 @@ function drawChart(labels, data) {
 
 async function loadMode() {
   // Detect mode from stats (if balance == initial default, likely dry-run)
-  const r = await fetch('/api/stats');
+  const r = await apiFetch('/api/stats');
   if (!r.ok) return;
   const s = await r.json();
   const badge = document.getElementById('mode-badge');
@@ -443,6 +636,67 @@ async function loadMode() {
   badge.className = 'badge ' + (isDryRun ? 'dryrun' : 'live');
 }
 
+function adminToken(force) {
+  let token = sessionStorage.getItem('adminToken');
+  if (!token || force) {
+    token = prompt('Admin token');
+    if (token) sessionStorage.setItem('adminToken', token);
+  }
+  return token;
+}
+
+async function adminFetch(path, body) {
+  const request = token => fetch(path, {
+    method: body ? 'POST' : 'GET',
+    headers: { 'Authorization': 'Bearer ' + token, 'Content-Type': 'application/json' },
+    body: body ? JSON.stringify(body) : undefined,
+  });
+  let token = adminToken(false);
+  if (!token) return null;
+  let r = await request(token);
+  if (r.status === 401) {
+    token = adminToken(true);
+    if (!token) return null;
+    r = await request(token);
+  }
+  const text = await r.text();
+  if (!r.ok) { alert(text); return null; }
+  return JSON.parse(text);
+}
+
+function showControls(outcome) {
+  if (!outcome) return;
+  const c = outcome.controls;
+  const parts = [c.entries_paused ? 'Entries PAUSED' : 'Entries active'];
+  if (c.paused_sports.length) parts.push('paused: ' + c.paused_sports.join(', '));
+  if (c.blacklisted_markets.length) parts.push('markets: ' + c.blacklisted_markets.join(', '));
+  if (c.blacklisted_teams.length) parts.push('teams: ' + c.blacklisted_teams.join(', '));
+  document.getElementById('controls-state').textContent = parts.join(' · ');
+  if (outcome.message && !['ok', 'applied'].includes(outcome.message)) alert(outcome.message);
+}
+
+async function control(body) {
+  if (body.action === 'flatten' && !confirm('Pause entries and close ALL open positions at market?')) return;
+  showControls(await adminFetch('/api/admin/controls', body));
+  loadAll();
+}
+
+function sportControl(action) {
+  const sport = document.getElementById('ctl-sport').value.trim();
+  if (sport) control({ action, sport });
+}
+
+function blacklistControl(action) {
+  const target = document.getElementById('ctl-target').value.trim();
+  if (target) control({ action, [document.getElementById('ctl-kind').value]: target });
+}
+
+async function closePosition(id) {
+  if (!confirm(`Close position ${id} at market?`)) return;
+  showControls(await adminFetch(`/api/admin/positions/${id}/close`, {}));
+  loadPositions();
+}
+
 async function loadAll() {
   await Promise.all([loadStats(), loadPositions(), loadScoreEvents(), loadMarkets(), loadBalanceHistory()]);
   document.getElementById('last-updated').textContent = 'Updated ' + new Date().toLocaleTimeString();
@@ -450,6 +704,7 @@ async function loadAll() {
 
 // Auto-refresh every 5 seconds
 loadAll();
+if (sessionStorage.getItem('adminToken')) adminFetch('/api/admin/controls').then(showControls);
 setInterval(loadAll, 5000);
 
 // Set mode badge from server-injected data attribute
@@ -462,3 +717,48 @@ document.addEventListener('DOMContentLoaded', () => {
 </script>
 </body>
 </html>"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::net::SocketAddr;
+
+    #[tokio::test]
+    async fn page_is_served_openly_while_the_api_wants_a_token() {
+        let config = Config::parse_from(["livesports-bot", "--dry-run"]);
+        let (control, _control_rx) = mpsc::channel(1);
+        let state = AppState {
+            db: Database::open(":memory:").unwrap(),
+            dry_run: true,
+            initial_balance: config.initial_balance,
+            admin_tokens: AdminTokens::default(),
+            profiles: StrategyProfiles::global(&config),
+            auth: DashboardAuth::new(&["viewer-token-0123456789".into()], &[]).unwrap(),
+            config: watch::channel(config).0,
+            control,
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let app = router(state).into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let page = client.get(format!("{}/", base)).send().await.unwrap();
+        assert_eq!(page.status(), StatusCode::OK);
+        assert!(page.text().await.unwrap().contains("apiFetch"));
+
+        let stats = format!("{}/api/stats", base);
+        let denied = client.get(&stats).send().await.unwrap();
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+        let challenge = denied.headers()[header::WWW_AUTHENTICATE].to_str().unwrap();
+        assert!(challenge.starts_with("Bearer"));
+        let granted = client
+            .get(&stats)
+            .bearer_auth("viewer-token-0123456789")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(granted.status(), StatusCode::OK);
+    }
+}
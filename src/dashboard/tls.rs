@@ -0,0 +1,85 @@
+//! HTTPS for the dashboard.
+//!
+//! `axum::serve` only speaks plain HTTP, so with `TLS_CERT_PATH` and
+//! `TLS_KEY_PATH` set the listener is driven here instead: each connection
+//! is handshaken with rustls and then served by hyper's HTTP/1 server.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use axum::extract::ConnectInfo;
+use axum::{Extension, Router};
+use hyper::server::conn::http1;
+use hyper_util::rt::TokioIo;
+use hyper_util::service::TowerToHyperService;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, warn};
+
+/// Clients that have not finished the handshake by then are dropped, so
+/// idle connections cannot pile up.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Server config from a PEM certificate chain and private key.
+pub fn load_server_config(cert_path: &str, key_path: &str) -> anyhow::Result<Arc<ServerConfig>> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("Failed to read TLS certificates from {}", cert_path))?;
+    if certs.is_empty() {
+        anyhow::bail!("no certificates found in {}", cert_path);
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .with_context(|| format!("Failed to read TLS private key from {}", key_path))?;
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("TLS certificate and key do not match")?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+/// Serve `app` over TLS until the process exits.
+pub async fn serve(listener: TcpListener, app: Router, config: Arc<ServerConfig>) {
+    let acceptor = TlsAcceptor::from(config);
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                // Usually out of file descriptors; back off like axum::serve.
+                warn!("Dashboard accept failed: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        // Same client address as `axum::serve` provides, for auth limits.
+        let service = TowerToHyperService::new(app.clone().layer(Extension(ConnectInfo(peer))));
+        tokio::spawn(async move {
+            let stream =
+                match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => stream,
+                    Ok(Err(e)) => {
+                        debug!("TLS handshake with {} failed: {}", peer, e);
+                        return;
+                    }
+                    Err(_) => {
+                        debug!("TLS handshake with {} timed out", peer);
+                        return;
+                    }
+                };
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .with_upgrades()
+                .await
+            {
+                debug!("Dashboard connection from {} ended: {}", peer, e);
+            }
+        });
+    }
+}
@@ -61,6 +61,62 @@ impl Database {
         ensure_column(&conn, "markets", "end_date", "TEXT")?;
         ensure_column(&conn, "markets", "liquidity", "REAL")?;
         ensure_column(&conn, "positions", "market_slug", "TEXT")?;
+        ensure_column(&conn, "positions", "fixture_id", "TEXT")?;
+        ensure_column(&conn, "positions", "entry_score_event_id", "INTEGER")?;
+        ensure_column(&conn, "positions", "yes_is_home", "INTEGER")?;
+        ensure_column(
+            &conn,
+            "positions",
+            "correction_induced",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        ensure_column(
+            &conn,
+            "positions",
+            "exit_policy",
+            "TEXT NOT NULL DEFAULT 'fixed'",
+        )?;
+        ensure_column(&conn, "positions", "high_water_price", "REAL")?;
+        ensure_column(
+            &conn,
+            "positions",
+            "realized_pnl",
+            "REAL NOT NULL DEFAULT 0.0",
+        )?;
+        ensure_column(
+            &conn,
+            "positions",
+            "partial_exit_count",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        ensure_column(
+            &conn,
+            "positions",
+            "add_count",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        ensure_column(
+            &conn,
+            "positions",
+            "strategy",
+            "TEXT NOT NULL DEFAULT 'directional'",
+        )?;
+        ensure_column(&conn, "positions", "group_id", "INTEGER")?;
+        ensure_column(&conn, "positions", "model_version", "TEXT")?;
+        // Calibrations are keyed per hierarchy level (`soccer/epl/late`),
+        // not per sport.
+        rename_column(&conn, "model_calibrations", "sport", "level_key")?;
+        ensure_column(
+            &conn,
+            "model_calibrations",
+            "model_kind",
+            "TEXT NOT NULL DEFAULT 'platt'",
+        )?;
+        ensure_column(&conn, "model_calibrations", "params_json", "TEXT")?;
+        ensure_column(&conn, "model_calibrations", "reliability_json", "TEXT")?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_positions_fixture ON positions(fixture_id);",
+        )?;
         Ok(())
     }
 
@@ -119,8 +175,12 @@ impl Database {
                 entry_ws_age_ms, estimated_round_trip_cost_bps,
                 stop_loss_price, take_profit_price, status,
                 opened_at, dry_run, ws_used_count, rest_fallback_count, last_ws_age_ms,
-                sport, league, event_name, market_slug
-             ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15,?16,?17,?18,?19,?20,?21,?22,?23)",
+                sport, league, event_name, market_slug,
+                fixture_id, entry_score_event_id, yes_is_home, correction_induced,
+                exit_policy, high_water_price, realized_pnl, partial_exit_count,
+                strategy, group_id, model_version
+             ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15,?16,?17,?18,?19,?20,?21,?22,?23,
+                       ?24,?25,?26,?27,?28,?29,?30,?31,?32,?33,?34)",
             params![
                 pos.market_id,
                 pos.asset_id,
@@ -145,17 +205,225 @@ impl Database {
                 pos.league,
                 pos.event_name,
                 pos.market_slug,
+                pos.fixture_id,
+                pos.entry_score_event_id,
+                pos.yes_is_home,
+                pos.correction_induced,
+                pos.exit_policy,
+                pos.high_water_price,
+                pos.realized_pnl,
+                pos.partial_exit_count,
+                pos.strategy,
+                pos.group_id,
+                pos.model_version,
             ],
         )?;
-        Ok(conn.last_insert_rowid())
+        let id = conn.last_insert_rowid();
+        if pos.entry_price > 0.0 {
+            insert_fill(
+                &conn,
+                id,
+                "buy",
+                pos.size_usd / pos.entry_price,
+                pos.entry_price,
+                pos.size_usd,
+                0.0,
+                "entry",
+            )?;
+        }
+        Ok(id)
     }
 
-    /// Update position status and close fields
+    /// Close the remainder of a position.
+    ///
+    /// `exit_price` and `pnl` describe the final leg only; the stored
+    /// `exit_price` becomes the share-weighted average of all sell fills and
+    /// `pnl` the total across partial exits and the final leg.
     pub fn close_position(&self, id: i64, status: &str, exit_price: f64, pnl: f64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let (size_usd, entry_price, realized_pnl): (f64, f64, f64) = tx.query_row(
+            "SELECT size_usd, entry_price, realized_pnl FROM positions WHERE id = ?1",
+            params![id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )?;
+        if entry_price > 0.0 {
+            insert_fill(
+                &tx,
+                id,
+                "sell",
+                size_usd / entry_price,
+                exit_price,
+                size_usd,
+                pnl,
+                status,
+            )?;
+        }
+        let (sold_shares, sold_value): (f64, f64) = tx.query_row(
+            "SELECT COALESCE(SUM(shares),0.0), COALESCE(SUM(shares * price),0.0)
+             FROM position_fills WHERE position_id = ?1 AND side = 'sell'",
+            params![id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        let avg_exit_price = if sold_shares > 0.0 {
+            sold_value / sold_shares
+        } else {
+            exit_price
+        };
+        let total_pnl = realized_pnl + pnl;
+        tx.execute(
+            "UPDATE positions SET status=?1, exit_price=?2, pnl=?3, realized_pnl=?3, closed_at=?4
+             WHERE id=?5",
+            params![status, avg_exit_price, total_pnl, Utc::now(), id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Sell part of an open position.
+    ///
+    /// `size_usd` is the cost basis released (remaining `size_usd` shrinks by
+    /// it); `pnl` is the net P&L of the sold slice.
+    pub fn partial_close_position(
+        &self,
+        id: i64,
+        size_usd: f64,
+        exit_price: f64,
+        pnl: f64,
+        reason: &str,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let entry_price: f64 = tx.query_row(
+            "SELECT entry_price FROM positions WHERE id = ?1",
+            params![id],
+            |r| r.get(0),
+        )?;
+        if entry_price > 0.0 {
+            insert_fill(
+                &tx,
+                id,
+                "sell",
+                size_usd / entry_price,
+                exit_price,
+                size_usd,
+                pnl,
+                reason,
+            )?;
+        }
+        tx.execute(
+            "UPDATE positions
+             SET size_usd = size_usd - ?1,
+                 realized_pnl = realized_pnl + ?2,
+                 partial_exit_count = partial_exit_count + 1
+             WHERE id = ?3",
+            params![size_usd, pnl, id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Add to an open position (pyramiding, maker bid fills).
+    ///
+    /// Records a buy fill of `size_usd` at `price` and moves the position to
+    /// the averaged entry and the `(stop_loss, take_profit)` levels.
+    pub fn add_to_position(
+        &self,
+        id: i64,
+        size_usd: f64,
+        price: f64,
+        avg_entry_price: f64,
+        (stop_loss_price, take_profit_price): (f64, f64),
+        reason: &str,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        if price > 0.0 {
+            insert_fill(
+                &tx,
+                id,
+                "buy",
+                size_usd / price,
+                price,
+                size_usd,
+                0.0,
+                reason,
+            )?;
+        }
+        tx.execute(
+            "UPDATE positions
+             SET size_usd = size_usd + ?1,
+                 entry_price = ?2,
+                 stop_loss_price = ?3,
+                 take_profit_price = ?4,
+                 high_water_price = MAX(COALESCE(high_water_price, ?5), ?5),
+                 add_count = add_count + 1
+             WHERE id = ?6 AND status = 'open'",
+            params![
+                size_usd,
+                avg_entry_price,
+                stop_loss_price,
+                take_profit_price,
+                price,
+                id
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Link a position to a hedge group.
+    pub fn set_position_group(&self, id: i64, group_id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE positions SET group_id = ?1 WHERE id = ?2",
+            params![group_id, id],
+        )?;
+        Ok(())
+    }
+
+    /// All fills (entry, partial exits, final exit) for a position, oldest first.
+    pub fn list_position_fills(&self, position_id: i64) -> Result<Vec<PositionFill>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, position_id, side, shares, price, size_usd, pnl, reason, filled_at
+             FROM position_fills WHERE position_id = ?1 ORDER BY id ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![position_id], |row| {
+                Ok(PositionFill {
+                    id: row.get(0)?,
+                    position_id: row.get(1)?,
+                    side: row.get(2)?,
+                    shares: row.get(3)?,
+                    price: row.get(4)?,
+                    size_usd: row.get(5)?,
+                    pnl: row.get(6)?,
+                    reason: row.get(7)?,
+                    filled_at: row.get(8)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Flag a position as affected by a score correction of its triggering event.
+    pub fn mark_position_correction_induced(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE positions SET correction_induced = 1 WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Raise the high-water mark of an open position.
+    pub fn update_position_high_water(&self, id: i64, price: f64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "UPDATE positions SET status=?1, exit_price=?2, pnl=?3, closed_at=?4 WHERE id=?5",
-            params![status, exit_price, pnl, Utc::now(), id],
+            "UPDATE positions SET high_water_price = ?1
+             WHERE id = ?2 AND (high_water_price IS NULL OR high_water_price < ?1)",
+            params![price, id],
         )?;
         Ok(())
     }
@@ -188,16 +456,10 @@ impl Database {
     /// List open positions
     pub fn list_open_positions(&self) -> Result<Vec<Position>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, market_id, asset_id, outcome, side, size_usd, entry_price,
-                    entry_price_source, entry_model_prob_raw, entry_model_prob,
-                    entry_ws_age_ms, estimated_round_trip_cost_bps,
-                    stop_loss_price, take_profit_price, status,
-                    opened_at, closed_at, exit_price, pnl, dry_run,
-                    ws_used_count, rest_fallback_count, last_ws_age_ms,
-                    sport, league, event_name, market_slug
-             FROM positions WHERE status='open' ORDER BY opened_at DESC",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM positions WHERE status='open' ORDER BY opened_at DESC",
+            POSITION_COLUMNS
+        ))?;
         let positions = stmt
             .query_map([], map_position)?
             .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -207,16 +469,10 @@ impl Database {
     /// List all positions (paginated)
     pub fn list_positions(&self, limit: i64, offset: i64) -> Result<Vec<Position>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, market_id, asset_id, outcome, side, size_usd, entry_price,
-                    entry_price_source, entry_model_prob_raw, entry_model_prob,
-                    entry_ws_age_ms, estimated_round_trip_cost_bps,
-                    stop_loss_price, take_profit_price, status,
-                    opened_at, closed_at, exit_price, pnl, dry_run,
-                    ws_used_count, rest_fallback_count, last_ws_age_ms,
-                    sport, league, event_name, market_slug
-             FROM positions ORDER BY opened_at DESC LIMIT ?1 OFFSET ?2",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM positions ORDER BY opened_at DESC LIMIT ?1 OFFSET ?2",
+            POSITION_COLUMNS
+        ))?;
         let positions = stmt
             .query_map(params![limit, offset], map_position)?
             .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -319,6 +575,20 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// Fetch a single score event by row ID.
+    pub fn get_score_event(&self, id: i64) -> Result<Option<ScoreEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, event_id, sport, league, home_team, away_team,
+                    source_provider, provider_consensus_count,
+                    prev_home_score, prev_away_score, home_score, away_score,
+                    minute, event_type, detected_at
+             FROM score_events WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], map_score_event)?;
+        Ok(rows.next().transpose()?)
+    }
+
     /// List recent score events
     pub fn list_recent_score_events(&self, limit: i64) -> Result<Vec<ScoreEvent>> {
         let conn = self.conn.lock().unwrap();
@@ -374,10 +644,14 @@ impl Database {
                 r.get(0)
             })
             .unwrap_or(0);
+        // Open positions contribute P&L already booked by partial exits.
         let total_pnl: f64 = conn
-            .query_row("SELECT COALESCE(SUM(pnl),0) FROM positions", [], |r| {
-                r.get(0)
-            })
+            .query_row(
+                "SELECT COALESCE(SUM(CASE WHEN status = 'open' THEN realized_pnl ELSE pnl END),0)
+                 FROM positions",
+                [],
+                |r| r.get(0),
+            )
             .unwrap_or(0.0);
         let open_positions: i64 = conn
             .query_row(
@@ -449,6 +723,16 @@ impl Database {
                 |r| r.get(0),
             )
             .unwrap_or(0.0);
+        let (correction_induced_trades, correction_induced_pnl): (i64, f64) = conn
+            .query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(CASE WHEN status = 'open' THEN realized_pnl ELSE pnl END),0.0)
+                 FROM positions
+                 WHERE correction_induced = 1",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap_or((0, 0.0));
         let total_marks = ws_marks_total + rest_fallback_total;
         let rest_fallback_rate = if total_marks > 0 {
             rest_fallback_total as f64 / total_marks as f64
@@ -508,6 +792,26 @@ impl Database {
                 })
             })?
             .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut model_version_stmt = conn.prepare(
+            "SELECT COALESCE(model_version, 'unversioned') as model_version,
+                    COUNT(*) as n,
+                    COALESCE(SUM(pnl),0.0) as pnl,
+                    COALESCE(AVG(CASE WHEN pnl > 0 THEN 1.0 ELSE 0.0 END),0.0) as win_rate
+             FROM positions
+             WHERE status != 'open' AND strategy IN ('directional', 'hedge')
+             GROUP BY COALESCE(model_version, 'unversioned')
+             ORDER BY MIN(opened_at) ASC",
+        )?;
+        let model_version_stats = model_version_stmt
+            .query_map([], |row| {
+                Ok(ModelVersionStats {
+                    model_version: row.get(0)?,
+                    trades: row.get(1)?,
+                    pnl: row.get(2)?,
+                    win_rate: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
 
         Ok(Stats {
             total_trades,
@@ -524,8 +828,11 @@ impl Database {
             calibration_models_active,
             calibration_last_fit_at,
             avg_closed_clv_bps,
+            correction_induced_trades,
+            correction_induced_pnl,
             sport_quote_stats,
             sport_clv_stats,
+            model_version_stats,
         })
     }
 
@@ -547,17 +854,22 @@ impl Database {
     }
 
     /// Calibration candidates from closed trades with recorded raw model probabilities.
+    ///
+    /// One row per position regardless of how many legs it was exited in.
     pub fn list_calibration_candidates(&self) -> Result<Vec<CalibrationCandidate>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT COALESCE(sport, 'unknown') as sport,
-                    market_id,
-                    outcome,
-                    entry_model_prob_raw
-             FROM positions
-             WHERE status != 'open'
-               AND entry_model_prob_raw IS NOT NULL
-               AND outcome IN ('YES', 'NO')",
+            "SELECT COALESCE(p.sport, 'unknown') as sport,
+                    p.market_id,
+                    p.outcome,
+                    p.entry_model_prob_raw,
+                    p.league,
+                    se.minute
+             FROM positions p
+             LEFT JOIN score_events se ON se.id = p.entry_score_event_id
+             WHERE p.status != 'open'
+               AND p.entry_model_prob_raw IS NOT NULL
+               AND p.outcome IN ('YES', 'NO')",
         )?;
         let rows = stmt
             .query_map([], |row| {
@@ -566,6 +878,8 @@ impl Database {
                     market_id: row.get(1)?,
                     outcome: row.get(2)?,
                     model_prob_raw: row.get(3)?,
+                    league: row.get(4)?,
+                    minute: row.get(5)?,
                 })
             })?
             .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -575,14 +889,16 @@ impl Database {
     pub fn load_model_calibrations(&self) -> Result<Vec<ModelCalibration>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT sport, a, b, samples, logloss_before, logloss_after,
-                    brier_before, brier_after, fitted_at
-             FROM model_calibrations",
+            "SELECT level_key, a, b, samples, logloss_before, logloss_after,
+                    brier_before, brier_after, fitted_at, model_kind,
+                    params_json, reliability_json
+             FROM model_calibrations
+             ORDER BY level_key",
         )?;
         let rows = stmt
             .query_map([], |row| {
                 Ok(ModelCalibration {
-                    sport: row.get(0)?,
+                    key: row.get(0)?,
                     a: row.get(1)?,
                     b: row.get(2)?,
                     samples: row.get(3)?,
@@ -591,20 +907,36 @@ impl Database {
                     brier_before: row.get(6)?,
                     brier_after: row.get(7)?,
                     fitted_at: row.get(8)?,
+                    model_kind: row.get(9)?,
+                    params_json: row.get(10)?,
+                    reliability_json: row.get(11)?,
                 })
             })?
             .collect::<rusqlite::Result<Vec<_>>>()?;
         Ok(rows)
     }
 
+    /// Archive a calibration set under its content version (see
+    /// `calibration_set_version`); positions reference it in `model_version`.
+    pub fn insert_calibration_version(&self, version: &str, models_json: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO calibration_versions (version, models_json, created_at)
+             VALUES (?1,?2,?3)",
+            params![version, models_json, Utc::now()],
+        )?;
+        Ok(())
+    }
+
     pub fn upsert_model_calibration(&self, model: &ModelCalibration) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
             "INSERT INTO model_calibrations (
-                sport, a, b, samples, logloss_before, logloss_after,
-                brier_before, brier_after, fitted_at
-             ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9)
-             ON CONFLICT(sport) DO UPDATE SET
+                level_key, a, b, samples, logloss_before, logloss_after,
+                brier_before, brier_after, fitted_at, model_kind,
+                params_json, reliability_json
+             ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12)
+             ON CONFLICT(level_key) DO UPDATE SET
                 a=excluded.a,
                 b=excluded.b,
                 samples=excluded.samples,
@@ -612,9 +944,12 @@ impl Database {
                 logloss_after=excluded.logloss_after,
                 brier_before=excluded.brier_before,
                 brier_after=excluded.brier_after,
-                fitted_at=excluded.fitted_at",
+                fitted_at=excluded.fitted_at,
+                model_kind=excluded.model_kind,
+                params_json=excluded.params_json,
+                reliability_json=excluded.reliability_json",
             params![
-                model.sport,
+                model.key,
                 model.a,
                 model.b,
                 model.samples,
@@ -623,14 +958,580 @@ impl Database {
                 model.brier_before,
                 model.brier_after,
                 model.fitted_at,
+                model.model_kind,
+                model.params_json,
+                model.reliability_json,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Record the raw model probability seen on a score event.
+    pub fn insert_model_observation(&self, obs: &ModelObservation) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO model_observations (
+                score_event_id, fixture_id, sport, league, minute,
+                home_score, away_score, model_prob_home_raw, market_id,
+                yes_is_home, observed_at, home_won, label_source, labelled_at
+             ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14)",
+            params![
+                obs.score_event_id,
+                obs.fixture_id,
+                obs.sport,
+                obs.league,
+                obs.minute,
+                obs.home_score,
+                obs.away_score,
+                obs.model_prob_home_raw,
+                obs.market_id,
+                obs.yes_is_home,
+                obs.observed_at,
+                obs.home_won,
+                obs.label_source,
+                obs.labelled_at,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Link an observation to the fixture's winner market.
+    pub fn set_observation_market(
+        &self,
+        id: i64,
+        market_id: &str,
+        yes_is_home: bool,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE model_observations SET market_id = ?2, yes_is_home = ?3 WHERE id = ?1",
+            params![id, market_id, yes_is_home],
+        )?;
+        Ok(())
+    }
+
+    /// Label a fixture's unlabelled observations from its final score.
+    pub fn label_fixture_observations(&self, fixture_id: &str, home_won: bool) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let n = conn.execute(
+            "UPDATE model_observations
+             SET home_won = ?2, label_source = 'final_score', labelled_at = ?3
+             WHERE fixture_id = ?1 AND home_won IS NULL",
+            params![fixture_id, home_won, Utc::now()],
+        )?;
+        Ok(n)
+    }
+
+    /// Label unlabelled observations linked to a resolved winner market.
+    pub fn label_market_observations(&self, market_id: &str, home_won: bool) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let n = conn.execute(
+            "UPDATE model_observations
+             SET home_won = ?2, label_source = 'market_resolution', labelled_at = ?3
+             WHERE market_id = ?1 AND home_won IS NULL",
+            params![market_id, home_won, Utc::now()],
+        )?;
+        Ok(n)
+    }
+
+    /// Detach observations from a market whose resolution cannot label them.
+    pub fn unlink_market_observations(&self, market_id: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let n = conn.execute(
+            "UPDATE model_observations SET market_id = NULL, yes_is_home = NULL
+             WHERE market_id = ?1 AND home_won IS NULL",
+            params![market_id],
+        )?;
+        Ok(n)
+    }
+
+    /// Distinct `(market_id, yes_is_home, sport)` of unlabelled observations
+    /// made before `observed_before`.
+    pub fn list_unlabelled_observation_markets(
+        &self,
+        observed_before: DateTime<Utc>,
+    ) -> Result<Vec<(String, bool, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT market_id, yes_is_home, sport
+             FROM model_observations
+             WHERE home_won IS NULL
+               AND market_id IS NOT NULL
+               AND yes_is_home IS NOT NULL
+               AND observed_at < ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![observed_before], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Observations with a known fixture result, optionally only those
+    /// observed at or after `since`.
+    pub fn list_labelled_observations(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ModelObservation>> {
+        let conn = self.conn.lock().unwrap();
+        let mut sql = String::from(
+            "SELECT id, score_event_id, fixture_id, sport, league, minute,
+                    home_score, away_score, model_prob_home_raw, market_id,
+                    yes_is_home, observed_at, home_won, label_source, labelled_at
+             FROM model_observations
+             WHERE home_won IS NOT NULL",
+        );
+        if since.is_some() {
+            sql.push_str(" AND observed_at >= ?1");
+        }
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(since), |row| {
+                Ok(ModelObservation {
+                    id: row.get(0)?,
+                    score_event_id: row.get(1)?,
+                    fixture_id: row.get(2)?,
+                    sport: row.get(3)?,
+                    league: row.get(4)?,
+                    minute: row.get(5)?,
+                    home_score: row.get(6)?,
+                    away_score: row.get(7)?,
+                    model_prob_home_raw: row.get(8)?,
+                    market_id: row.get(9)?,
+                    yes_is_home: row.get(10)?,
+                    observed_at: row.get(11)?,
+                    home_won: row.get(12)?,
+                    label_source: row.get(13)?,
+                    labelled_at: row.get(14)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Archive a finished fixture with its final score and score timeline.
+    ///
+    /// The timeline is copied out of `score_events`, which is pruned, while
+    /// `fixtures` is kept indefinitely as ground truth for model fitting.
+    /// Returns the number of timeline entries archived.
+    pub fn archive_fixture(&self, game: &LiveGame) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT minute, home_score, away_score, event_type, detected_at
+             FROM score_events
+             WHERE event_id = ?1
+             ORDER BY detected_at ASC, id ASC",
+        )?;
+        let timeline = stmt
+            .query_map(params![game.event_id], |row| {
+                Ok(ScoreTimelineEntry {
+                    minute: row.get(0)?,
+                    home_score: row.get(1)?,
+                    away_score: row.get(2)?,
+                    event_type: row.get(3)?,
+                    detected_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        conn.execute(
+            "INSERT INTO fixtures (
+                event_id, sport, league, home_team, away_team, home_score,
+                away_score, status, start_time, finished_at, timeline_json
+             ) VALUES (?1,?2,?3,?4,?5,?6,?7,'finished',?8,?9,?10)
+             ON CONFLICT(event_id) DO UPDATE SET
+                home_score=excluded.home_score,
+                away_score=excluded.away_score,
+                status=excluded.status,
+                finished_at=excluded.finished_at,
+                timeline_json=excluded.timeline_json",
+            params![
+                game.event_id,
+                game.sport,
+                game.league,
+                game.home_team,
+                game.away_team,
+                game.home_score,
+                game.away_score,
+                game.start_time,
+                Utc::now(),
+                serde_json::to_string(&timeline)?,
+            ],
+        )?;
+        Ok(timeline.len())
+    }
+
+    pub fn insert_shadow_evaluations(&self, rows: &[ShadowEvaluation]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for row in rows {
+            tx.execute(
+                "INSERT INTO shadow_evaluations (
+                    model_name, role, score_event_id, fixture_id, market_id, sport,
+                    yes_is_home, model_prob_yes, yes_price, no_price, outcome, edge,
+                    threshold_edge, would_trade, stake_usd, entry_price, evaluated_at
+                 ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15,?16,?17)",
+                params![
+                    row.model_name,
+                    row.role,
+                    row.score_event_id,
+                    row.fixture_id,
+                    row.market_id,
+                    row.sport,
+                    row.yes_is_home,
+                    row.model_prob_yes,
+                    row.yes_price,
+                    row.no_price,
+                    row.outcome,
+                    row.edge,
+                    row.threshold_edge,
+                    row.would_trade,
+                    row.stake_usd,
+                    row.entry_price,
+                    row.evaluated_at,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Label a finished fixture's shadow evaluations from its final score.
+    pub fn label_fixture_shadow_evaluations(
+        &self,
+        fixture_id: &str,
+        home_score: i32,
+        away_score: i32,
+    ) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let n = conn.execute(
+            "UPDATE shadow_evaluations
+             SET yes_won = CASE WHEN yes_is_home THEN ?2 ELSE ?3 END, labelled_at = ?4
+             WHERE fixture_id = ?1 AND yes_won IS NULL",
+            params![
+                fixture_id,
+                home_score > away_score,
+                away_score > home_score,
+                Utc::now()
+            ],
+        )?;
+        Ok(n)
+    }
+
+    pub fn list_shadow_evaluations(&self) -> Result<Vec<ShadowEvaluation>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, model_name, role, score_event_id, fixture_id, market_id, sport,
+                    yes_is_home, model_prob_yes, yes_price, no_price, outcome, edge,
+                    threshold_edge, would_trade, stake_usd, entry_price, evaluated_at,
+                    yes_won, labelled_at
+             FROM shadow_evaluations
+             ORDER BY id ASC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ShadowEvaluation {
+                    id: row.get(0)?,
+                    model_name: row.get(1)?,
+                    role: row.get(2)?,
+                    score_event_id: row.get(3)?,
+                    fixture_id: row.get(4)?,
+                    market_id: row.get(5)?,
+                    sport: row.get(6)?,
+                    yes_is_home: row.get(7)?,
+                    model_prob_yes: row.get(8)?,
+                    yes_price: row.get(9)?,
+                    no_price: row.get(10)?,
+                    outcome: row.get(11)?,
+                    edge: row.get(12)?,
+                    threshold_edge: row.get(13)?,
+                    would_trade: row.get(14)?,
+                    stake_usd: row.get(15)?,
+                    entry_price: row.get(16)?,
+                    evaluated_at: row.get(17)?,
+                    yes_won: row.get(18)?,
+                    labelled_at: row.get(19)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn insert_drift_alert(&self, alert: &DriftAlert) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO drift_alerts (
+                kind, scope, metric, baseline, threshold, action, message, raised_at
+             ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8)",
+            params![
+                alert.kind,
+                alert.scope,
+                alert.metric,
+                alert.baseline,
+                alert.threshold,
+                alert.action,
+                alert.message,
+                alert.raised_at,
             ],
         )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn resolve_drift_alert(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE drift_alerts SET resolved_at = ?2 WHERE id = ?1 AND resolved_at IS NULL",
+            params![id, Utc::now()],
+        )?;
+        Ok(())
+    }
+
+    /// Close alerts left open by a previous run; monitors re-raise them if
+    /// the breach persists.
+    pub fn resolve_open_drift_alerts(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let n = conn.execute(
+            "UPDATE drift_alerts SET resolved_at = ?1 WHERE resolved_at IS NULL",
+            params![Utc::now()],
+        )?;
+        Ok(n)
+    }
+
+    /// Most recent drift alerts, open ones first.
+    pub fn list_drift_alerts(&self, limit: i64) -> Result<Vec<DriftAlert>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, scope, metric, baseline, threshold, action, message,
+                    raised_at, resolved_at
+             FROM drift_alerts
+             ORDER BY resolved_at IS NOT NULL, raised_at DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![limit], |row| {
+                Ok(DriftAlert {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    scope: row.get(2)?,
+                    metric: row.get(3)?,
+                    baseline: row.get(4)?,
+                    threshold: row.get(5)?,
+                    action: row.get(6)?,
+                    message: row.get(7)?,
+                    raised_at: row.get(8)?,
+                    resolved_at: row.get(9)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Record runtime parameter changes in one transaction.
+    pub fn insert_config_changes(&self, changes: &[ConfigChange]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for change in changes {
+            tx.execute(
+                "INSERT INTO config_changes (actor, key, old_value, new_value, changed_at)
+                 VALUES (?1,?2,?3,?4,?5)",
+                params![
+                    change.actor,
+                    change.key,
+                    change.old_value,
+                    change.new_value,
+                    change.changed_at,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Most recent runtime parameter changes first.
+    pub fn list_config_changes(&self, limit: i64) -> Result<Vec<ConfigChange>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, actor, key, old_value, new_value, changed_at
+             FROM config_changes
+             ORDER BY changed_at DESC, id DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![limit], |row| {
+                Ok(ConfigChange {
+                    id: row.get(0)?,
+                    actor: row.get(1)?,
+                    key: row.get(2)?,
+                    old_value: row.get(3)?,
+                    new_value: row.get(4)?,
+                    changed_at: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Replace the persisted pause and blacklist state with `rows` of
+    /// `(kind, value)` (see `ManualControls::to_rows`).
+    pub fn replace_manual_controls(&self, rows: &[(String, String)]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM manual_controls", [])?;
+        for (kind, value) in rows {
+            tx.execute(
+                "INSERT OR IGNORE INTO manual_controls (kind, value) VALUES (?1,?2)",
+                params![kind, value],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn list_manual_controls(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT kind, value FROM manual_controls ORDER BY kind, value")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Replace the persisted live entry orders with `entries` of
+    /// `(order_id, entry_json)` (see `bot::execution::PendingEntry`).
+    pub fn replace_pending_entries(&self, entries: &[(String, String)]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM pending_entries", [])?;
+        let now = Utc::now();
+        for (order_id, entry_json) in entries {
+            tx.execute(
+                "INSERT OR REPLACE INTO pending_entries (order_id, entry_json, updated_at)
+                 VALUES (?1,?2,?3)",
+                params![order_id, entry_json, now],
+            )?;
+        }
+        tx.commit()?;
         Ok(())
     }
+
+    /// JSON of the persisted live entry orders.
+    pub fn list_pending_entries(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT entry_json FROM pending_entries ORDER BY updated_at, order_id")?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn insert_control_action(&self, action: &ControlAction) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO control_actions (actor, command, outcome, acted_at)
+             VALUES (?1,?2,?3,?4)",
+            params![
+                action.actor,
+                action.command,
+                action.outcome,
+                action.acted_at
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Most recent manual control actions first.
+    pub fn list_control_actions(&self, limit: i64) -> Result<Vec<ControlAction>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, actor, command, outcome, acted_at
+             FROM control_actions
+             ORDER BY acted_at DESC, id DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![limit], |row| {
+                Ok(ControlAction {
+                    id: row.get(0)?,
+                    actor: row.get(1)?,
+                    command: row.get(2)?,
+                    outcome: row.get(3)?,
+                    acted_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// `(sport, clv_bps)` of closed directional trades, oldest close first
+    /// (CLV as in `SportClvStats`).
+    pub fn list_closed_clv(&self) -> Result<Vec<(String, f64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(sport, 'unknown'), (exit_price - entry_price) * 10000.0
+             FROM positions
+             WHERE status != 'open' AND exit_price IS NOT NULL AND strategy = 'directional'
+             ORDER BY closed_at ASC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Every archived fixture with its parsed score timeline.
+    pub fn list_fixtures(&self) -> Result<Vec<ArchivedFixture>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT event_id, sport, league, home_team, away_team, home_score,
+                    away_score, status, start_time, finished_at, timeline_json
+             FROM fixtures
+             ORDER BY finished_at ASC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    ArchivedFixture {
+                        event_id: row.get(0)?,
+                        sport: row.get(1)?,
+                        league: row.get(2)?,
+                        home_team: row.get(3)?,
+                        away_team: row.get(4)?,
+                        home_score: row.get(5)?,
+                        away_score: row.get(6)?,
+                        status: row.get(7)?,
+                        start_time: row.get(8)?,
+                        finished_at: row.get(9)?,
+                        timeline: Vec::new(),
+                    },
+                    row.get::<_, String>(10)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.into_iter()
+            .map(|(mut fixture, timeline_json)| {
+                fixture.timeline = serde_json::from_str(&timeline_json)?;
+                Ok(fixture)
+            })
+            .collect()
+    }
 }
 
 // ── SQL helpers ────────────────────────────────────────────────────────────────
 
+/// Column list matching the field order expected by `map_position`.
+const POSITION_COLUMNS: &str = "id, market_id, asset_id, outcome, side, size_usd, entry_price,
+    entry_price_source, entry_model_prob_raw, entry_model_prob,
+    entry_ws_age_ms, estimated_round_trip_cost_bps,
+    stop_loss_price, take_profit_price, status,
+    opened_at, closed_at, exit_price, pnl, dry_run,
+    ws_used_count, rest_fallback_count, last_ws_age_ms,
+    sport, league, event_name, market_slug,
+    fixture_id, entry_score_event_id, yes_is_home, correction_induced,
+    exit_policy, high_water_price, realized_pnl, partial_exit_count, add_count,
+    strategy, group_id, model_version";
+
 fn map_position(row: &rusqlite::Row) -> rusqlite::Result<Position> {
     Ok(Position {
         id: row.get(0)?,
@@ -660,9 +1561,41 @@ fn map_position(row: &rusqlite::Row) -> rusqlite::Result<Position> {
         league: row.get(24)?,
         event_name: row.get(25)?,
         market_slug: row.get(26)?,
+        fixture_id: row.get(27)?,
+        entry_score_event_id: row.get(28)?,
+        yes_is_home: row.get(29)?,
+        correction_induced: row.get(30)?,
+        exit_policy: row.get(31)?,
+        high_water_price: row.get(32)?,
+        realized_pnl: row.get(33)?,
+        partial_exit_count: row.get(34)?,
+        add_count: row.get(35)?,
+        strategy: row.get(36)?,
+        group_id: row.get(37)?,
+        model_version: row.get(38)?,
     })
 }
 
+/// Record one leg of a position's entry or exit in `position_fills`.
+#[allow(clippy::too_many_arguments)]
+fn insert_fill(
+    conn: &Connection,
+    position_id: i64,
+    side: &str,
+    shares: f64,
+    price: f64,
+    size_usd: f64,
+    pnl: f64,
+    reason: &str,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO position_fills (position_id, side, shares, price, size_usd, pnl, reason, filled_at)
+         VALUES (?1,?2,?3,?4,?5,?6,?7,?8)",
+        params![position_id, side, shares, price, size_usd, pnl, reason, Utc::now()],
+    )?;
+    Ok(())
+}
+
 fn map_market(row: &rusqlite::Row) -> rusqlite::Result<Market> {
     Ok(Market {
         id: row.get(0)?,
@@ -749,9 +1682,36 @@ CREATE TABLE IF NOT EXISTS positions (
     sport             TEXT,
     league            TEXT,
     event_name        TEXT,
+    market_slug       TEXT,
+    fixture_id        TEXT,
+    entry_score_event_id INTEGER,
+    yes_is_home       INTEGER,
+    correction_induced INTEGER NOT NULL DEFAULT 0,
+    exit_policy       TEXT    NOT NULL DEFAULT 'fixed',
+    high_water_price  REAL,
+    realized_pnl      REAL    NOT NULL DEFAULT 0.0,
+    partial_exit_count INTEGER NOT NULL DEFAULT 0,
+    add_count         INTEGER NOT NULL DEFAULT 0,
+    strategy          TEXT    NOT NULL DEFAULT 'directional',
+    group_id          INTEGER,
+    model_version     TEXT,
     FOREIGN KEY (market_id) REFERENCES markets(id)
 );
 
+CREATE TABLE IF NOT EXISTS position_fills (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    position_id INTEGER NOT NULL,
+    side        TEXT    NOT NULL,
+    shares      REAL    NOT NULL,
+    price       REAL    NOT NULL,
+    size_usd    REAL    NOT NULL,
+    pnl         REAL    NOT NULL DEFAULT 0.0,
+    reason      TEXT,
+    filled_at   TEXT    NOT NULL,
+    FOREIGN KEY (position_id) REFERENCES positions(id)
+);
+CREATE INDEX IF NOT EXISTS idx_position_fills_position ON position_fills(position_id);
+
 CREATE TABLE IF NOT EXISTS score_events (
     id          INTEGER PRIMARY KEY AUTOINCREMENT,
     event_id    TEXT    NOT NULL,
@@ -771,7 +1731,7 @@ CREATE TABLE IF NOT EXISTS score_events (
 );
 
 CREATE TABLE IF NOT EXISTS model_calibrations (
-    sport       TEXT    PRIMARY KEY,
+    level_key   TEXT    PRIMARY KEY,
     a           REAL    NOT NULL,
     b           REAL    NOT NULL,
     samples     INTEGER NOT NULL,
@@ -779,13 +1739,127 @@ CREATE TABLE IF NOT EXISTS model_calibrations (
     logloss_after  REAL NOT NULL,
     brier_before   REAL NOT NULL,
     brier_after    REAL NOT NULL,
-    fitted_at   TEXT    NOT NULL
+    fitted_at   TEXT    NOT NULL,
+    model_kind  TEXT    NOT NULL DEFAULT 'platt',
+    params_json TEXT,
+    reliability_json TEXT
 );
 
 CREATE INDEX IF NOT EXISTS idx_positions_status ON positions(status);
 CREATE INDEX IF NOT EXISTS idx_positions_market ON positions(market_id);
 CREATE INDEX IF NOT EXISTS idx_score_events_event ON score_events(event_id);
 CREATE INDEX IF NOT EXISTS idx_model_calibrations_fitted_at ON model_calibrations(fitted_at);
+
+CREATE TABLE IF NOT EXISTS fixtures (
+    event_id      TEXT    PRIMARY KEY,
+    sport         TEXT    NOT NULL,
+    league        TEXT    NOT NULL,
+    home_team     TEXT    NOT NULL,
+    away_team     TEXT    NOT NULL,
+    home_score    INTEGER NOT NULL,
+    away_score    INTEGER NOT NULL,
+    status        TEXT    NOT NULL,
+    start_time    TEXT,
+    finished_at   TEXT    NOT NULL,
+    timeline_json TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_fixtures_sport ON fixtures(sport, finished_at);
+
+CREATE TABLE IF NOT EXISTS model_observations (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    score_event_id      INTEGER,
+    fixture_id          TEXT    NOT NULL,
+    sport               TEXT    NOT NULL,
+    league              TEXT,
+    minute              INTEGER,
+    home_score          INTEGER NOT NULL,
+    away_score          INTEGER NOT NULL,
+    model_prob_home_raw REAL    NOT NULL,
+    market_id           TEXT,
+    yes_is_home         INTEGER,
+    observed_at         TEXT    NOT NULL,
+    home_won            INTEGER,
+    label_source        TEXT,
+    labelled_at         TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_model_observations_fixture ON model_observations(fixture_id);
+CREATE INDEX IF NOT EXISTS idx_model_observations_market ON model_observations(market_id);
+CREATE INDEX IF NOT EXISTS idx_model_observations_observed_at ON model_observations(observed_at);
+
+CREATE TABLE IF NOT EXISTS shadow_evaluations (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    model_name      TEXT    NOT NULL,
+    role            TEXT    NOT NULL,
+    score_event_id  INTEGER,
+    fixture_id      TEXT    NOT NULL,
+    market_id       TEXT    NOT NULL,
+    sport           TEXT    NOT NULL,
+    yes_is_home     INTEGER NOT NULL,
+    model_prob_yes  REAL    NOT NULL,
+    yes_price       REAL    NOT NULL,
+    no_price        REAL    NOT NULL,
+    outcome         TEXT    NOT NULL,
+    edge            REAL    NOT NULL,
+    threshold_edge  REAL    NOT NULL,
+    would_trade     INTEGER NOT NULL,
+    stake_usd       REAL    NOT NULL,
+    entry_price     REAL    NOT NULL,
+    evaluated_at    TEXT    NOT NULL,
+    yes_won         INTEGER,
+    labelled_at     TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_shadow_evaluations_fixture ON shadow_evaluations(fixture_id);
+
+CREATE TABLE IF NOT EXISTS drift_alerts (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind        TEXT    NOT NULL,
+    scope       TEXT    NOT NULL,
+    metric      REAL    NOT NULL,
+    baseline    REAL    NOT NULL,
+    threshold   REAL    NOT NULL,
+    action      TEXT    NOT NULL,
+    message     TEXT    NOT NULL,
+    raised_at   TEXT    NOT NULL,
+    resolved_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS config_changes (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor       TEXT    NOT NULL,
+    key         TEXT    NOT NULL,
+    old_value   TEXT    NOT NULL,
+    new_value   TEXT    NOT NULL,
+    changed_at  TEXT    NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS calibration_versions (
+    version     TEXT    PRIMARY KEY,
+    models_json TEXT    NOT NULL,
+    created_at  TEXT    NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS manual_controls (
+    kind        TEXT    NOT NULL,
+    value       TEXT    NOT NULL,
+    PRIMARY KEY (kind, value)
+);
+
+CREATE TABLE IF NOT EXISTS pending_entries (
+    order_id    TEXT    PRIMARY KEY,
+    entry_json  TEXT    NOT NULL,
+    updated_at  TEXT    NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS control_actions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor       TEXT    NOT NULL,
+    command     TEXT    NOT NULL,
+    outcome     TEXT    NOT NULL,
+    acted_at    TEXT    NOT NULL
+);
 "#;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -804,8 +1878,13 @@ pub struct Stats {
     pub calibration_models_active: i64,
     pub calibration_last_fit_at: Option<DateTime<Utc>>,
     pub avg_closed_clv_bps: f64,
+    /// Positions whose triggering score event was later reverted by a correction.
+    pub correction_induced_trades: i64,
+    pub correction_induced_pnl: f64,
     pub sport_quote_stats: Vec<SportQuoteStats>,
     pub sport_clv_stats: Vec<SportClvStats>,
+    /// Closed model-driven trades by win-probability parameter version
+    pub model_version_stats: Vec<ModelVersionStats>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -825,6 +1904,14 @@ pub struct SportClvStats {
     pub win_rate: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVersionStats {
+    pub model_version: String,
+    pub trades: i64,
+    pub pnl: f64,
+    pub win_rate: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BalanceSnapshot {
     pub balance: f64,
@@ -833,7 +1920,10 @@ pub struct BalanceSnapshot {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelCalibration {
-    pub sport: String,
+    /// Calibration level: "sport", "sport/league" or "sport/league/phase"
+    /// (stored in the `level_key` column)
+    pub key: String,
+    /// Platt parameters (identity for other model kinds)
     pub a: f64,
     pub b: f64,
     pub samples: i64,
@@ -842,6 +1932,135 @@ pub struct ModelCalibration {
     pub brier_before: f64,
     pub brier_after: f64,
     pub fitted_at: DateTime<Utc>,
+    /// "platt", "isotonic" or "histogram"
+    pub model_kind: String,
+    /// Serialized `CalibrationModel`; absent for legacy Platt rows
+    pub params_json: Option<String>,
+    /// Serialized reliability-diagram bins of the fitted model
+    pub reliability_json: Option<String>,
+}
+
+/// One score change in an archived fixture's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreTimelineEntry {
+    pub minute: Option<i32>,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub event_type: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// A drift monitor breach (see `bot::drift`); open until `resolved_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftAlert {
+    pub id: Option<i64>,
+    /// "calibration", "edge_decay" or "feed_quality"
+    pub kind: String,
+    /// Sport key, or "*" for all sports
+    pub scope: String,
+    pub metric: f64,
+    pub baseline: f64,
+    pub threshold: f64,
+    /// Response taken: "alert", "tighten" or "pause"
+    pub action: String,
+    pub message: String,
+    pub raised_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// A runtime parameter change made through the admin API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub id: Option<i64>,
+    /// Name of the admin token that made the change
+    pub actor: String,
+    pub key: String,
+    /// JSON-encoded values before and after
+    pub old_value: String,
+    pub new_value: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A manual control command run through the admin API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlAction {
+    pub id: Option<i64>,
+    /// Name of the admin token that issued the command
+    pub actor: String,
+    /// JSON-encoded `ControlCommand`
+    pub command: String,
+    /// Engine's reply, or the error that rejected the command
+    pub outcome: String,
+    pub acted_at: DateTime<Utc>,
+}
+
+/// One model's view of a winner market on a score event (see `bot::shadow`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowEvaluation {
+    pub id: Option<i64>,
+    pub model_name: String,
+    /// "champion" (the live model) or "challenger"
+    pub role: String,
+    pub score_event_id: Option<i64>,
+    pub fixture_id: String,
+    pub market_id: String,
+    pub sport: String,
+    pub yes_is_home: bool,
+    pub model_prob_yes: f64,
+    pub yes_price: f64,
+    pub no_price: f64,
+    /// Side with the larger raw edge
+    pub outcome: String,
+    pub edge: f64,
+    pub threshold_edge: f64,
+    pub would_trade: bool,
+    pub stake_usd: f64,
+    pub entry_price: f64,
+    pub evaluated_at: DateTime<Utc>,
+    /// Whether YES paid out, once the final score is known
+    pub yes_won: Option<bool>,
+    pub labelled_at: Option<DateTime<Utc>>,
+}
+
+/// A finished fixture from the `fixtures` archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFixture {
+    pub event_id: String,
+    pub sport: String,
+    pub league: String,
+    pub home_team: String,
+    pub away_team: String,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub status: String,
+    pub start_time: Option<DateTime<Utc>>,
+    pub finished_at: DateTime<Utc>,
+    pub timeline: Vec<ScoreTimelineEntry>,
+}
+
+/// Raw model probability recorded on a score event, labelled once the
+/// fixture's result is known.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelObservation {
+    pub id: Option<i64>,
+    pub score_event_id: Option<i64>,
+    pub fixture_id: String,
+    pub sport: String,
+    pub league: Option<String>,
+    pub minute: Option<i32>,
+    pub home_score: i32,
+    pub away_score: i32,
+    /// Uncalibrated home-win probability after the event
+    pub model_prob_home_raw: f64,
+    /// Winner market used to label from resolution
+    pub market_id: Option<String>,
+    pub yes_is_home: Option<bool>,
+    pub observed_at: DateTime<Utc>,
+    /// Label: whether the home side won (draws count as not won)
+    pub home_won: Option<bool>,
+    /// "final_score" or "market_resolution"
+    pub label_source: Option<String>,
+    pub labelled_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone)]
@@ -850,9 +2069,12 @@ pub struct CalibrationCandidate {
     pub market_id: String,
     pub outcome: String,
     pub model_prob_raw: f64,
+    pub league: Option<String>,
+    /// Game minute of the entry score event, when still retained
+    pub minute: Option<i32>,
 }
 
-fn ensure_column(conn: &Connection, table: &str, column: &str, column_type: &str) -> Result<()> {
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
     let pragma = format!("PRAGMA table_info({})", table);
     let mut stmt = conn.prepare(&pragma)?;
     let exists = stmt
@@ -860,7 +2082,19 @@ fn ensure_column(conn: &Connection, table: &str, column: &str, column_type: &str
         .collect::<rusqlite::Result<Vec<_>>>()?
         .iter()
         .any(|name| name == column);
-    if !exists {
+    Ok(exists)
+}
+
+fn rename_column(conn: &Connection, table: &str, from: &str, to: &str) -> Result<()> {
+    if has_column(conn, table, from)? && !has_column(conn, table, to)? {
+        let alter = format!("ALTER TABLE {} RENAME COLUMN {} TO {}", table, from, to);
+        conn.execute_batch(&alter)?;
+    }
+    Ok(())
+}
+
+fn ensure_column(conn: &Connection, table: &str, column: &str, column_type: &str) -> Result<()> {
+    if !has_column(conn, table, column)? {
         let alter = format!(
             "ALTER TABLE {} ADD COLUMN {} {}",
             table, column, column_type
@@ -869,3 +2103,318 @@ fn ensure_column(conn: &Connection, table: &str, column: &str, column_type: &str
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn db() -> Database {
+        Database::open(":memory:").unwrap()
+    }
+
+    fn market(db: &Database, id: &str) {
+        db.upsert_market(&Market {
+            id: id.into(),
+            question: "Will Arsenal win?".into(),
+            sport: Some("soccer".into()),
+            league: None,
+            event_name: None,
+            yes_price: None,
+            no_price: None,
+            volume: None,
+            status: "active".into(),
+            fetched_at: Utc::now(),
+            slug: None,
+            end_date: None,
+            liquidity: None,
+        })
+        .unwrap();
+    }
+
+    fn position(db: &Database) -> i64 {
+        market(db, "mkt1");
+        // 20 shares at 0.50
+        db.insert_position(&Position::new_open(
+            "mkt1",
+            "YES",
+            "directional",
+            10.0,
+            0.5,
+            true,
+        ))
+        .unwrap()
+    }
+
+    fn observation(db: &Database, fixture_id: &str, market: Option<(&str, bool)>) -> i64 {
+        let id = db
+            .insert_model_observation(&ModelObservation {
+                id: None,
+                score_event_id: None,
+                fixture_id: fixture_id.into(),
+                sport: "soccer".into(),
+                league: Some("premier league".into()),
+                minute: Some(30),
+                home_score: 1,
+                away_score: 0,
+                model_prob_home_raw: 0.7,
+                market_id: None,
+                yes_is_home: None,
+                observed_at: Utc::now() - chrono::Duration::hours(5),
+                home_won: None,
+                label_source: None,
+                labelled_at: None,
+            })
+            .unwrap();
+        if let Some((market_id, yes_is_home)) = market {
+            db.set_observation_market(id, market_id, yes_is_home)
+                .unwrap();
+        }
+        id
+    }
+
+    #[test]
+    fn observations_are_labelled_once_from_score_or_resolution() {
+        let db = db();
+        observation(&db, "fx1", Some(("mkt1", true)));
+        observation(&db, "fx1", Some(("mkt1", true)));
+        observation(&db, "fx2", Some(("mkt2", false)));
+        observation(&db, "fx3", None);
+
+        let cutoff = Utc::now() - chrono::Duration::hours(3);
+        let mut markets = db.list_unlabelled_observation_markets(cutoff).unwrap();
+        markets.sort();
+        assert_eq!(
+            markets,
+            vec![
+                ("mkt1".to_string(), true, "soccer".to_string()),
+                ("mkt2".to_string(), false, "soccer".to_string()),
+            ]
+        );
+        // Too recent to label yet.
+        let early = Utc::now() - chrono::Duration::hours(6);
+        assert!(db
+            .list_unlabelled_observation_markets(early)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(db.label_fixture_observations("fx1", true).unwrap(), 2);
+        // Already labelled from the final score; resolution does not relabel.
+        assert_eq!(db.label_market_observations("mkt1", false).unwrap(), 0);
+        assert_eq!(db.label_market_observations("mkt2", false).unwrap(), 1);
+        assert_eq!(db.unlink_market_observations("mkt2").unwrap(), 0);
+        assert!(db
+            .list_unlabelled_observation_markets(cutoff)
+            .unwrap()
+            .is_empty());
+
+        let labelled = db.list_labelled_observations(None).unwrap();
+        assert_eq!(labelled.len(), 3);
+        let sources: Vec<_> = labelled
+            .iter()
+            .map(|o| (o.fixture_id.as_str(), o.home_won, o.label_source.as_deref()))
+            .collect();
+        assert!(sources.contains(&("fx1", Some(true), Some("final_score"))));
+        assert!(sources.contains(&("fx2", Some(false), Some("market_resolution"))));
+
+        // Drift windows are cut in SQL.
+        let window = |hours| Utc::now() - chrono::Duration::hours(hours);
+        assert_eq!(
+            db.list_labelled_observations(Some(window(6)))
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(db
+            .list_labelled_observations(Some(window(4)))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn unlinked_observations_wait_for_the_final_score() {
+        let db = db();
+        observation(&db, "fx1", Some(("mkt1", false)));
+        assert_eq!(db.unlink_market_observations("mkt1").unwrap(), 1);
+        let cutoff = Utc::now() - chrono::Duration::hours(3);
+        assert!(db
+            .list_unlabelled_observation_markets(cutoff)
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.label_fixture_observations("fx1", false).unwrap(), 1);
+    }
+
+    #[test]
+    fn partial_close_shrinks_position_and_books_a_sell_leg() {
+        let db = db();
+        let id = position(&db);
+        db.partial_close_position(id, 4.0, 0.6, 0.8, "partial_take_profit")
+            .unwrap();
+
+        let pos = &db.list_open_positions().unwrap()[0];
+        assert_relative_eq!(pos.size_usd, 6.0);
+        assert_relative_eq!(pos.realized_pnl, 0.8);
+        assert_eq!(pos.partial_exit_count, 1);
+
+        let fills = db.list_position_fills(id).unwrap();
+        assert_eq!(fills.len(), 2);
+        let sell = fills.iter().find(|f| f.side == "sell").unwrap();
+        assert_relative_eq!(sell.shares, 8.0);
+        assert_relative_eq!(sell.price, 0.6);
+        assert_eq!(sell.reason.as_deref(), Some("partial_take_profit"));
+
+        // Booked partial P&L counts before the position closes.
+        assert_relative_eq!(db.get_stats().unwrap().total_pnl, 0.8);
+    }
+
+    #[test]
+    fn close_after_partial_exit_averages_exit_price_and_totals_pnl() {
+        let db = db();
+        let id = position(&db);
+        db.partial_close_position(id, 4.0, 0.6, 0.8, "partial_take_profit")
+            .unwrap();
+        // Remaining 12 shares at 0.70
+        db.close_position(id, "closed_profit", 0.7, 2.4).unwrap();
+
+        let pos = &db.list_positions(10, 0).unwrap()[0];
+        assert_eq!(pos.status, "closed_profit");
+        // (8 * 0.6 + 12 * 0.7) / 20
+        assert_relative_eq!(pos.exit_price.unwrap(), 0.66, epsilon = 1e-9);
+        assert_relative_eq!(pos.pnl.unwrap(), 3.2, epsilon = 1e-9);
+        assert_eq!(db.list_position_fills(id).unwrap().len(), 3);
+
+        let stats = db.get_stats().unwrap();
+        assert_eq!(stats.total_trades, 1);
+        assert_eq!(stats.winning_trades, 1);
+        assert_eq!(stats.open_positions, 0);
+        assert_relative_eq!(stats.total_pnl, 3.2, epsilon = 1e-9);
+        assert_relative_eq!(stats.avg_closed_clv_bps, 1600.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn manual_controls_are_replaced_and_actions_listed_newest_first() {
+        let db = db();
+        let rows = vec![
+            ("entries_paused".to_string(), String::new()),
+            ("paused_sport".to_string(), "basketball".to_string()),
+        ];
+        db.replace_manual_controls(&rows).unwrap();
+        assert_eq!(db.list_manual_controls().unwrap(), rows);
+        db.replace_manual_controls(&rows[1..]).unwrap();
+        assert_eq!(db.list_manual_controls().unwrap(), rows[1..]);
+
+        let action = |command: &str, secs: i64| ControlAction {
+            id: None,
+            actor: "ops".into(),
+            command: command.into(),
+            outcome: "applied".into(),
+            acted_at: Utc::now() + chrono::Duration::seconds(secs),
+        };
+        db.insert_control_action(&action(r#"{"action":"pause"}"#, 0))
+            .unwrap();
+        db.insert_control_action(&action(r#"{"action":"resume"}"#, 1))
+            .unwrap();
+        let actions = db.list_control_actions(10).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].command, r#"{"action":"resume"}"#);
+        assert_eq!(db.list_control_actions(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn archived_timeline_outlives_pruned_score_events() {
+        let db = db();
+        let goal = |event_id: &str, minute, home_score, away_score, days_ago| ScoreEvent {
+            id: None,
+            event_id: event_id.into(),
+            source_provider: Some("espn".into()),
+            provider_consensus_count: Some(2),
+            sport: "soccer".into(),
+            league: "premier league".into(),
+            home_team: "Arsenal".into(),
+            away_team: "Chelsea".into(),
+            prev_home_score: None,
+            prev_away_score: None,
+            home_score,
+            away_score,
+            minute: Some(minute),
+            event_type: "goal".into(),
+            detected_at: Utc::now() - chrono::Duration::days(days_ago),
+        };
+        db.insert_score_event(&goal("fx1", 12, 1, 0, 10)).unwrap();
+        let equaliser = db.insert_score_event(&goal("fx1", 70, 1, 1, 9)).unwrap();
+        db.insert_score_event(&goal("fx2", 5, 0, 1, 0)).unwrap();
+
+        let game = LiveGame {
+            event_id: "fx1".into(),
+            sport: "soccer".into(),
+            league: "premier league".into(),
+            home_team: "Arsenal".into(),
+            away_team: "Chelsea".into(),
+            home_score: 1,
+            away_score: 1,
+            minute: Some(90),
+            status: GameStatus::Finished,
+            start_time: None,
+            period: Some(2),
+            added_time: None,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            power_play: None,
+        };
+        assert_eq!(db.archive_fixture(&game).unwrap(), 2);
+        assert_eq!(db.prune_score_events(7).unwrap(), 2);
+        assert!(db.get_score_event(equaliser).unwrap().is_none());
+
+        let fixtures = db.list_fixtures().unwrap();
+        assert_eq!(fixtures.len(), 1);
+        let fixture = &fixtures[0];
+        assert_eq!(
+            (
+                fixture.event_id.as_str(),
+                fixture.home_score,
+                fixture.away_score
+            ),
+            ("fx1", 1, 1)
+        );
+        let timeline: Vec<_> = fixture
+            .timeline
+            .iter()
+            .map(|e| (e.minute, e.home_score, e.away_score, e.event_type.as_str()))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![(Some(12), 1, 0, "goal"), (Some(70), 1, 1, "goal")]
+        );
+    }
+
+    #[test]
+    fn calibration_key_column_is_renamed_in_place() {
+        let path = std::env::temp_dir().join(format!(
+            "livesports-calibration-rename-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE model_calibrations (
+                    sport TEXT PRIMARY KEY, a REAL NOT NULL, b REAL NOT NULL,
+                    samples INTEGER NOT NULL, logloss_before REAL NOT NULL,
+                    logloss_after REAL NOT NULL, brier_before REAL NOT NULL,
+                    brier_after REAL NOT NULL, fitted_at TEXT NOT NULL
+                 );
+                 INSERT INTO model_calibrations VALUES
+                    ('soccer/epl', 1.0, 0.0, 80, 0.6, 0.5, 0.2, 0.18,
+                     '2024-01-01T00:00:00Z');",
+            )
+            .unwrap();
+        }
+        let db = Database::open(path.to_str().unwrap()).unwrap();
+        let rows = db.load_model_calibrations().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].key, "soccer/epl");
+        assert_eq!(rows[0].model_kind, "platt");
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -55,6 +55,7 @@ pub struct Position {
     /// Price at which we trigger take-profit exit
     pub take_profit_price: f64,
     /// "open" | "closed_profit" | "closed_loss" | "closed_stop_loss" | "closed_feed_health" | "closed_time_exit"
+    /// | "closed_score_correction" | "closed_settled" | "closed_maker" | ...
     pub status: String,
     pub opened_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
@@ -72,6 +73,105 @@ pub struct Position {
     pub league: Option<String>,
     pub event_name: Option<String>,
     pub market_slug: Option<String>,
+    /// Score-feed event ID of the fixture this position was opened on.
+    pub fixture_id: Option<String>,
+    /// `score_events` row whose score change triggered the entry.
+    pub entry_score_event_id: Option<i64>,
+    /// Whether YES on this market pays out on a home win (see `infer_yes_is_home`).
+    pub yes_is_home: Option<bool>,
+    /// Set when a later score correction reverted the event that opened this position.
+    pub correction_induced: bool,
+    /// Named exit policy: "fixed", "trailing", "time_decay", "breakeven" or "composite"
+    pub exit_policy: String,
+    /// Highest mark seen since entry (drives trailing and break-even stops)
+    pub high_water_price: Option<f64>,
+    /// Net P&L already booked by partial exits (see `position_fills`)
+    pub realized_pnl: f64,
+    /// Number of partial exits taken so far
+    pub partial_exit_count: i32,
+    /// Number of times the position was added to (pyramided) after entry
+    pub add_count: i32,
+    /// "directional" (score-driven entries), "hedge", "arbitrage" or "market_making"
+    pub strategy: String,
+    /// Shared by a position and its hedge (the original position's ID)
+    pub group_id: Option<i64>,
+    /// Win-probability parameter version used at entry (see `model_store`)
+    pub model_version: Option<String>,
+}
+
+impl Position {
+    /// A fresh open buy with no telemetry, fixture link or exit history.
+    /// Stop-loss and take-profit sit at 0 and 1 (never hit) and the exit
+    /// policy is "fixed"; callers override the rest with struct-update
+    /// syntax.
+    pub fn new_open(
+        market_id: impl Into<String>,
+        outcome: impl Into<String>,
+        strategy: &str,
+        size_usd: f64,
+        entry_price: f64,
+        dry_run: bool,
+    ) -> Self {
+        Position {
+            id: None,
+            market_id: market_id.into(),
+            asset_id: None,
+            outcome: outcome.into(),
+            side: "buy".into(),
+            size_usd,
+            entry_price,
+            entry_price_source: None,
+            entry_model_prob_raw: None,
+            entry_model_prob: None,
+            entry_ws_age_ms: None,
+            estimated_round_trip_cost_bps: 0.0,
+            stop_loss_price: 0.0,
+            take_profit_price: 1.0,
+            status: "open".into(),
+            opened_at: Utc::now(),
+            closed_at: None,
+            exit_price: None,
+            pnl: None,
+            dry_run,
+            ws_used_count: 0,
+            rest_fallback_count: 0,
+            last_ws_age_ms: None,
+            sport: None,
+            league: None,
+            event_name: None,
+            market_slug: None,
+            fixture_id: None,
+            entry_score_event_id: None,
+            yes_is_home: None,
+            correction_induced: false,
+            exit_policy: "fixed".into(),
+            high_water_price: Some(entry_price),
+            realized_pnl: 0.0,
+            partial_exit_count: 0,
+            add_count: 0,
+            strategy: strategy.into(),
+            group_id: None,
+            model_version: None,
+        }
+    }
+}
+
+/// One leg of a position: the entry buy or a (partial) exit sell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionFill {
+    pub id: i64,
+    pub position_id: i64,
+    /// "buy" or "sell"
+    pub side: String,
+    pub shares: f64,
+    pub price: f64,
+    /// Cost basis bought (buy) or released (sell)
+    pub size_usd: f64,
+    /// Net P&L booked by this leg (0 for buys)
+    pub pnl: f64,
+    /// "entry", "partial_take_profit", "exposure_reduction" or the close status
+    pub reason: Option<String>,
+    pub filled_at: DateTime<Utc>,
 }
 
 /// A detected live score change event
@@ -113,6 +213,16 @@ pub struct LiveGame {
     pub away_score: i32,
     pub minute: Option<i32>,
     pub status: GameStatus,
+    /// Scheduled kick-off, when the provider reports it
+    pub start_time: Option<DateTime<Utc>>,
+    /// Current period (1-based half/quarter/period), when known
+    pub period: Option<i32>,
+    /// Added (stoppage/injury) minutes being played, e.g. 2 for "45+2"
+    pub added_time: Option<i32>,
+    pub home_red_cards: i32,
+    pub away_red_cards: i32,
+    /// Side currently on a power play (ice hockey)
+    pub power_play: Option<TeamSide>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -122,3 +232,9 @@ pub enum GameStatus {
     HalfTime,
     Finished,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeamSide {
+    Home,
+    Away,
+}
@@ -0,0 +1,253 @@
+//! Game-clock extrapolation between provider updates.
+//!
+//! `LiveGame.minute` is only as fresh as the last provider payload, and REST
+//! feeds can lag the real clock by minutes.  A [`GameClock`] per fixture
+//! remembers the last reported minute and when it was first observed, then
+//! extrapolates the current game time at a sport-specific rate.  It never
+//! runs past the end of the current period (the provider must confirm the
+//! next one) and it is frozen at half-time.
+
+use chrono::{DateTime, Utc};
+
+use crate::db::models::{GameStatus, LiveGame};
+
+/// Never extrapolate further than this from the last observed minute change;
+/// beyond it the feed is stale and guessing does more harm than good.
+pub const MAX_EXTRAPOLATION_SECS: i64 = 10 * 60;
+
+/// How a sport's reported "minute" advances with wall-clock time.
+#[derive(Debug, Clone, Copy)]
+struct ClockProfile {
+    /// Game minutes elapsed per wall-clock minute (stoppages included).
+    rate: f64,
+    /// Game minutes at which periods end.
+    period_ends: &'static [f64],
+    /// Length of regulation time in game minutes.
+    regulation: f64,
+}
+
+/// Sport clock profiles.  Sports whose "minute" is not a running clock
+/// (baseball innings, tennis sets) are not extrapolated.
+fn clock_profile(sport: &str) -> Option<ClockProfile> {
+    match sport.to_lowercase().as_str() {
+        "soccer" | "football" | "football_eu" => Some(ClockProfile {
+            rate: 1.0,
+            period_ends: &[45.0, 90.0, 105.0, 120.0],
+            regulation: 90.0,
+        }),
+        // ~48 game minutes over ~2h15 of wall time
+        "basketball" | "nba" => Some(ClockProfile {
+            rate: 0.45,
+            period_ends: &[12.0, 24.0, 36.0, 48.0],
+            regulation: 48.0,
+        }),
+        // ~60 game minutes over ~3h of wall time
+        "american_football" | "nfl" => Some(ClockProfile {
+            rate: 0.3,
+            period_ends: &[15.0, 30.0, 45.0, 60.0],
+            regulation: 60.0,
+        }),
+        // ~60 game minutes over ~2h20 including intermissions
+        "ice_hockey" | "nhl" => Some(ClockProfile {
+            rate: 0.6,
+            period_ends: &[20.0, 40.0, 60.0],
+            regulation: 60.0,
+        }),
+        _ => None,
+    }
+}
+
+/// One-based period for a game minute, if the sport has a period structure.
+/// A minute equal to a period end still belongs to that period (45 → 1st half).
+pub fn period_for_minute(sport: &str, minute: i32) -> Option<i32> {
+    let profile = clock_profile(sport)?;
+    let minute = minute as f64;
+    let index = profile
+        .period_ends
+        .iter()
+        .position(|&end| minute <= end)
+        .unwrap_or(profile.period_ends.len());
+    Some(index as i32 + 1)
+}
+
+/// Fraction of regulation time elapsed (0.0–1.0) for a game minute.
+/// Baseball "minutes" are innings out of nine.
+pub fn game_progress(sport: &str, minute: i32) -> Option<f64> {
+    let regulation = match clock_profile(sport) {
+        Some(profile) => profile.regulation,
+        None if matches!(sport.to_lowercase().as_str(), "baseball" | "mlb") => 9.0,
+        None => return None,
+    };
+    Some((minute as f64 / regulation).clamp(0.0, 1.0))
+}
+
+/// Per-fixture clock model.
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    profile: Option<ClockProfile>,
+    /// Last reported minute and when it was first observed.
+    anchor: Option<(i32, DateTime<Utc>)>,
+    status: GameStatus,
+}
+
+impl GameClock {
+    pub fn new(sport: &str) -> Self {
+        GameClock {
+            profile: clock_profile(sport),
+            anchor: None,
+            status: GameStatus::NotStarted,
+        }
+    }
+
+    /// Record a provider snapshot observed at `now`.
+    pub fn observe(&mut self, game: &LiveGame, now: DateTime<Utc>) {
+        if self.profile.is_none() {
+            self.profile = clock_profile(&game.sport);
+        }
+        let resumed =
+            self.status != GameStatus::InProgress && game.status == GameStatus::InProgress;
+        self.status = game.status.clone();
+
+        let Some(reported) = game.minute else {
+            return;
+        };
+        match self.anchor {
+            Some((anchor_minute, _)) if !resumed => {
+                let estimate = self.minute_at(now).unwrap_or(anchor_minute);
+                if reported > anchor_minute && reported >= estimate {
+                    // Fresh minute at or ahead of our estimate.
+                    self.anchor = Some((reported, now));
+                } else if reported < anchor_minute {
+                    // Clock went backwards (feed switch or correction): trust it.
+                    self.anchor = Some((reported, now));
+                }
+                // Otherwise the feed is lagging our estimate; keep the anchor.
+            }
+            _ => self.anchor = Some((reported, now)),
+        }
+    }
+
+    /// Best estimate of the game minute at `now`.
+    pub fn minute_at(&self, now: DateTime<Utc>) -> Option<i32> {
+        let (minute, observed_at) = self.anchor?;
+        let Some(profile) = self.profile else {
+            return Some(minute);
+        };
+        if self.status != GameStatus::InProgress {
+            return Some(minute);
+        }
+
+        let elapsed_secs = (now - observed_at)
+            .num_seconds()
+            .clamp(0, MAX_EXTRAPOLATION_SECS);
+        let extrapolated = minute as f64 + profile.rate * elapsed_secs as f64 / 60.0;
+
+        // Hold at the end of the current period until the feed confirms the
+        // next; a minute on a boundary belongs to the period it ends, as in
+        // `period_for_minute`.
+        let capped = match profile
+            .period_ends
+            .iter()
+            .find(|&&end| (minute as f64) <= end)
+        {
+            Some(&end) => extrapolated.min(end),
+            None => minute as f64,
+        };
+        Some((capped.floor() as i32).max(minute))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn game(sport: &str, minute: i32, status: GameStatus) -> LiveGame {
+        LiveGame {
+            event_id: "1".into(),
+            sport: sport.into(),
+            league: "test".into(),
+            home_team: "Home".into(),
+            away_team: "Away".into(),
+            home_score: 0,
+            away_score: 0,
+            minute: Some(minute),
+            status,
+            start_time: None,
+            period: None,
+            added_time: None,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            power_play: None,
+        }
+    }
+
+    fn t0() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 10, 15, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn soccer_clock_runs_and_caps_at_half() {
+        let mut clock = GameClock::new("soccer");
+        clock.observe(&game("soccer", 40, GameStatus::InProgress), t0());
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(3)), Some(43));
+        // Never runs past the end of the first half.
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(9)), Some(45));
+    }
+
+    #[test]
+    fn minute_on_a_period_boundary_holds_until_the_next_period() {
+        let mut clock = GameClock::new("soccer");
+        // Feeds report 45 through first-half stoppage time.
+        clock.observe(&game("soccer", 45, GameStatus::InProgress), t0());
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(4)), Some(45));
+        assert_eq!(period_for_minute("soccer", 45), Some(1));
+
+        let mut clock = GameClock::new("basketball");
+        clock.observe(&game("basketball", 12, GameStatus::InProgress), t0());
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(10)), Some(12));
+        assert_eq!(period_for_minute("basketball", 12), Some(1));
+        clock.observe(&game("basketball", 13, GameStatus::InProgress), t0());
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(10)), Some(17));
+    }
+
+    #[test]
+    fn paused_at_half_time_and_restarted_on_resume() {
+        let mut clock = GameClock::new("soccer");
+        clock.observe(&game("soccer", 45, GameStatus::HalfTime), t0());
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(10)), Some(45));
+
+        let resume = t0() + Duration::minutes(15);
+        clock.observe(&game("soccer", 46, GameStatus::InProgress), resume);
+        assert_eq!(clock.minute_at(resume + Duration::minutes(4)), Some(50));
+    }
+
+    #[test]
+    fn lagging_feed_does_not_pull_clock_back() {
+        let mut clock = GameClock::new("soccer");
+        clock.observe(&game("soccer", 60, GameStatus::InProgress), t0());
+        let later = t0() + Duration::minutes(4);
+        clock.observe(&game("soccer", 61, GameStatus::InProgress), later);
+        assert_eq!(clock.minute_at(later), Some(64));
+    }
+
+    #[test]
+    fn basketball_runs_slower_than_wall_clock() {
+        let mut clock = GameClock::new("basketball");
+        clock.observe(&game("basketball", 30, GameStatus::InProgress), t0());
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(10)), Some(34));
+        assert_eq!(period_for_minute("basketball", 30), Some(3));
+        assert_eq!(period_for_minute("soccer", 45), Some(1));
+    }
+
+    #[test]
+    fn innings_are_not_extrapolated() {
+        let mut clock = GameClock::new("baseball");
+        clock.observe(&game("baseball", 5, GameStatus::InProgress), t0());
+        assert_eq!(clock.minute_at(t0() + Duration::minutes(30)), Some(5));
+        assert_eq!(period_for_minute("baseball", 5), None);
+        assert_eq!(game_progress("baseball", 9), Some(1.0));
+        assert_eq!(game_progress("soccer", 45), Some(0.5));
+        assert_eq!(game_progress("tennis", 2), None);
+    }
+}
@@ -0,0 +1,387 @@
+//! Cross-provider fixture linking.
+//!
+//! Every score provider uses its own event-ID scheme (`allsports_11205`,
+//! `polymarket_arsenal-vs-chelsea`, TheSportsDB numeric IDs, …), so the same
+//! game never shares an ID across feeds.  The [`FixtureLinker`] maps each
+//! `(provider, provider_event_id)` pair onto a canonical fixture using team
+//! names, sport/league and scheduled start time.  The score monitor groups
+//! provider snapshots by that canonical ID, which is what makes consensus
+//! counting and cross-provider dedup meaningful.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+use crate::db::models::LiveGame;
+
+/// Minimum per-team similarity for two provider snapshots to be the same game.
+const TEAM_MATCH_THRESHOLD: f64 = 0.99;
+
+/// Tokens that carry no team identity (club suffixes, league tags leaked
+/// into Polymarket slugs, …).
+const TEAM_NOISE_TOKENS: &[&str] = &[
+    "fc", "cf", "afc", "sc", "ac", "the", "club", "epl", "mls", "nba", "nfl", "nhl", "mlb",
+];
+
+/// Canonical fixture known to the linker.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// Canonical fixture ID (the first provider event ID seen for the game).
+    pub id: String,
+    pub sport: String,
+    pub league: String,
+    pub start_time: Option<DateTime<Utc>>,
+    home_tokens: Vec<String>,
+    away_tokens: Vec<String>,
+    league_tokens: Vec<String>,
+    providers: HashSet<String>,
+    last_seen: DateTime<Utc>,
+}
+
+/// Maps provider-specific event IDs onto canonical fixtures.
+pub struct FixtureLinker {
+    fixtures: HashMap<String, Fixture>,
+    /// (provider, provider event ID) → canonical fixture ID
+    links: HashMap<(String, String), String>,
+    /// Maximum start-time difference for two snapshots to be the same game.
+    start_tolerance: chrono::Duration,
+}
+
+impl FixtureLinker {
+    pub fn new(start_tolerance: chrono::Duration) -> Self {
+        FixtureLinker {
+            fixtures: HashMap::new(),
+            links: HashMap::new(),
+            start_tolerance,
+        }
+    }
+
+    /// Resolve the canonical fixture ID for a provider snapshot, creating a
+    /// new fixture when no existing one matches.
+    pub fn link(&mut self, provider: &str, game: &LiveGame, now: DateTime<Utc>) -> String {
+        let key = (provider.to_string(), game.event_id.clone());
+        if let Some(fixture_id) = self.links.get(&key).cloned() {
+            if let Some(fixture) = self.fixtures.get_mut(&fixture_id) {
+                fixture.absorb(game, now);
+                return fixture_id;
+            }
+            self.links.remove(&key);
+        }
+
+        let home_tokens = team_tokens(&game.home_team);
+        let away_tokens = team_tokens(&game.away_team);
+        let league_tokens = team_tokens(&game.league);
+
+        let mut best: Option<(String, f64)> = None;
+        for fixture in self.fixtures.values() {
+            if fixture.providers.contains(provider) {
+                // A provider never reports the same game under two IDs.
+                continue;
+            }
+            if !sports_compatible(&fixture.sport, &game.sport) {
+                continue;
+            }
+            if let (Some(a), Some(b)) = (fixture.start_time, game.start_time) {
+                if (a - b).abs() > self.start_tolerance {
+                    continue;
+                }
+            }
+            let home_sim = token_similarity(&fixture.home_tokens, &home_tokens);
+            let away_sim = token_similarity(&fixture.away_tokens, &away_tokens);
+            if home_sim < TEAM_MATCH_THRESHOLD || away_sim < TEAM_MATCH_THRESHOLD {
+                continue;
+            }
+            let league_bonus = if token_similarity(&fixture.league_tokens, &league_tokens) > 0.0 {
+                0.1
+            } else {
+                0.0
+            };
+            let score = home_sim + away_sim + league_bonus;
+            if best.as_ref().is_none_or(|(_, s)| score > *s) {
+                best = Some((fixture.id.clone(), score));
+            }
+        }
+
+        let fixture_id = match best {
+            Some((id, _)) => id,
+            None => {
+                let id = game.event_id.clone();
+                self.fixtures.insert(
+                    id.clone(),
+                    Fixture {
+                        id: id.clone(),
+                        sport: game.sport.clone(),
+                        league: game.league.clone(),
+                        start_time: game.start_time,
+                        home_tokens,
+                        away_tokens,
+                        league_tokens,
+                        providers: HashSet::new(),
+                        last_seen: now,
+                    },
+                );
+                id
+            }
+        };
+
+        if let Some(fixture) = self.fixtures.get_mut(&fixture_id) {
+            fixture.providers.insert(provider.to_string());
+            fixture.absorb(game, now);
+        }
+        self.links.insert(key, fixture_id.clone());
+        fixture_id
+    }
+
+    /// Canonical fixture by ID.
+    pub fn fixture(&self, fixture_id: &str) -> Option<&Fixture> {
+        self.fixtures.get(fixture_id)
+    }
+
+    /// Drop fixtures (and their provider links) not seen since `cutoff`.
+    pub fn prune(&mut self, cutoff: DateTime<Utc>) {
+        self.fixtures.retain(|_, f| f.last_seen >= cutoff);
+        let fixtures = &self.fixtures;
+        self.links.retain(|_, id| fixtures.contains_key(id));
+    }
+}
+
+impl Fixture {
+    /// Fill in details that earlier providers could not supply.
+    fn absorb(&mut self, game: &LiveGame, now: DateTime<Utc>) {
+        self.last_seen = now;
+        if self.start_time.is_none() {
+            self.start_time = game.start_time;
+        }
+        if is_unknown(&self.sport) && !is_unknown(&game.sport) {
+            self.sport = game.sport.clone();
+            self.league = game.league.clone();
+            self.league_tokens = team_tokens(&game.league);
+        }
+    }
+}
+
+fn is_unknown(sport: &str) -> bool {
+    sport.is_empty() || sport.eq_ignore_ascii_case("unknown")
+}
+
+fn sports_compatible(a: &str, b: &str) -> bool {
+    is_unknown(a) || is_unknown(b) || a.eq_ignore_ascii_case(b)
+}
+
+/// Normalize a team (or league) name into identity-bearing tokens.
+/// "Arsenal FC" → ["arsenal"], "Man United" → ["man", "united"]
+fn team_tokens(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .filter(|t| !TEAM_NOISE_TOKENS.contains(t))
+        .map(|t| t.to_string())
+        .collect()
+}
+
+/// Two tokens match when equal, or when the shorter (≥ 3 chars) is a prefix
+/// of the longer ("man" / "manchester").
+fn tokens_match(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    short.len() >= 3 && long.starts_with(short)
+}
+
+/// Fraction of the shorter name's tokens that match a token of the other.
+fn token_similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let matched = short
+        .iter()
+        .filter(|s| long.iter().any(|l| tokens_match(s, l)))
+        .count();
+    matched as f64 / short.len() as f64
+}
+
+/// Parse a provider kick-off timestamp.
+///
+/// Accepts RFC 3339 (`2024-03-10T15:00:00+00:00`), naive UTC date-times
+/// (`2024-03-10T15:00:00`, `2024-03-10 15:00`) and unix seconds.
+pub fn parse_kickoff(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    for fmt in [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Some(naive.and_utc());
+        }
+    }
+    if let Ok(secs) = s.parse::<i64>() {
+        return DateTime::from_timestamp(secs, 0);
+    }
+    None
+}
+
+/// Combine a separate date (`2024-03-10`) and time (`15:00`) into a kick-off.
+pub fn parse_kickoff_parts(date: &str, time: &str) -> Option<DateTime<Utc>> {
+    let time = time.trim();
+    if time.is_empty() {
+        let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()?;
+        return day.and_hms_opt(0, 0, 0).map(|n| n.and_utc());
+    }
+    parse_kickoff(&format!("{} {}", date.trim(), time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::GameStatus;
+    use chrono::TimeZone;
+
+    fn game(id: &str, sport: &str, home: &str, away: &str, start_hour: Option<u32>) -> LiveGame {
+        LiveGame {
+            event_id: id.into(),
+            sport: sport.into(),
+            league: "Premier League".into(),
+            home_team: home.into(),
+            away_team: away.into(),
+            home_score: 0,
+            away_score: 0,
+            minute: Some(10),
+            status: GameStatus::InProgress,
+            start_time: start_hour.map(|h| Utc.with_ymd_and_hms(2024, 3, 10, h, 0, 0).unwrap()),
+            period: None,
+            added_time: None,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            power_play: None,
+        }
+    }
+
+    fn linker() -> FixtureLinker {
+        FixtureLinker::new(chrono::Duration::minutes(90))
+    }
+
+    #[test]
+    fn links_same_game_across_providers() {
+        let mut l = linker();
+        let now = Utc::now();
+        let a = l.link(
+            "TheSportsDB",
+            &game("123", "soccer", "Manchester United", "Chelsea", Some(15)),
+            now,
+        );
+        let b = l.link(
+            "AllSportsAPI-WS",
+            &game(
+                "allsports_9",
+                "soccer",
+                "Man United",
+                "Chelsea FC",
+                Some(15),
+            ),
+            now,
+        );
+        let c = l.link(
+            "Polymarket-Sports-WS",
+            &game(
+                "polymarket_x",
+                "unknown",
+                "Manchester United",
+                "Chelsea Epl",
+                None,
+            ),
+            now,
+        );
+        assert_eq!(a, "123");
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        // Repeated lookups are stable.
+        assert_eq!(
+            l.link(
+                "AllSportsAPI-WS",
+                &game(
+                    "allsports_9",
+                    "soccer",
+                    "Man United",
+                    "Chelsea FC",
+                    Some(15)
+                ),
+                now,
+            ),
+            a
+        );
+    }
+
+    #[test]
+    fn does_not_link_different_teams_or_kickoffs() {
+        let mut l = linker();
+        let now = Utc::now();
+        let a = l.link(
+            "p1",
+            &game("1", "soccer", "Manchester United", "Chelsea", Some(15)),
+            now,
+        );
+        let city = l.link(
+            "p2",
+            &game("2", "soccer", "Manchester City", "Chelsea", Some(15)),
+            now,
+        );
+        let rematch = l.link(
+            "p3",
+            &game("3", "soccer", "Manchester United", "Chelsea", Some(20)),
+            now,
+        );
+        assert_ne!(a, city);
+        assert_ne!(a, rematch);
+    }
+
+    #[test]
+    fn same_provider_ids_stay_separate() {
+        let mut l = linker();
+        let now = Utc::now();
+        let a = l.link("p1", &game("1", "soccer", "Arsenal", "Chelsea", None), now);
+        let b = l.link("p1", &game("2", "soccer", "Arsenal", "Chelsea", None), now);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn unknown_sport_is_upgraded_and_pruned() {
+        let mut l = linker();
+        let now = Utc::now();
+        let id = l.link(
+            "pm",
+            &game("pm_1", "unknown", "Arsenal", "Chelsea", None),
+            now,
+        );
+        l.link(
+            "p2",
+            &game("2", "soccer", "Arsenal", "Chelsea", Some(15)),
+            now,
+        );
+        let fixture = l.fixture(&id).unwrap();
+        assert_eq!(fixture.sport, "soccer");
+        assert!(fixture.start_time.is_some());
+
+        l.prune(now + chrono::Duration::seconds(1));
+        assert!(l.fixture(&id).is_none());
+    }
+
+    #[test]
+    fn parses_provider_kickoff_formats() {
+        let expected = Utc.with_ymd_and_hms(2024, 3, 10, 15, 0, 0).unwrap();
+        assert_eq!(parse_kickoff("2024-03-10T15:00:00+00:00"), Some(expected));
+        assert_eq!(parse_kickoff("2024-03-10T15:00:00"), Some(expected));
+        assert_eq!(parse_kickoff("2024-03-10 15:00"), Some(expected));
+        assert_eq!(parse_kickoff("1710082800"), Some(expected));
+        assert_eq!(parse_kickoff_parts("2024-03-10", "15:00"), Some(expected));
+        assert_eq!(parse_kickoff("not a date"), None);
+    }
+}
@@ -1,9 +1,15 @@
+pub mod clock;
+pub mod fixtures;
 pub mod provider;
 pub mod sports;
+pub mod state;
 pub mod websocket;
 
+pub use clock::GameClock;
+pub use fixtures::FixtureLinker;
 pub use provider::ScoreProvider;
 pub use sports::{detect_score_change, TheSportsDB};
+pub use state::{carry_red_cards, detect_state_changes, GameStateChange, GameStateEvent};
 pub use websocket::{WebSocketProvider, WebSocketProviderConfig};
 
 use chrono::Utc;
@@ -11,10 +17,21 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::db::models::{LiveGame, ScoreEvent};
 
+/// Events emitted by the score monitor.
+#[derive(Debug, Clone)]
+pub enum MonitorEvent {
+    /// Scoreline change (goal, basket, correction, …)
+    Score(ScoreEvent, LiveGame),
+    /// Non-score state transition (red card, period, final whistle, …)
+    State(GameStateEvent, LiveGame),
+    /// Every merged game of a poll, sent after that poll's events
+    Snapshot(Vec<LiveGame>),
+}
+
 fn provider_weight(name: &str) -> f64 {
     let n = name.to_lowercase();
     if n.contains("polymarket") {
@@ -28,11 +45,12 @@ fn provider_weight(name: &str) -> f64 {
     }
 }
 
-fn consensus_score_key(game: &LiveGame) -> (i32, i32, Option<i32>, String) {
+/// Consensus compares scoreline and status only: providers report the clock
+/// with different lag, so including the minute would split agreeing feeds.
+fn consensus_score_key(game: &LiveGame) -> (i32, i32, String) {
     (
         game.home_score,
         game.away_score,
-        game.minute,
         format!("{:?}", game.status),
     )
 }
@@ -43,8 +61,7 @@ fn select_consensus_game(candidates: Vec<(String, LiveGame)>) -> Option<(String,
     }
 
     // Group by score snapshot key and count agreement.
-    let mut groups: HashMap<(i32, i32, Option<i32>, String), Vec<(String, LiveGame)>> =
-        HashMap::new();
+    let mut groups: HashMap<(i32, i32, String), Vec<(String, LiveGame)>> = HashMap::new();
     for (provider, game) in candidates {
         groups
             .entry(consensus_score_key(&game))
@@ -84,15 +101,19 @@ fn select_consensus_game(candidates: Vec<(String, LiveGame)>) -> Option<(String,
 }
 
 /// Spawns a background task that polls live scores from **multiple providers
-/// concurrently** at the configured interval and sends `ScoreEvent`s through
-/// the returned channel whenever a score change is detected.
+/// concurrently** at the configured interval and sends [`MonitorEvent`]s
+/// through the returned channel whenever a score change or game-state
+/// transition is detected.
 ///
 /// Multiple providers race in parallel; results are merged so the bot gets
-/// the union of all games with the freshest data.
+/// the union of all games with the freshest data.  Provider event IDs are
+/// linked to canonical fixtures (see [`FixtureLinker`]) so the same game
+/// reported by several feeds is merged and emitted under one `event_id`.
 pub fn start_score_monitor(
     providers: Vec<Arc<dyn ScoreProvider>>,
     poll_interval: Duration,
-) -> mpsc::Receiver<(ScoreEvent, LiveGame)> {
+    fixture_start_tolerance: chrono::Duration,
+) -> mpsc::Receiver<MonitorEvent> {
     let (tx, rx) = mpsc::channel(1024);
 
     tokio::spawn(async move {
@@ -109,6 +130,8 @@ pub fn start_score_monitor(
         let mut last_seen: HashMap<String, tokio::time::Instant> = HashMap::new();
         let provider_timeout = poll_interval.min(Duration::from_secs(2));
         let stale_after = Duration::from_secs(6 * 60 * 60);
+        let mut linker = FixtureLinker::new(fixture_start_tolerance);
+        let mut clocks: HashMap<String, GameClock> = HashMap::new();
         let mut interval = tokio::time::interval(poll_interval);
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
@@ -136,14 +159,18 @@ pub fn start_score_monitor(
 
             let results = futures_util::future::join_all(fetch_futures).await;
 
-            // Merge results with provider-consensus selection.
+            // Link provider events to canonical fixtures, then merge with
+            // provider-consensus selection.
+            let link_time = Utc::now();
             let mut by_event: HashMap<String, Vec<(String, LiveGame)>> = HashMap::new();
             for (provider_name, result) in results {
                 match result {
                     Ok(games) => {
-                        for game in games {
+                        for mut game in games {
+                            let fixture_id = linker.link(&provider_name, &game, link_time);
+                            game.event_id = fixture_id.clone();
                             by_event
-                                .entry(game.event_id.clone())
+                                .entry(fixture_id)
                                 .or_default()
                                 .push((provider_name.clone(), game));
                         }
@@ -156,7 +183,35 @@ pub fn start_score_monitor(
 
             let mut merged: HashMap<String, (String, i32, LiveGame)> = HashMap::new();
             for (event_id, candidates) in by_event {
-                if let Some((provider, game, consensus_count)) = select_consensus_game(candidates) {
+                if let Some((provider, mut game, consensus_count)) =
+                    select_consensus_game(candidates)
+                {
+                    // Fill details the winning feed lacks (e.g. Polymarket
+                    // reports no sport) from the linked fixture.
+                    if let Some(fixture) = linker.fixture(&event_id) {
+                        if game.sport == "unknown" {
+                            game.sport = fixture.sport.clone();
+                            game.league = fixture.league.clone();
+                        }
+                        if game.start_time.is_none() {
+                            game.start_time = fixture.start_time;
+                        }
+                    }
+                    if let Some(prev) = prev_snapshot.get(&event_id) {
+                        carry_red_cards(prev, &mut game);
+                    }
+                    // Replace the feed's (possibly stale) minute with the
+                    // extrapolated game clock.
+                    let clock = clocks
+                        .entry(event_id.clone())
+                        .or_insert_with(|| GameClock::new(&game.sport));
+                    clock.observe(&game, link_time);
+                    if let Some(minute) = clock.minute_at(link_time) {
+                        game.minute = Some(minute);
+                        if game.period.is_none() {
+                            game.period = clock::period_for_minute(&game.sport, minute);
+                        }
+                    }
                     merged.insert(event_id, (provider, consensus_count, game));
                 }
             }
@@ -187,10 +242,39 @@ pub fn start_score_monitor(
                             ev.league, ev.event_id, ev.home_score, ev.away_score, ev.event_type
                         );
                         // Log when events are dropped instead of silently ignoring
-                        if let Err(e) = tx.try_send((ev, game.clone())) {
+                        if let Err(e) = tx.try_send(MonitorEvent::Score(ev, game.clone())) {
                             error!("Score event channel full, event DROPPED: {}", e);
                         }
                     }
+                    for change in detect_state_changes(prev, game) {
+                        let ev = GameStateEvent {
+                            event_id: game.event_id.clone(),
+                            source_provider: Some(provider.clone()),
+                            league: game.league.clone(),
+                            home_team: game.home_team.clone(),
+                            away_team: game.away_team.clone(),
+                            home_score: game.home_score,
+                            away_score: game.away_score,
+                            minute: game.minute,
+                            change,
+                        };
+                        info!(
+                            "Game state change: {} {} {} ({}-{})",
+                            ev.league, ev.event_id, ev.change, ev.home_score, ev.away_score
+                        );
+                        if let Err(e) = tx.try_send(MonitorEvent::State(ev, game.clone())) {
+                            error!("Score event channel full, state event DROPPED: {}", e);
+                        }
+                    }
+                }
+            }
+
+            // Keeps the engine's fixture clocks fresh between events; a
+            // dropped snapshot is superseded by the next poll.
+            let games: Vec<LiveGame> = merged.values().map(|(_, _, g)| g.clone()).collect();
+            if !games.is_empty() {
+                if let Err(e) = tx.try_send(MonitorEvent::Snapshot(games)) {
+                    debug!("Score event channel full, snapshot skipped: {}", e);
                 }
             }
 
@@ -203,6 +287,7 @@ pub fn start_score_monitor(
             }
             // Prune finished games to prevent unbounded snapshot growth
             prev_snapshot.retain(|_, g| g.status != crate::db::models::GameStatus::Finished);
+            clocks.retain(|id, _| prev_snapshot.contains_key(id));
             let stale_ids: Vec<String> = last_seen
                 .iter()
                 .filter_map(|(id, seen)| {
@@ -217,6 +302,7 @@ pub fn start_score_monitor(
                 last_seen.remove(&id);
                 prev_snapshot.remove(&id);
             }
+            linker.prune(link_time - chrono::Duration::from_std(stale_after).unwrap_or_default());
         }
     });
 
@@ -228,6 +314,7 @@ pub fn start_score_monitor(
 pub fn start_score_monitor_single(
     provider: Arc<dyn ScoreProvider>,
     poll_interval: Duration,
-) -> mpsc::Receiver<(ScoreEvent, LiveGame)> {
-    start_score_monitor(vec![provider], poll_interval)
+    fixture_start_tolerance: chrono::Duration,
+) -> mpsc::Receiver<MonitorEvent> {
+    start_score_monitor(vec![provider], poll_interval, fixture_start_tolerance)
 }
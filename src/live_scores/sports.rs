@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use reqwest::Client;
 use tracing::debug;
 
+use super::fixtures::{parse_kickoff, parse_kickoff_parts};
 use super::provider::ScoreProvider;
 use crate::db::models::{GameStatus, LiveGame};
 
@@ -110,6 +111,14 @@ fn parse_livescore_response(raw: &serde_json::Value) -> Result<Vec<LiveGame>> {
             let status_str = ev["strStatus"].as_str().unwrap_or("In Progress");
             let status = TheSportsDB::status_from_str(status_str);
 
+            let start_time = ev["strTimestamp"]
+                .as_str()
+                .and_then(parse_kickoff)
+                .or_else(|| {
+                    let date = ev["dateEvent"].as_str()?;
+                    parse_kickoff_parts(date, ev["strTime"].as_str().unwrap_or(""))
+                });
+
             Some(LiveGame {
                 event_id,
                 sport,
@@ -120,6 +129,12 @@ fn parse_livescore_response(raw: &serde_json::Value) -> Result<Vec<LiveGame>> {
                 away_score,
                 minute,
                 status,
+                start_time,
+                period: None,
+                added_time: None,
+                home_red_cards: count_card_list(ev["strHomeRedCards"].as_str()),
+                away_red_cards: count_card_list(ev["strAwayRedCards"].as_str()),
+                power_play: None,
             })
         })
         .collect();
@@ -127,6 +142,12 @@ fn parse_livescore_response(raw: &serde_json::Value) -> Result<Vec<LiveGame>> {
     Ok(games)
 }
 
+/// Count entries in a TheSportsDB card list like "45':Player A;78':Player B;".
+fn count_card_list(list: Option<&str>) -> i32 {
+    list.map(|l| l.split(';').filter(|p| !p.trim().is_empty()).count() as i32)
+        .unwrap_or(0)
+}
+
 /// Detect changes between two game snapshots and return a description.
 /// Returns `Some(event_type)` if a scoreline change is detected.
 pub fn detect_score_change(prev: &LiveGame, curr: &LiveGame) -> Option<String> {
@@ -221,6 +242,12 @@ mod tests {
             away_score: away,
             minute: Some(45),
             status: GameStatus::InProgress,
+            start_time: None,
+            period: None,
+            added_time: None,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            power_play: None,
         }
     }
 
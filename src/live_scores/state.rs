@@ -0,0 +1,233 @@
+//! Game-state change detection beyond scoreline changes.
+//!
+//! Red cards, period boundaries, half-time, injury time, power plays and the
+//! final whistle all move win probability without touching the score.  The
+//! score monitor diffs consecutive snapshots with [`detect_state_changes`]
+//! and emits a [`GameStateEvent`] per transition.
+
+use std::fmt;
+
+use crate::db::models::{GameStatus, LiveGame, TeamSide};
+
+/// A typed non-score transition in a live game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameStateChange {
+    RedCard { side: TeamSide },
+    PeriodStart { period: i32 },
+    PeriodEnd { period: i32 },
+    HalfTime,
+    InjuryTimeStart { added_minutes: i32 },
+    PowerPlayStart { side: TeamSide },
+    PowerPlayEnd { side: TeamSide },
+    MatchFinished,
+}
+
+impl fmt::Display for GameStateChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameStateChange::RedCard { side } => write!(f, "red_card_{:?}", side),
+            GameStateChange::PeriodStart { period } => write!(f, "period_{}_start", period),
+            GameStateChange::PeriodEnd { period } => write!(f, "period_{}_end", period),
+            GameStateChange::HalfTime => write!(f, "half_time"),
+            GameStateChange::InjuryTimeStart { added_minutes } => {
+                write!(f, "injury_time_+{}", added_minutes)
+            }
+            GameStateChange::PowerPlayStart { side } => write!(f, "power_play_{:?}_start", side),
+            GameStateChange::PowerPlayEnd { side } => write!(f, "power_play_{:?}_end", side),
+            GameStateChange::MatchFinished => write!(f, "match_finished"),
+        }
+    }
+}
+
+/// A game-state transition detected by the score monitor.
+#[derive(Debug, Clone)]
+pub struct GameStateEvent {
+    /// Canonical fixture ID (same as `ScoreEvent.event_id`)
+    pub event_id: String,
+    pub source_provider: Option<String>,
+    pub league: String,
+    pub home_team: String,
+    pub away_team: String,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub minute: Option<i32>,
+    pub change: GameStateChange,
+}
+
+/// Keep red-card counts from going down between snapshots of a fixture.
+///
+/// Cards are never rescinded in play, but consensus may switch to a feed
+/// that reports fewer of them; without this the next switch back would
+/// raise the same red card again.
+pub fn carry_red_cards(prev: &LiveGame, curr: &mut LiveGame) {
+    curr.home_red_cards = curr.home_red_cards.max(prev.home_red_cards);
+    curr.away_red_cards = curr.away_red_cards.max(prev.away_red_cards);
+}
+
+/// Detect non-score transitions between two snapshots of the same game.
+pub fn detect_state_changes(prev: &LiveGame, curr: &LiveGame) -> Vec<GameStateChange> {
+    let mut changes = Vec::new();
+
+    if curr.status == GameStatus::Finished {
+        if prev.status != GameStatus::Finished {
+            changes.push(GameStateChange::MatchFinished);
+        }
+        return changes;
+    }
+
+    for _ in prev.home_red_cards..curr.home_red_cards {
+        changes.push(GameStateChange::RedCard {
+            side: TeamSide::Home,
+        });
+    }
+    for _ in prev.away_red_cards..curr.away_red_cards {
+        changes.push(GameStateChange::RedCard {
+            side: TeamSide::Away,
+        });
+    }
+
+    let went_to_half_time =
+        prev.status == GameStatus::InProgress && curr.status == GameStatus::HalfTime;
+    if went_to_half_time {
+        if let Some(period) = prev.period {
+            changes.push(GameStateChange::PeriodEnd { period });
+        }
+        changes.push(GameStateChange::HalfTime);
+    }
+
+    if prev.status == GameStatus::NotStarted && curr.status == GameStatus::InProgress {
+        changes.push(GameStateChange::PeriodStart {
+            period: curr.period.unwrap_or(1),
+        });
+    } else if let (Some(p), Some(c)) = (prev.period, curr.period) {
+        if c > p {
+            // The half-time transition already closed the previous period.
+            if prev.status != GameStatus::HalfTime && !went_to_half_time {
+                changes.push(GameStateChange::PeriodEnd { period: p });
+            }
+            changes.push(GameStateChange::PeriodStart { period: c });
+        }
+    }
+
+    if let (None, Some(added_minutes)) = (prev.added_time, curr.added_time) {
+        changes.push(GameStateChange::InjuryTimeStart { added_minutes });
+    }
+
+    if prev.power_play != curr.power_play {
+        if let Some(side) = prev.power_play {
+            changes.push(GameStateChange::PowerPlayEnd { side });
+        }
+        if let Some(side) = curr.power_play {
+            changes.push(GameStateChange::PowerPlayStart { side });
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(minute: i32, period: i32, status: GameStatus) -> LiveGame {
+        LiveGame {
+            event_id: "1".into(),
+            sport: "soccer".into(),
+            league: "Premier League".into(),
+            home_team: "Arsenal".into(),
+            away_team: "Chelsea".into(),
+            home_score: 0,
+            away_score: 0,
+            minute: Some(minute),
+            status,
+            start_time: None,
+            period: Some(period),
+            added_time: None,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            power_play: None,
+        }
+    }
+
+    #[test]
+    fn no_changes_for_identical_snapshots() {
+        let g = game(30, 1, GameStatus::InProgress);
+        assert!(detect_state_changes(&g, &g).is_empty());
+    }
+
+    #[test]
+    fn detects_red_cards_and_injury_time() {
+        let prev = game(44, 1, GameStatus::InProgress);
+        let mut curr = prev.clone();
+        curr.away_red_cards = 1;
+        curr.added_time = Some(3);
+        assert_eq!(
+            detect_state_changes(&prev, &curr),
+            vec![
+                GameStateChange::RedCard {
+                    side: TeamSide::Away
+                },
+                GameStateChange::InjuryTimeStart { added_minutes: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn red_cards_are_raised_once_across_feed_switches() {
+        let prev = game(50, 1, GameStatus::InProgress);
+        let mut carded = prev.clone();
+        carded.home_red_cards = 1;
+        // A feed without card data wins consensus, then the carded one again.
+        let mut cardless = prev.clone();
+        carry_red_cards(&carded, &mut cardless);
+        assert_eq!(cardless.home_red_cards, 1);
+        assert!(detect_state_changes(&carded, &cardless).is_empty());
+        let mut again = carded.clone();
+        carry_red_cards(&cardless, &mut again);
+        assert!(detect_state_changes(&cardless, &again).is_empty());
+    }
+
+    #[test]
+    fn detects_half_time_then_second_half() {
+        let first = game(45, 1, GameStatus::InProgress);
+        let ht = game(45, 1, GameStatus::HalfTime);
+        assert_eq!(
+            detect_state_changes(&first, &ht),
+            vec![
+                GameStateChange::PeriodEnd { period: 1 },
+                GameStateChange::HalfTime
+            ]
+        );
+        let second = game(46, 2, GameStatus::InProgress);
+        assert_eq!(
+            detect_state_changes(&ht, &second),
+            vec![GameStateChange::PeriodStart { period: 2 }]
+        );
+    }
+
+    #[test]
+    fn detects_power_play_and_final_whistle() {
+        let prev = game(30, 2, GameStatus::InProgress);
+        let mut pp = prev.clone();
+        pp.power_play = Some(TeamSide::Home);
+        assert_eq!(
+            detect_state_changes(&prev, &pp),
+            vec![GameStateChange::PowerPlayStart {
+                side: TeamSide::Home
+            }]
+        );
+        assert_eq!(
+            detect_state_changes(&pp, &prev),
+            vec![GameStateChange::PowerPlayEnd {
+                side: TeamSide::Home
+            }]
+        );
+
+        let finished = game(90, 2, GameStatus::Finished);
+        assert_eq!(
+            detect_state_changes(&prev, &finished),
+            vec![GameStateChange::MatchFinished]
+        );
+        assert!(detect_state_changes(&finished, &finished).is_empty());
+    }
+}
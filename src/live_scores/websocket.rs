@@ -29,6 +29,7 @@ use tokio::sync::RwLock;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{error, info, warn};
 
+use super::fixtures::{parse_kickoff, parse_kickoff_parts};
 use super::provider::ScoreProvider;
 use crate::db::models::{GameStatus, LiveGame};
 
@@ -99,6 +100,9 @@ impl ScoreProvider for WebSocketProvider {
     }
 }
 
+/// How long finished games stay in the snapshot after the final whistle.
+const FINISHED_RETENTION: std::time::Duration = std::time::Duration::from_secs(120);
+
 /// Persistent WebSocket connection loop with auto-reconnect and exponential
 /// backoff.
 async fn ws_connection_loop(
@@ -111,6 +115,7 @@ async fn ws_connection_loop(
 ) {
     let mut backoff_secs = 1u64;
     let max_backoff = 30u64;
+    let mut finished_at: HashMap<String, tokio::time::Instant> = HashMap::new();
 
     loop {
         info!("[{}] Connecting to WebSocket: {}", name, url);
@@ -147,10 +152,19 @@ async fn ws_connection_loop(
                                     let games = parse_fn(&text);
                                     if !games.is_empty() {
                                         let mut snap = snapshot.write().await;
+                                        let now = tokio::time::Instant::now();
                                         for game in games {
+                                            if game.status == GameStatus::Finished {
+                                                finished_at.entry(game.event_id.clone()).or_insert(now);
+                                            }
                                             snap.insert(game.event_id.clone(), game);
                                         }
-                                        snap.retain(|_, g| g.status != GameStatus::Finished);
+                                        // Keep finished games long enough for the
+                                        // monitor to observe the final whistle.
+                                        finished_at.retain(|_, at| now.duration_since(*at) < FINISHED_RETENTION);
+                                        snap.retain(|id, g| {
+                                            g.status != GameStatus::Finished || finished_at.contains_key(id)
+                                        });
                                     }
                                 }
                                 Some(Ok(Message::Ping(data))) => {
@@ -266,6 +280,13 @@ pub fn parse_allsportsapi(text: &str) -> Vec<LiveGame> {
                 .unwrap_or("");
 
             let (status, minute) = parse_allsports_status(status_str);
+            let added_time = parse_added_time(status_str);
+
+            // "cards": [{"home_fault": "...", "card": "red card", "away_fault": ""}, …]
+            let (home_red_cards, away_red_cards) = ev
+                .get("cards")
+                .map(count_allsports_red_cards)
+                .unwrap_or((0, 0));
 
             // Classify sport from league name heuristics or country
             let sport = classify_sport_from_league(&league_name);
@@ -276,6 +297,14 @@ pub fn parse_allsportsapi(text: &str) -> Vec<LiveGame> {
                 return None;
             }
 
+            let start_time = ev
+                .get("event_date")
+                .and_then(|v| v.as_str())
+                .and_then(|date| {
+                    let time = ev.get("event_time").and_then(|v| v.as_str()).unwrap_or("");
+                    parse_kickoff_parts(date, time)
+                });
+
             Some(LiveGame {
                 event_id: format!("allsports_{}", event_id),
                 sport,
@@ -286,6 +315,12 @@ pub fn parse_allsportsapi(text: &str) -> Vec<LiveGame> {
                 away_score,
                 minute,
                 status,
+                start_time,
+                period: None,
+                added_time,
+                home_red_cards,
+                away_red_cards,
+                power_play: None,
             })
         })
         .collect()
@@ -342,6 +377,13 @@ pub fn parse_polymarket_sports(text: &str) -> Vec<LiveGame> {
 
             // Try to extract minute from period (e.g., "Q3 5:42" or "75'")
             let minute = extract_minute_from_period(period);
+            let added_time = parse_added_time(period);
+
+            let start_time = ev
+                .get("startTime")
+                .or_else(|| ev.get("gameStartTime"))
+                .and_then(|v| v.as_str())
+                .and_then(parse_kickoff);
 
             Some(LiveGame {
                 event_id: format!("polymarket_{}", slug),
@@ -353,6 +395,12 @@ pub fn parse_polymarket_sports(text: &str) -> Vec<LiveGame> {
                 away_score,
                 minute,
                 status,
+                start_time,
+                period: parse_period_label(period),
+                added_time,
+                home_red_cards: 0,
+                away_red_cards: 0,
+                power_play: None,
             })
         })
         .collect()
@@ -379,6 +427,12 @@ fn parse_allsports_status(status: &str) -> (GameStatus, Option<i32>) {
     if let Ok(minute) = status.parse::<i32>() {
         return (GameStatus::InProgress, Some(minute));
     }
+    // Stoppage time: "45+2" → minute 45
+    if let Some((base, _)) = status.split_once('+') {
+        if let Ok(minute) = base.trim().parse::<i32>() {
+            return (GameStatus::InProgress, Some(minute));
+        }
+    }
     match status.to_lowercase().as_str() {
         "finished" | "ft" | "after pen." | "after extra time" => (GameStatus::Finished, None),
         "half time" | "ht" => (GameStatus::HalfTime, None),
@@ -444,6 +498,12 @@ fn extract_minute_from_period(period: &str) -> Option<i32> {
     if let Ok(m) = period.trim().trim_end_matches('\'').parse::<i32>() {
         return Some(m);
     }
+    // Stoppage time: "90+3'" → 90
+    if let Some((base, _)) = period.trim().split_once('+') {
+        if let Ok(m) = base.trim().parse::<i32>() {
+            return Some(m);
+        }
+    }
     // Try last token
     if let Some(last) = period.split_whitespace().last() {
         if let Ok(m) = last.trim_end_matches('\'').parse::<i32>() {
@@ -453,6 +513,51 @@ fn extract_minute_from_period(period: &str) -> Option<i32> {
     None
 }
 
+/// Added minutes from a stoppage-time clock string: "45+2" → 2, "90+3'" → 3.
+fn parse_added_time(s: &str) -> Option<i32> {
+    let (_, added) = s.trim().split_once('+')?;
+    added.trim().trim_end_matches('\'').parse::<i32>().ok()
+}
+
+/// Period number from a period label.
+/// E.g., "Q3 5:42" → 3, "2H" → 2, "P1" → 1, "75'" → None
+fn parse_period_label(period: &str) -> Option<i32> {
+    let token = period.split_whitespace().next()?.to_uppercase();
+    if let Some(n) = token.strip_prefix('Q').or_else(|| token.strip_prefix('P')) {
+        return n.parse().ok();
+    }
+    if let Some(n) = token.strip_suffix('H') {
+        return n.parse().ok();
+    }
+    None
+}
+
+/// Count red cards per side from an AllSportsAPI `cards` array.
+fn count_allsports_red_cards(cards: &serde_json::Value) -> (i32, i32) {
+    let Some(cards) = cards.as_array() else {
+        return (0, 0);
+    };
+    let mut home = 0;
+    let mut away = 0;
+    for card in cards {
+        let kind = card.get("card").and_then(|c| c.as_str()).unwrap_or("");
+        if !kind.to_lowercase().contains("red") {
+            continue;
+        }
+        let is_set = |key: &str| {
+            card.get(key)
+                .and_then(|v| v.as_str())
+                .is_some_and(|v| !v.trim().is_empty())
+        };
+        if is_set("home_fault") {
+            home += 1;
+        } else if is_set("away_fault") {
+            away += 1;
+        }
+    }
+    (home, away)
+}
+
 // ── Legacy parsers (API-Football, BetsAPI) ───────────────────────────────────
 
 /// Parse function for API-Football v3 WebSocket events.
@@ -508,6 +613,25 @@ pub fn parse_api_football(text: &str) -> Vec<LiveGame> {
                 "FT" | "AET" | "PEN" => GameStatus::Finished,
                 _ => GameStatus::InProgress,
             };
+            let period = match short_status {
+                "1H" => Some(1),
+                "2H" => Some(2),
+                "ET" => Some(3),
+                "P" => Some(4),
+                _ => None,
+            };
+            // "extra": added minutes announced for the current half
+            let added_time = fixture
+                .get("status")
+                .and_then(|s| s.get("extra"))
+                .and_then(|e| e.as_i64())
+                .map(|e| e as i32);
+
+            let start_time = fixture
+                .get("timestamp")
+                .and_then(|t| t.as_i64())
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                .or_else(|| fixture.get("date")?.as_str().and_then(parse_kickoff));
 
             Some(LiveGame {
                 event_id: format!("apifootball_{}", event_id),
@@ -519,6 +643,12 @@ pub fn parse_api_football(text: &str) -> Vec<LiveGame> {
                 away_score,
                 minute: elapsed,
                 status,
+                start_time,
+                period,
+                added_time,
+                home_red_cards: 0,
+                away_red_cards: 0,
+                power_play: None,
             })
         })
         .collect()
@@ -600,6 +730,13 @@ pub fn parse_betsapi(text: &str) -> Vec<LiveGame> {
                     .or_else(|| tm.as_i64().map(|v| v as i32))
             });
 
+            // BetsAPI reports the scheduled start as unix seconds in "time".
+            let start_time = ev.get("time").and_then(|t| {
+                t.as_str()
+                    .and_then(parse_kickoff)
+                    .or_else(|| chrono::DateTime::from_timestamp(t.as_i64()?, 0))
+            });
+
             Some(LiveGame {
                 event_id: format!("betsapi_{}", event_id),
                 sport,
@@ -610,6 +747,12 @@ pub fn parse_betsapi(text: &str) -> Vec<LiveGame> {
                 away_score,
                 minute,
                 status,
+                start_time,
+                period: None,
+                added_time: None,
+                home_red_cards: 0,
+                away_red_cards: 0,
+                power_play: None,
             })
         })
         .collect()
@@ -643,6 +786,27 @@ mod tests {
             (GameStatus::HalfTime, None)
         );
         assert_eq!(parse_allsports_status(""), (GameStatus::NotStarted, None));
+        assert_eq!(
+            parse_allsports_status("45+2"),
+            (GameStatus::InProgress, Some(45))
+        );
+    }
+
+    #[test]
+    fn test_parse_game_state_fields() {
+        assert_eq!(parse_added_time("45+2"), Some(2));
+        assert_eq!(parse_added_time("90+3'"), Some(3));
+        assert_eq!(parse_added_time("74"), None);
+        assert_eq!(parse_period_label("Q3 5:42"), Some(3));
+        assert_eq!(parse_period_label("2H"), Some(2));
+        assert_eq!(parse_period_label("75'"), None);
+
+        let cards = serde_json::json!([
+            {"home_fault": "A. Player", "card": "red card", "away_fault": ""},
+            {"home_fault": "", "card": "yellow card", "away_fault": "B. Player"},
+            {"home_fault": "", "card": "red card", "away_fault": "C. Player"}
+        ]);
+        assert_eq!(count_allsports_red_cards(&cards), (1, 1));
     }
 
     #[test]
@@ -1,5 +1,4 @@
 use anyhow::Result;
-use clap::Parser;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -7,17 +6,20 @@ use tracing::{error, info, warn};
 
 mod bot;
 mod config;
+mod config_file;
 mod dashboard;
 mod db;
 mod live_scores;
 mod polymarket;
 
+use bot::model_store::{self, ModelSource, ModelStore};
+use bot::win_probability;
 use bot::BotEngine;
-use config::Config;
-use dashboard::AppState;
+use config::{Command, Config, FitModelsArgs};
+use dashboard::{AdminTokens, AppState, DashboardAuth};
 use db::Database;
 use live_scores::ScoreProvider;
-use live_scores::{start_score_monitor, TheSportsDB};
+use live_scores::{start_score_monitor, MonitorEvent, TheSportsDB};
 use polymarket::{MarketCache, PolymarketClient};
 
 #[tokio::main]
@@ -30,7 +32,52 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    let config = Config::parse();
+    let (config, settings) = config_file::load()?;
+    if config.print_config {
+        print!("{}", config_file::render(&settings));
+        return config.validate();
+    }
+    match &config.command {
+        Some(Command::FitModels(args)) => return fit_models(&config, args),
+        Some(Command::PromoteModel(args)) => {
+            let artifact = ModelStore::new(&config.model_dir).promote(&args.version)?;
+            info!(
+                "Promoted model {} ({}, sha256 {})",
+                args.version,
+                artifact.path.display(),
+                artifact.sha256
+            );
+            return Ok(());
+        }
+        Some(Command::ListModels) => {
+            for a in ModelStore::new(&config.model_dir).list()? {
+                println!(
+                    "{}{}\t{}\t{}{}",
+                    if a.promoted { "* " } else { "  " },
+                    a.version,
+                    a.sha256,
+                    a.path.display(),
+                    if a.verified {
+                        ""
+                    } else {
+                        "\t(checksum missing or mismatched)"
+                    }
+                );
+            }
+            return Ok(());
+        }
+        Some(Command::HashPassword) => {
+            let mut password = String::new();
+            std::io::stdin().read_line(&mut password)?;
+            let password = password.trim_end_matches(['\r', '\n']);
+            if password.is_empty() {
+                anyhow::bail!("no password given on stdin");
+            }
+            println!("{}", dashboard::auth::PasswordHash::generate(password));
+            return Ok(());
+        }
+        None => {}
+    }
     config.validate()?;
 
     if config.dry_run {
@@ -42,6 +89,29 @@ async fn main() -> Result<()> {
         info!("🔴 LIVE mode – real trades WILL be placed on Polymarket");
     }
 
+    // Win-probability parameters: pinned file or the store's promoted
+    // version, reloaded in place when the files change or on SIGHUP.
+    let model_source = model_source(&config);
+    let model_checksum = match model_source.load()? {
+        Some(artifact) => {
+            info!(
+                "Win-probability model {} loaded from {}",
+                artifact.params.version,
+                artifact.path.display()
+            );
+            win_probability::install_params(artifact.params);
+            Some(artifact.sha256)
+        }
+        None => {
+            info!(
+                "No promoted model in {}; using built-in win-probability parameters",
+                config.model_dir
+            );
+            None
+        }
+    };
+    model_store::spawn_reloader(model_source, model_checksum);
+
     // Open database
     let db = Database::open(&config.database_path)?;
     info!("Database opened: {}", config.database_path);
@@ -101,14 +171,34 @@ async fn main() -> Result<()> {
     info!("Configured {} score provider(s)", score_providers.len());
 
     // Start the dashboard HTTP server
+    // Admin patches reach the engine through this channel.
+    let (config_tx, mut config_rx) = tokio::sync::watch::channel(config.clone());
+    // Manual controls from the admin API run on the engine in the main loop.
+    let (control_tx, mut control_rx) = tokio::sync::mpsc::channel(16);
     let dashboard_state = AppState {
         db: db.clone(),
         dry_run: config.dry_run,
         initial_balance: config.initial_balance,
+        config: config_tx,
+        admin_tokens: AdminTokens::parse(&config.admin_tokens),
+        profiles: bot::profiles::StrategyProfiles::from_config(&config)?,
+        auth: DashboardAuth::new(&config.dashboard_tokens, &config.dashboard_users)?,
+        control: control_tx,
     };
+    if !dashboard_state.auth.is_enabled() {
+        warn!("Dashboard authentication is disabled; set DASHBOARD_TOKENS or DASHBOARD_USERS");
+    }
     let app = dashboard::router(dashboard_state);
+    let tls = match (&config.tls_cert_path, &config.tls_key_path) {
+        (Some(cert), Some(key)) => Some(dashboard::tls::load_server_config(cert, key)?),
+        _ => None,
+    };
     let addr: SocketAddr = config.dashboard_addr.parse()?;
-    info!("Dashboard listening on http://{}", addr);
+    info!(
+        "Dashboard listening on {}://{}",
+        if tls.is_some() { "https" } else { "http" },
+        addr
+    );
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
     // Create the shared market cache — populated by background task, read
@@ -121,9 +211,11 @@ async fn main() -> Result<()> {
     let bot_polymarket = polymarket.clone();
     let bot_cache = market_cache.clone();
     let poll_interval = Duration::from_secs(config.poll_interval_secs);
+    let fixture_start_tolerance =
+        chrono::Duration::minutes(config.fixture_link_start_tolerance_mins);
 
     tokio::spawn(async move {
-        let mut rx = start_score_monitor(score_providers, poll_interval);
+        let mut rx = start_score_monitor(score_providers, poll_interval, fixture_start_tolerance);
 
         let mut engine = match BotEngine::new(
             bot_config.clone(),
@@ -137,6 +229,9 @@ async fn main() -> Result<()> {
                 return;
             }
         };
+        if let Err(e) = engine.reconcile_pending_entries().await {
+            error!("Failed to reconcile pending entry orders: {:#}", e);
+        }
 
         // Background market-discovery task — fetches all sports markets and
         // populates both SQLite (durable) and MarketCache (fast in-memory).
@@ -184,18 +279,46 @@ async fn main() -> Result<()> {
         let mut maintenance_interval = tokio::time::interval(Duration::from_secs(60 * 60));
         let mut calibration_interval =
             tokio::time::interval(Duration::from_secs(bot_config.calibration_interval_secs));
+        let mut drift_interval =
+            tokio::time::interval(Duration::from_secs(bot_config.drift_check_interval_secs));
+        let mut arbitrage_interval =
+            tokio::time::interval(Duration::from_secs(bot_config.arbitrage_scan_interval_secs));
 
         loop {
             tokio::select! {
-                Some((score_event, live_game)) = rx.recv() => {
-                    if let Err(e) = engine.on_score_event(&score_event, &live_game).await {
-                        error!("Error processing score event: {}", e);
+                Some(monitor_event) = rx.recv() => match monitor_event {
+                    MonitorEvent::Score(score_event, live_game) => {
+                        if let Err(e) = engine.on_score_event(&score_event, &live_game).await {
+                            error!("Error processing score event: {}", e);
+                        }
+                    }
+                    MonitorEvent::State(state_event, live_game) => {
+                        if let Err(e) = engine.on_game_state_event(&state_event, &live_game).await {
+                            error!("Error processing game state event: {}", e);
+                        }
+                    }
+                    MonitorEvent::Snapshot(games) => engine.on_fixture_snapshot(&games),
+                },
+                Some(request) = control_rx.recv() => {
+                    let outcome = engine
+                        .handle_control(&request.actor, request.command)
+                        .await
+                        .map_err(|e| format!("{:#}", e));
+                    let _ = request.reply.send(outcome);
+                }
+                Ok(()) = config_rx.changed() => {
+                    let config = config_rx.borrow_and_update().clone();
+                    if let Err(e) = engine.apply_config(config) {
+                        error!("Rejected runtime config change: {:#}", e);
                     }
                 }
                 _ = position_sweep_interval.tick() => {
                     if let Err(e) = engine.manage_positions().await {
                         error!("Error managing positions: {}", e);
                     }
+                    if let Err(e) = engine.refresh_maker_quotes().await {
+                        error!("Error refreshing maker quotes: {}", e);
+                    }
                 }
                 _ = maintenance_interval.tick() => {
                     match bot_db.prune_score_events(bot_config.score_events_retention_days) {
@@ -214,12 +337,78 @@ async fn main() -> Result<()> {
                         warn!("Calibration retraining failed: {}", e);
                     }
                 }
+                _ = drift_interval.tick() => {
+                    if let Err(e) = engine.check_drift().await {
+                        warn!("Drift check failed: {}", e);
+                    }
+                }
+                _ = arbitrage_interval.tick() => {
+                    if let Err(e) = engine.scan_arbitrage().await {
+                        warn!("Arbitrage scan failed: {}", e);
+                    }
+                }
             }
         }
     });
 
     // Run dashboard server (blocks until shutdown)
-    axum::serve(listener, app).await?;
+    match tls {
+        Some(tls) => dashboard::tls::serve(listener, app, tls).await,
+        None => {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await?
+        }
+    }
 
     Ok(())
 }
+
+/// `fit-models`: refit the win-probability parameters and write them out.
+fn fit_models(config: &Config, args: &FitModelsArgs) -> Result<()> {
+    let source = args.source.as_deref().unwrap_or(&config.database_path);
+    let rows = bot::model_fit::load_rows(source)?;
+    info!("Loaded {} game states from {}", rows.len(), source);
+
+    let base = model_source(config)
+        .load()?
+        .map(|artifact| artifact.params)
+        .unwrap_or_default();
+    info!("Refitting from model {}", base.version);
+    let report = bot::model_fit::fit(&rows, &base, args.min_samples);
+    for family in &report.families {
+        info!(
+            "{}: {} rows, log-loss {:.4} → {:.4}{}",
+            family.family,
+            family.samples,
+            family.logloss_before,
+            family.logloss_after,
+            if family.accepted {
+                ""
+            } else {
+                " (kept previous)"
+            }
+        );
+    }
+    let store = ModelStore::new(args.output_dir.as_deref().unwrap_or(&config.model_dir));
+    let path = store.save(&report.params)?;
+    info!(
+        "Saved model {} to {}",
+        report.params.version,
+        path.display()
+    );
+    if args.promote {
+        store.promote(&report.params.version)?;
+        info!("Promoted model {}", report.params.version);
+    }
+    Ok(())
+}
+
+fn model_source(config: &Config) -> ModelSource {
+    match &config.win_prob_params_path {
+        Some(path) => ModelSource::Pinned(path.into()),
+        None => ModelSource::Store(ModelStore::new(&config.model_dir)),
+    }
+}
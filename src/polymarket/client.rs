@@ -146,6 +146,101 @@ impl PolymarketClient {
         Ok(order_id)
     }
 
+    /// Rest a limit order on the CLOB book.  `size` is in shares.
+    pub async fn place_limit_order(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        side: &str,
+        size: f64,
+        price: f64,
+    ) -> Result<String> {
+        let api_key = self.api_key.as_deref().unwrap_or_default();
+
+        info!(
+            "Placing limit order: market={}, outcome={}, side={}, size={:.2}, price={:.3}",
+            market_id, outcome, side, size, price
+        );
+
+        let order = serde_json::json!({
+            "market": market_id,
+            "outcome": outcome,
+            "price": price,
+            "size": size,
+            "side": side,
+            "orderType": "GTC",
+        });
+
+        let url = format!("{}/order", self.clob_url);
+        let resp = self
+            .http
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&order)
+            .send()
+            .await
+            .context("Failed to place Polymarket limit order")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Limit order placement failed {}: {}", status, body);
+        }
+
+        let result: serde_json::Value = resp.json().await?;
+        let order_id = result["orderId"]
+            .as_str()
+            .or_else(|| result["orderID"].as_str())
+            .context("Limit order response missing order id")?
+            .to_string();
+        Ok(order_id)
+    }
+
+    /// Cancel a resting order.
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let api_key = self.api_key.as_deref().unwrap_or_default();
+
+        let url = format!("{}/order", self.clob_url);
+        let resp = self
+            .http
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&serde_json::json!({ "orderID": order_id }))
+            .send()
+            .await
+            .context("Failed to cancel Polymarket order")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Order cancel failed {}: {}", status, body);
+        }
+        Ok(())
+    }
+
+    /// Shares matched so far on an order and whether it is still live.
+    pub async fn get_order_status(&self, order_id: &str) -> Result<OrderStatus> {
+        let api_key = self.api_key.as_deref().unwrap_or_default();
+
+        let url = format!("{}/data/order/{}", self.clob_url, order_id);
+        let resp = self
+            .http
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .send()
+            .await
+            .context("Failed to fetch Polymarket order status")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Order status request failed {}: {}", status, body);
+        }
+
+        let raw: serde_json::Value = resp.json().await?;
+        Ok(parse_order_status(&raw))
+    }
+
     /// Close/sell an existing position.
     pub async fn close_position(
         &self,
@@ -426,6 +521,26 @@ fn parse_token_asset_ids(item: &serde_json::Value) -> (Option<String>, Option<St
     (None, None)
 }
 
+/// Fill state of a CLOB order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderStatus {
+    /// Shares matched so far
+    pub size_matched: f64,
+    /// Whether the order is still resting on the book
+    pub live: bool,
+}
+
+fn parse_order_status(raw: &serde_json::Value) -> OrderStatus {
+    let size_matched = raw["size_matched"]
+        .as_f64()
+        .or_else(|| raw["size_matched"].as_str().and_then(|s| s.parse().ok()))
+        .unwrap_or(0.0);
+    let live = raw["status"]
+        .as_str()
+        .is_some_and(|s| s.eq_ignore_ascii_case("live") || s.eq_ignore_ascii_case("open"));
+    OrderStatus { size_matched, live }
+}
+
 fn extract_price(raw: &serde_json::Value, outcome: &str) -> Result<f64> {
     let (yes_price, no_price) = parse_token_prices(raw);
     match outcome.to_lowercase().as_str() {
@@ -523,4 +638,14 @@ mod tests {
         });
         assert_eq!(parse_resolved_outcome(&raw).as_deref(), Some("YES"));
     }
+
+    #[test]
+    fn parse_order_status_reads_matched_size() {
+        let raw = serde_json::json!({ "status": "LIVE", "size_matched": "12.5" });
+        let status = super::parse_order_status(&raw);
+        assert_eq!(status.size_matched, 12.5);
+        assert!(status.live);
+        let done = super::parse_order_status(&serde_json::json!({ "status": "MATCHED" }));
+        assert!(!done.live);
+    }
 }
@@ -134,6 +134,18 @@ impl MarketCache {
         }
     }
 
+    /// Snapshot of all active cached markets.
+    pub async fn active_markets(&self) -> Vec<Market> {
+        self.inner
+            .read()
+            .await
+            .markets
+            .values()
+            .filter(|m| m.status == "active")
+            .cloned()
+            .collect()
+    }
+
     /// Number of cached markets.
     pub async fn len(&self) -> usize {
         self.inner.read().await.markets.len()
@@ -165,17 +177,15 @@ fn token_candidates<'a>(
 /// (e.g. "Willian FC" containing "will" matching "Will Jesus Christ return…").
 const STOP_WORDS: &[&str] = &[
     // Modal / auxiliary verbs
-    "will", "shall", "would", "could", "should", "might", "must", "have",
-    "been", "were", "was", "has", "had", "did", "does", "are", "not",
-    // Determiners / pronouns
-    "the", "this", "that", "these", "those", "which", "who", "whom",
-    "whose", "what", "all", "both", "each", "either", "neither", "any",
-    "some", "few", "more", "most", "other", "such", "than", "then",
-    "they", "them", "their", "your", "its", "our", "her", "him", "his",
+    "will", "shall", "would", "could", "should", "might", "must", "have", "been", "were", "was",
+    "has", "had", "did", "does", "are", "not", // Determiners / pronouns
+    "the", "this", "that", "these", "those", "which", "who", "whom", "whose", "what", "all",
+    "both", "each", "either", "neither", "any", "some", "few", "more", "most", "other", "such",
+    "than", "then", "they", "them", "their", "your", "its", "our", "her", "him", "his",
     // Conjunctions / prepositions
-    "and", "but", "for", "nor", "yet", "from", "into", "onto", "with",
-    "about", "after", "before", "during", "through", "within", "along",
-    "among", "upon", "since", "until", "while", "there", "here",
+    "and", "but", "for", "nor", "yet", "from", "into", "onto", "with", "about", "after", "before",
+    "during", "through", "within", "along", "among", "upon", "since", "until", "while", "there",
+    "here",
 ];
 
 /// Extract normalized lowercase tokens from a market question and event name.
@@ -322,7 +332,9 @@ mod tests {
 
         // "Willian" contains "will" as a substring. Without stop-word filtering
         // the indexed "will" token would match "willian" via substring search.
-        let results = cache.search("Willian FC", "Arsenal", "premier-league").await;
+        let results = cache
+            .search("Willian FC", "Arsenal", "premier-league")
+            .await;
         assert!(
             results.is_empty(),
             "Non-sports market matched via stop-word token 'will': {:?}",
@@ -104,6 +104,15 @@ impl PriceFeed {
         prices.get(asset_id).cloned()
     }
 
+    /// Seed a snapshot as if it had arrived over the socket.
+    #[cfg(test)]
+    pub async fn set_price(&self, asset_id: &str, snapshot: PriceSnapshot) {
+        self.prices
+            .write()
+            .await
+            .insert(asset_id.to_string(), snapshot);
+    }
+
     /// Get the mid-price for an asset, or `None` if unavailable.
     pub async fn get_mid_price(&self, asset_id: &str) -> Option<f64> {
         self.get_price(asset_id).await.map(|p| p.mid_price)